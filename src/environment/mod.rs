@@ -47,7 +47,7 @@ pub trait Environment: 'static + Clone + Debug + Default + Send + Sync {
     /// The specified type of node.
     const NODE_TYPE: NodeType;
     /// The version of the network protocol; it can be incremented in order to force users to update.
-    const MESSAGE_VERSION: u32 = 11;
+    const MESSAGE_VERSION: u32 = 13;
     /// If `true`, a mining node will craft public coinbase transactions.
     const COINBASE_IS_PUBLIC: bool = false;
 
@@ -80,8 +80,12 @@ pub trait Environment: 'static + Clone + Debug + Default + Send + Sync {
     const MAXIMUM_NUMBER_OF_PEERS: usize;
     /// The maximum number of connection failures permitted by an inbound connecting peer.
     const MAXIMUM_CONNECTION_FAILURES: u32 = 3;
-    /// The maximum number of candidate peers permitted to be stored in the node.
+    /// The maximum number of new, untried peer addresses permitted to be stored in the address book.
     const MAXIMUM_CANDIDATE_PEERS: usize = 10_000;
+    /// The maximum number of tried peer addresses permitted to be stored in the address book.
+    const MAXIMUM_TRIED_PEERS: usize = 1_000;
+    /// The maximum number of peer addresses shared with a peer in a single `PeerResponse`.
+    const MAXIMUM_PEER_RESPONSE_SIZE: usize = 100;
 
     /// The maximum size of a message that can be transmitted in the network.
     const MAXIMUM_MESSAGE_SIZE: usize = 128 * 1024 * 1024; // 128 MiB
@@ -89,8 +93,15 @@ pub trait Environment: 'static + Clone + Debug + Default + Send + Sync {
     const MAXIMUM_BLOCK_REQUEST: u32 = 250;
     /// The maximum number of blocks that a fork can be.
     const MAXIMUM_FORK_DEPTH: u32 = 4096;
-    /// The maximum number of failures tolerated before disconnecting from a peer.
-    const MAXIMUM_NUMBER_OF_FAILURES: usize = 1024;
+
+    /// The reputation score at which a peer is deprioritized.
+    const REPUTATION_DEPRIORITIZE_THRESHOLD: f64 = 25.0;
+    /// The reputation score at which a peer is disconnected.
+    const REPUTATION_DISCONNECT_THRESHOLD: f64 = 100.0;
+    /// The reputation score at which a peer is banned, until its score decays below this threshold.
+    const REPUTATION_BAN_THRESHOLD: f64 = 200.0;
+    /// The duration in seconds in which the reputation score of a peer decays by half.
+    const REPUTATION_HALF_LIFE_IN_SECS: u64 = 1800; // 30 minutes
 }
 
 #[derive(Clone, Debug, Default)]
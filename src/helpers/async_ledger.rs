@@ -0,0 +1,241 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::LedgerReader;
+use snarkos_storage::{BlockLocators, CacheMetrics, LedgerState};
+use snarkvm::dpc::prelude::*;
+
+use anyhow::{anyhow, Result};
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use tokio::sync::{oneshot, Semaphore};
+
+/// The maximum number of storage calls that may be queued or running in the storage pool at once.
+const MAXIMUM_PENDING_STORAGE_CALLS: usize = 256;
+/// The maximum duration of a storage call, including the time spent waiting for the storage pool.
+const STORAGE_CALL_TIMEOUT_IN_SECS: u64 = 30;
+
+///
+/// A snapshot of the storage calls served by an `AsyncLedgerReader`.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageMetrics {
+    /// The number of storage calls that have been requested.
+    pub calls: u64,
+    /// The number of storage calls that returned an error.
+    pub errors: u64,
+    /// The number of storage calls that did not complete within the timeout.
+    pub timeouts: u64,
+    /// The number of storage calls that are currently queued or running.
+    pub pending: u64,
+    /// The average duration of a completed storage call, in microseconds.
+    pub average_latency_in_micros: u64,
+    /// The number of hits and misses of the block cache of the ledger.
+    pub cache: CacheMetrics,
+}
+
+#[derive(Debug, Default)]
+struct StorageCounters {
+    calls: AtomicU64,
+    completed: AtomicU64,
+    in_flight: AtomicU64,
+    errors: AtomicU64,
+    timeouts: AtomicU64,
+    total_latency_in_micros: AtomicU64,
+}
+
+///
+/// An asynchronous reader for the ledger, which performs its storage calls on a dedicated thread pool.
+///
+/// Storage calls block on disk, so running them directly on a `tokio` worker thread stalls every other task
+/// scheduled on that thread. Instead, each call is sent to a bounded storage pool and awaited with a timeout.
+/// Inexpensive reads of the latest state, which are held in memory, remain available synchronously.
+///
+#[derive(Clone, Debug)]
+pub struct AsyncLedgerReader<N: Network> {
+    /// The ledger state.
+    ledger: LedgerReader<N>,
+    /// The read-only ledger state that follows the ledger state, if one is open.
+    reader: Option<LedgerReader<N>>,
+    /// The thread pool for storage calls.
+    pool: Arc<ThreadPool>,
+    /// The permits bounding the number of queued or running storage calls.
+    permits: Arc<Semaphore>,
+    /// The maximum duration of a storage call.
+    timeout: Duration,
+    /// The counters for the storage calls.
+    counters: Arc<StorageCounters>,
+}
+
+impl<N: Network> AsyncLedgerReader<N> {
+    /// Initializes a new instance of the asynchronous ledger reader.
+    pub fn new(ledger: LedgerReader<N>) -> Result<Self> {
+        let pool = ThreadPoolBuilder::new()
+            .thread_name(|index| format!("storage-{}", index))
+            .num_threads((num_cpus::get() / 4).max(2))
+            .build()?;
+
+        Ok(Self {
+            ledger,
+            reader: None,
+            pool: Arc::new(pool),
+            permits: Arc::new(Semaphore::new(MAXIMUM_PENDING_STORAGE_CALLS)),
+            timeout: Duration::from_secs(STORAGE_CALL_TIMEOUT_IN_SECS),
+            counters: Default::default(),
+        })
+    }
+
+    /// Returns an instance of the asynchronous ledger reader with the given timeout for storage calls.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns an instance of the asynchronous ledger reader that reports the lag of the given read-only ledger.
+    pub fn with_reader(mut self, reader: LedgerReader<N>) -> Self {
+        self.reader = Some(reader);
+        self
+    }
+
+    /// Returns the number of blocks the read-only ledger is behind the ledger, if a read-only ledger is set.
+    pub fn reader_lag(&self) -> Option<u32> {
+        self.reader
+            .as_ref()
+            .map(|reader| self.ledger.latest_block_height().saturating_sub(reader.latest_block_height()))
+    }
+
+    ///
+    /// Performs the given storage call on the storage pool, and returns its result.
+    ///
+    /// If the call does not complete within the timeout, an error is returned,
+    /// and the call is left to finish in the background while holding its permit.
+    ///
+    pub async fn run<T, F>(&self, call: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&LedgerState<N>) -> Result<T> + Send + 'static,
+    {
+        self.counters.calls.fetch_add(1, Ordering::SeqCst);
+        let start = Instant::now();
+
+        let ledger = self.ledger.clone();
+        let pool = self.pool.clone();
+        let permits = self.permits.clone();
+        let counters = self.counters.clone();
+        let result = tokio::time::timeout(self.timeout, async move {
+            // Wait for a slot in the storage pool.
+            let permit = permits.acquire_owned().await?;
+
+            let (router, handler) = oneshot::channel::<Result<T>>();
+            counters.in_flight.fetch_add(1, Ordering::SeqCst);
+            pool.spawn(move || {
+                let _ = router.send(call(&ledger));
+                // Release the slot once the call is finished, even if the caller has timed out.
+                counters.in_flight.fetch_sub(1, Ordering::SeqCst);
+                drop(permit);
+            });
+            handler.await?
+        })
+        .await;
+
+        match result {
+            Ok(result) => {
+                let latency = start.elapsed().as_micros() as u64;
+                self.counters.completed.fetch_add(1, Ordering::SeqCst);
+                self.counters.total_latency_in_micros.fetch_add(latency, Ordering::SeqCst);
+                if result.is_err() {
+                    self.counters.errors.fetch_add(1, Ordering::SeqCst);
+                }
+                result
+            }
+            Err(_) => {
+                self.counters.timeouts.fetch_add(1, Ordering::SeqCst);
+                Err(anyhow!("Storage call timed out after {} ms", self.timeout.as_millis()))
+            }
+        }
+    }
+
+    /// Returns the height of the oldest block that is retained in full.
+    pub fn pruned_block_height(&self) -> u32 {
+        self.ledger.pruned_block_height()
+    }
+
+    /// Returns the latest block.
+    pub fn latest_block(&self) -> Block<N> {
+        self.ledger.latest_block()
+    }
+
+    /// Returns the latest block height.
+    pub fn latest_block_height(&self) -> u32 {
+        self.ledger.latest_block_height()
+    }
+
+    /// Returns the latest block hash.
+    pub fn latest_block_hash(&self) -> N::BlockHash {
+        self.ledger.latest_block_hash()
+    }
+
+    /// Returns the latest cumulative weight.
+    pub fn latest_cumulative_weight(&self) -> u128 {
+        self.ledger.latest_cumulative_weight()
+    }
+
+    /// Returns the latest block header.
+    pub fn latest_block_header(&self) -> BlockHeader<N> {
+        self.ledger.latest_block_header()
+    }
+
+    /// Returns the transactions from the latest block.
+    pub fn latest_block_transactions(&self) -> Transactions<N> {
+        self.ledger.latest_block_transactions()
+    }
+
+    /// Returns the latest block locators.
+    pub fn latest_block_locators(&self) -> BlockLocators<N> {
+        self.ledger.latest_block_locators()
+    }
+
+    /// Returns the latest ledger root.
+    pub fn latest_ledger_root(&self) -> N::LedgerRoot {
+        self.ledger.latest_ledger_root()
+    }
+
+    /// Returns a snapshot of the storage calls served by this reader.
+    pub fn metrics(&self) -> StorageMetrics {
+        let calls = self.counters.calls.load(Ordering::SeqCst);
+        let completed = self.counters.completed.load(Ordering::SeqCst);
+        let total_latency_in_micros = self.counters.total_latency_in_micros.load(Ordering::SeqCst);
+
+        StorageMetrics {
+            calls,
+            errors: self.counters.errors.load(Ordering::SeqCst),
+            timeouts: self.counters.timeouts.load(Ordering::SeqCst),
+            pending: self.counters.in_flight.load(Ordering::SeqCst),
+            average_latency_in_micros: match completed {
+                0 => 0,
+                completed => total_latency_in_micros / completed,
+            },
+            cache: self.ledger.cache_metrics(),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::Result;
+use std::{fs, io::Write, path::Path};
+
+///
+/// Writes the given bytes to the file at the given path, replacing it atomically.
+///
+/// The bytes are written to a temporary file next to the given path, which is synced to disk
+/// before it is renamed over the given path, so that an interrupted write never leaves a partial file.
+/// On Unix, the file is only readable and writable by its owner.
+///
+pub fn write_atomically<P: AsRef<Path>>(path: P, bytes: &[u8]) -> Result<()> {
+    let path = path.as_ref();
+
+    let temporary_path = path.with_extension("tmp");
+    if temporary_path.exists() {
+        fs::remove_file(&temporary_path)?;
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut temporary_file = options.open(&temporary_path)?;
+    temporary_file.write_all(bytes)?;
+    temporary_file.sync_all()?;
+    fs::rename(&temporary_path, path)?;
+
+    // Sync the directory, so that the rename itself survives a crash.
+    #[cfg(unix)]
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::File::open(parent)?.sync_all()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_atomically() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("file");
+
+        // Ensure the file is written, and replaced on the next write, without leaving the temporary file.
+        write_atomically(&path, b"first").unwrap();
+        write_atomically(&path, b"second").unwrap();
+        assert_eq!(b"second".to_vec(), fs::read(&path).unwrap());
+        assert!(!path.with_extension("tmp").exists());
+    }
+}
@@ -36,6 +36,9 @@ pub fn find_maximal_peer<N: Network, E: Environment>(
     //     peers_contains_sync_node |= sync_nodes.contains(ip);
     // }
 
+    // Retrieve the latest block height of this ledger, as peers must be able to serve the blocks after it.
+    let latest_block_height = *maximum_block_height;
+
     let mut maximal_peer = None;
 
     for (peer_ip, peer_state) in peers_state.iter() {
@@ -43,7 +46,12 @@ pub fn find_maximal_peer<N: Network, E: Environment>(
         if !peers_contains_sync_node || sync_nodes.contains(peer_ip) {
             // Update the maximal peer state if the peer is ahead and the peer knows if you are a fork or not.
             // This accounts for (Case 1 and Case 2(a))
-            if let Some((_, _, is_on_fork, block_height, block_locators)) = peer_state {
+            if let Some((_, _, is_on_fork, block_height, pruned_block_height, block_locators)) = peer_state {
+                // Skip peers that have pruned the blocks this ledger is missing.
+                if *pruned_block_height > latest_block_height.saturating_add(1) {
+                    continue;
+                }
+
                 // Retrieve the cumulative weight, defaulting to the block height if it does not exist.
                 let cumulative_weight = match block_locators.get_cumulative_weight(*block_height) {
                     Some(cumulative_weight) => cumulative_weight,
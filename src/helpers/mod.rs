@@ -14,6 +14,12 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
+pub mod async_ledger;
+pub use async_ledger::*;
+
+pub mod atomic_file;
+pub use atomic_file::*;
+
 pub mod block_requests;
 pub use block_requests::*;
 
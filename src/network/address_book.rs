@@ -0,0 +1,583 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{helpers::write_atomically, Environment};
+
+use anyhow::Result;
+use chrono::Utc;
+use rand::{
+    distributions::{Distribution, WeightedIndex},
+    prelude::IteratorRandom,
+    rngs::OsRng,
+    Rng,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    marker::PhantomData,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    path::{Path, PathBuf},
+};
+
+/// The probability of selecting a candidate peer from the tried addresses, when there are also new addresses.
+const TRIED_SELECTION_PROBABILITY: f64 = 0.75;
+/// The minimum duration in seconds in between writes of the address book to disk.
+const SAVE_INTERVAL_IN_SECS: i64 = 60;
+/// The maximum number of addresses in the new table that were shared by peers in the same subnet.
+const MAXIMUM_NEW_ADDRESSES_PER_SOURCE: usize = 512;
+
+///
+/// The connection history of a known peer address.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressInfo {
+    /// The timestamp of when the address was first seen.
+    pub first_seen: i64,
+    /// The timestamp of the last outbound connection attempt to the address, if any.
+    pub last_attempt: Option<i64>,
+    /// The timestamp of the last successful outbound connection to the address, if any.
+    pub last_success: Option<i64>,
+    /// The number of consecutive failed connection attempts to the address.
+    pub failures: u32,
+    /// The subnet of the peer that shared the address, if it was learned from a peer response.
+    #[serde(default)]
+    pub source: Option<IpAddr>,
+}
+
+impl AddressInfo {
+    ///
+    /// Initializes the connection history of an address that was first seen at the given timestamp,
+    /// and was shared by a peer in the given subnet, if any.
+    ///
+    fn new(now: i64, source: Option<IpAddr>) -> Self {
+        Self {
+            first_seen: now,
+            last_attempt: None,
+            last_success: None,
+            failures: 0,
+            source,
+        }
+    }
+
+    ///
+    /// Returns `true` if the address should be forgotten, which is the case once it has failed
+    /// `MAXIMUM_CONNECTION_FAILURES` times in a row, without a recent successful connection.
+    ///
+    fn is_terrible<E: Environment>(&self, now: i64) -> bool {
+        let has_recent_success = match self.last_success {
+            Some(last_success) => now - last_success < E::FAILURE_EXPIRY_TIME_IN_SECS as i64,
+            None => false,
+        };
+        self.failures >= E::MAXIMUM_CONNECTION_FAILURES && !has_recent_success
+    }
+
+    /// Returns the relative chance of selecting the address for an outbound connection.
+    fn chance<E: Environment>(&self, now: i64) -> f64 {
+        // Deprioritize the address for each consecutive failure.
+        let mut chance = 0.66f64.powi(self.failures.min(8) as i32);
+        // Deprioritize the address if a connection was recently attempted.
+        if let Some(last_attempt) = self.last_attempt {
+            if now - last_attempt < E::RADIO_SILENCE_IN_SECS as i64 {
+                chance *= 0.01;
+            }
+        }
+        chance
+    }
+}
+
+/// The on-disk format of the address book.
+#[derive(Default, Serialize, Deserialize)]
+struct AddressBookFile {
+    tried: Vec<(SocketAddr, AddressInfo)>,
+    new: Vec<(SocketAddr, AddressInfo)>,
+}
+
+///
+/// The address book of known peers, which is persisted to disk across sessions.
+///
+/// Addresses are kept in two tables: the "tried" table holds addresses this node has successfully
+/// connected to, while the "new" table holds addresses that were learned from peers or inbound
+/// connections, and addresses that were demoted from the tried table after repeated failures.
+///
+pub struct AddressBook<E: Environment> {
+    /// The path of the address book on disk, if it is persisted.
+    path: Option<PathBuf>,
+    /// The map of addresses this node has successfully connected to.
+    tried: HashMap<SocketAddr, AddressInfo>,
+    /// The map of addresses this node has not successfully connected to.
+    new: HashMap<SocketAddr, AddressInfo>,
+    /// The number of addresses in the new table that were shared by peers in each subnet.
+    new_by_source: HashMap<IpAddr, usize>,
+    /// The flag indicating if the address book has been modified since it was last saved.
+    is_modified: bool,
+    /// The timestamp of when the address book was last saved.
+    last_saved: i64,
+    _phantom: PhantomData<E>,
+}
+
+impl<E: Environment> AddressBook<E> {
+    /// Initializes a new address book, which is not persisted.
+    pub fn new() -> Self {
+        Self {
+            path: None,
+            tried: Default::default(),
+            new: Default::default(),
+            new_by_source: Default::default(),
+            is_modified: false,
+            last_saved: Utc::now().timestamp(),
+            _phantom: PhantomData,
+        }
+    }
+
+    ///
+    /// Opens the address book at the given path.
+    /// If the address book does not exist, a new one is created at the given path.
+    ///
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let mut address_book = Self::new();
+        address_book.path = Some(path.to_path_buf());
+
+        match path.exists() {
+            true => {
+                let file: AddressBookFile = serde_json::from_slice(&fs::read(path)?)?;
+                address_book.tried = file.tried.into_iter().collect();
+                for (ip, info) in file.new {
+                    address_book.insert_new(ip, info);
+                }
+            }
+            false => {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                address_book.is_modified = true;
+                address_book.save()?;
+            }
+        }
+        Ok(address_book)
+    }
+
+    ///
+    /// Writes the address book to disk, if it is persisted and has been modified since it was last saved.
+    ///
+    pub fn save(&mut self) -> Result<()> {
+        if let (Some(path), true) = (&self.path, self.is_modified) {
+            let file = AddressBookFile {
+                tried: self.tried.iter().map(|(ip, info)| (*ip, *info)).collect(),
+                new: self.new.iter().map(|(ip, info)| (*ip, *info)).collect(),
+            };
+            write_atomically(path, &serde_json::to_vec(&file)?)?;
+
+            self.is_modified = false;
+            self.last_saved = Utc::now().timestamp();
+        }
+        Ok(())
+    }
+
+    ///
+    /// Writes the address book to disk, if `SAVE_INTERVAL_IN_SECS` has elapsed since it was last saved.
+    ///
+    pub fn save_periodically(&mut self) -> Result<()> {
+        match Utc::now().timestamp() - self.last_saved >= SAVE_INTERVAL_IN_SECS {
+            true => self.save(),
+            false => Ok(()),
+        }
+    }
+
+    /// Returns `true` if the given address is in the address book.
+    pub fn contains(&self, ip: &SocketAddr) -> bool {
+        self.tried.contains_key(ip) || self.new.contains_key(ip)
+    }
+
+    /// Returns `true` if the given address is in the tried table.
+    pub fn is_tried(&self, ip: &SocketAddr) -> bool {
+        self.tried.contains_key(ip)
+    }
+
+    /// Returns the connection history of the given address, if it is in the address book.
+    pub fn get(&self, ip: &SocketAddr) -> Option<&AddressInfo> {
+        self.tried.get(ip).or_else(|| self.new.get(ip))
+    }
+
+    /// Returns an iterator over all addresses in the address book.
+    pub fn addresses(&self) -> impl Iterator<Item = &SocketAddr> {
+        self.tried.keys().chain(self.new.keys())
+    }
+
+    /// Returns the number of addresses in the address book.
+    pub fn len(&self) -> usize {
+        self.tried.len() + self.new.len()
+    }
+
+    /// Returns `true` if the address book is empty.
+    pub fn is_empty(&self) -> bool {
+        self.tried.is_empty() && self.new.is_empty()
+    }
+
+    /// Returns the number of addresses in the tried table.
+    pub fn number_of_tried(&self) -> usize {
+        self.tried.len()
+    }
+
+    /// Returns the number of addresses in the new table.
+    pub fn number_of_new(&self) -> usize {
+        self.new.len()
+    }
+
+    ///
+    /// Adds the given addresses to the new table, skipping addresses that are already known.
+    ///
+    /// Addresses shared by a peer at the given source IP are only added while the new table has space,
+    /// and while the subnet of the source has shared fewer than `MAXIMUM_NEW_ADDRESSES_PER_SOURCE` of them,
+    /// so that a single peer cannot flood the address book. Addresses without a source, such as the
+    /// bootstrap nodes and connected peers, evict a terrible or the oldest untried address once the
+    /// new table is full, so that the address book keeps learning about the network over time.
+    ///
+    pub fn add_addresses(&mut self, ips: &[SocketAddr], source: Option<IpAddr>) {
+        let now = Utc::now().timestamp();
+        let source = source.map(source_subnet);
+        for ip in ips {
+            if self.contains(ip) {
+                continue;
+            }
+            match source {
+                Some(source) => {
+                    let number_of_addresses = self.new_by_source.get(&source).copied().unwrap_or(0);
+                    if self.new.len() >= E::MAXIMUM_CANDIDATE_PEERS || number_of_addresses >= MAXIMUM_NEW_ADDRESSES_PER_SOURCE {
+                        break;
+                    }
+                }
+                None => {
+                    if self.new.len() >= E::MAXIMUM_CANDIDATE_PEERS && !self.evict_new(now) {
+                        break;
+                    }
+                }
+            }
+            self.insert_new(*ip, AddressInfo::new(now, source));
+            self.is_modified = true;
+        }
+    }
+
+    /// Records an outbound connection attempt to the given address.
+    pub fn record_attempt(&mut self, ip: SocketAddr) {
+        let now = Utc::now().timestamp();
+        if !self.contains(&ip) {
+            self.add_addresses(&[ip], None);
+        }
+        if let Some(info) = self.tried.get_mut(&ip).or_else(|| self.new.get_mut(&ip)) {
+            info.last_attempt = Some(now);
+            self.is_modified = true;
+        }
+    }
+
+    /// Records a successful outbound connection to the given address, moving it to the tried table.
+    pub fn record_success(&mut self, ip: SocketAddr) {
+        let now = Utc::now().timestamp();
+        let mut info = match self.tried.remove(&ip).or_else(|| self.remove_new(&ip)) {
+            Some(info) => info,
+            None => AddressInfo::new(now, None),
+        };
+        info.last_attempt = Some(now);
+        info.last_success = Some(now);
+        info.failures = 0;
+        info.source = None;
+
+        // Ensure the tried table does not surpass its capacity, by demoting the least recently successful address.
+        if self.tried.len() >= E::MAXIMUM_TRIED_PEERS {
+            let oldest = self.tried.iter().min_by_key(|(_, info)| info.last_success).map(|(ip, _)| *ip);
+            if let Some(oldest) = oldest {
+                if let Some(oldest_info) = self.tried.remove(&oldest) {
+                    self.insert_new(oldest, oldest_info);
+                }
+            }
+        }
+
+        self.tried.insert(ip, info);
+        self.is_modified = true;
+    }
+
+    ///
+    /// Records a failed outbound connection to the given address.
+    ///
+    /// A tried address that has failed `MAXIMUM_CONNECTION_FAILURES` times in a row is demoted to the
+    /// new table, and a new address is forgotten once it is terrible.
+    ///
+    pub fn record_failure(&mut self, ip: SocketAddr) {
+        let now = Utc::now().timestamp();
+        if let Some(info) = self.tried.get_mut(&ip) {
+            info.failures += 1;
+            if info.failures >= E::MAXIMUM_CONNECTION_FAILURES {
+                if let Some(info) = self.tried.remove(&ip) {
+                    self.insert_new(ip, info);
+                }
+            }
+            self.is_modified = true;
+        } else if let Some(info) = self.new.get_mut(&ip) {
+            info.failures += 1;
+            if info.is_terrible::<E>(now) {
+                self.remove_new(&ip);
+            }
+            self.is_modified = true;
+        }
+    }
+
+    ///
+    /// Returns up to `number_of_candidates` addresses to connect to, skipping addresses that are excluded.
+    ///
+    /// Each candidate is chosen from the tried table with probability `TRIED_SELECTION_PROBABILITY`,
+    /// and otherwise from the new table. Within a table, addresses with fewer failures and no recent
+    /// connection attempt are more likely to be chosen.
+    ///
+    pub fn select_candidates<F: Fn(&SocketAddr) -> bool>(&self, number_of_candidates: usize, is_excluded: F) -> Vec<SocketAddr> {
+        let now = Utc::now().timestamp();
+        let weigh = |table: &HashMap<SocketAddr, AddressInfo>| -> Vec<(SocketAddr, f64)> {
+            table
+                .iter()
+                .filter(|(ip, _)| !is_excluded(ip))
+                .map(|(ip, info)| (*ip, info.chance::<E>(now)))
+                .collect()
+        };
+        let mut tried = weigh(&self.tried);
+        let mut new = weigh(&self.new);
+
+        let mut rng = OsRng::default();
+        let mut candidates = Vec::with_capacity(number_of_candidates);
+        while candidates.len() < number_of_candidates && !(tried.is_empty() && new.is_empty()) {
+            let table = match (tried.is_empty(), new.is_empty()) {
+                (false, false) => match rng.gen_bool(TRIED_SELECTION_PROBABILITY) {
+                    true => &mut tried,
+                    false => &mut new,
+                },
+                (false, true) => &mut tried,
+                (true, _) => &mut new,
+            };
+            let index = match WeightedIndex::new(table.iter().map(|(_, chance)| *chance)) {
+                Ok(distribution) => distribution.sample(&mut rng),
+                Err(_) => rng.gen_range(0..table.len()),
+            };
+            candidates.push(table.swap_remove(index).0);
+        }
+        candidates
+    }
+
+    ///
+    /// Returns up to `number_of_addresses` random addresses to share with a peer, skipping addresses that
+    /// are excluded. Only tried addresses and new addresses without failures are shared.
+    ///
+    pub fn sample_addresses<F: Fn(&SocketAddr) -> bool>(&self, number_of_addresses: usize, is_excluded: F) -> Vec<SocketAddr> {
+        self.tried
+            .keys()
+            .chain(self.new.iter().filter(|(_, info)| info.failures == 0).map(|(ip, _)| ip))
+            .filter(|ip| !is_excluded(ip))
+            .copied()
+            .choose_multiple(&mut OsRng::default(), number_of_addresses)
+    }
+
+    /// Removes all addresses from the address book.
+    pub fn clear(&mut self) {
+        self.tried.clear();
+        self.new.clear();
+        self.new_by_source.clear();
+        self.is_modified = true;
+    }
+
+    /// Inserts the given address into the new table, counting it towards the subnet of its source.
+    fn insert_new(&mut self, ip: SocketAddr, info: AddressInfo) {
+        if let Some(source) = info.source {
+            *self.new_by_source.entry(source).or_default() += 1;
+        }
+        if let Some(previous_info) = self.new.insert(ip, info) {
+            self.forget_source(&previous_info);
+        }
+    }
+
+    /// Removes the given address from the new table, returning its connection history if it was present.
+    fn remove_new(&mut self, ip: &SocketAddr) -> Option<AddressInfo> {
+        let info = self.new.remove(ip)?;
+        self.forget_source(&info);
+        Some(info)
+    }
+
+    /// Stops counting the given address towards the subnet of its source.
+    fn forget_source(&mut self, info: &AddressInfo) {
+        if let Some(source) = info.source {
+            if let Some(number_of_addresses) = self.new_by_source.get_mut(&source) {
+                *number_of_addresses = number_of_addresses.saturating_sub(1);
+                if *number_of_addresses == 0 {
+                    self.new_by_source.remove(&source);
+                }
+            }
+        }
+    }
+
+    ///
+    /// Evicts an address from the new table, preferring a terrible address, and otherwise the oldest
+    /// address that never connected successfully. Returns `true` if an address was evicted.
+    ///
+    fn evict_new(&mut self, now: i64) -> bool {
+        let evicted = match self.new.iter().find(|(_, info)| info.is_terrible::<E>(now)) {
+            Some((ip, _)) => Some(*ip),
+            None => self
+                .new
+                .iter()
+                .filter(|(_, info)| info.last_success.is_none())
+                .min_by_key(|(_, info)| info.first_seen)
+                .map(|(ip, _)| *ip),
+        };
+        match evicted {
+            Some(ip) => self.remove_new(&ip).is_some(),
+            None => false,
+        }
+    }
+}
+
+///
+/// Returns the subnet that the given source IP is counted towards, which is its /16 subnet for IPv4,
+/// including IPv4-mapped IPv6 addresses, and its /32 subnet for IPv6.
+///
+fn source_subnet(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, _, _] = ip.octets();
+            IpAddr::V4(Ipv4Addr::new(a, b, 0, 0))
+        }
+        IpAddr::V6(ip) => match ip.segments() {
+            [0, 0, 0, 0, 0, 0xffff, ..] => source_subnet(IpAddr::V4(ip.to_ipv4().unwrap_or(Ipv4Addr::UNSPECIFIED))),
+            [a, b, ..] => IpAddr::V6(Ipv6Addr::new(a, b, 0, 0, 0, 0, 0, 0)),
+        },
+    }
+}
+
+impl<E: Environment> Default for AddressBook<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Client;
+    use snarkvm::dpc::testnet2::Testnet2;
+
+    type TestAddressBook = AddressBook<Client<Testnet2>>;
+
+    fn address(port: u16) -> SocketAddr {
+        SocketAddr::from(([127, 0, 0, 1], port))
+    }
+
+    #[test]
+    fn test_open() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("peers");
+
+        // Ensure the address book is created on the first open.
+        let mut address_book = TestAddressBook::open(&path).unwrap();
+        assert!(path.exists());
+        assert!(address_book.is_empty());
+
+        address_book.add_addresses(&[address(4130), address(4131)], None);
+        address_book.record_success(address(4131));
+        address_book.save().unwrap();
+
+        // Ensure the tables are persisted for the next open.
+        let reopened = TestAddressBook::open(&path).unwrap();
+        assert_eq!(reopened.number_of_new(), 1);
+        assert_eq!(reopened.number_of_tried(), 1);
+        assert!(reopened.is_tried(&address(4131)));
+        assert_eq!(reopened.get(&address(4131)), address_book.get(&address(4131)));
+    }
+
+    #[test]
+    fn test_record_success_and_failure() {
+        let mut address_book = TestAddressBook::new();
+        let ip = address(4130);
+
+        // Ensure a successful connection moves the address to the tried table.
+        address_book.add_addresses(&[ip], None);
+        address_book.record_attempt(ip);
+        address_book.record_success(ip);
+        assert!(address_book.is_tried(&ip));
+        assert_eq!(address_book.number_of_new(), 0);
+
+        // Ensure repeated failures demote the address to the new table, where it is kept while its success is recent.
+        for _ in 0..Client::<Testnet2>::MAXIMUM_CONNECTION_FAILURES {
+            address_book.record_failure(ip);
+        }
+        assert!(!address_book.is_tried(&ip));
+        assert!(address_book.contains(&ip));
+
+        // Ensure an address that never connected is forgotten after repeated failures.
+        let other_ip = address(4131);
+        address_book.add_addresses(&[other_ip], None);
+        for _ in 0..Client::<Testnet2>::MAXIMUM_CONNECTION_FAILURES {
+            address_book.record_failure(other_ip);
+        }
+        assert!(!address_book.contains(&other_ip));
+    }
+
+    #[test]
+    fn test_add_addresses_from_source() {
+        let mut address_book = TestAddressBook::new();
+        let source = IpAddr::from([10, 0, 0, 1]);
+        let other_source = IpAddr::from([10, 0, 0, 2]);
+
+        // Ensure a subnet may only share up to its limit of addresses, across peer responses.
+        let ips: Vec<SocketAddr> = (0..MAXIMUM_NEW_ADDRESSES_PER_SOURCE as u16 + 1)
+            .map(|port| address(port + 1))
+            .collect();
+        let (first, second) = ips.split_at(ips.len() / 2);
+        address_book.add_addresses(first, Some(source));
+        address_book.add_addresses(second, Some(other_source));
+        assert_eq!(address_book.number_of_new(), MAXIMUM_NEW_ADDRESSES_PER_SOURCE);
+        assert!(!address_book.contains(ips.last().unwrap()));
+
+        // Ensure the addresses of a subnet no longer count towards its limit once they leave the new table.
+        address_book.record_success(ips[0]);
+        address_book.add_addresses(&ips[ips.len() - 1..], Some(source));
+        assert!(address_book.contains(ips.last().unwrap()));
+    }
+
+    #[test]
+    fn test_select_candidates() {
+        let mut address_book = TestAddressBook::new();
+        let ips: Vec<SocketAddr> = (4130..4140).map(address).collect();
+        address_book.add_addresses(&ips, None);
+        address_book.record_success(ips[0]);
+
+        // Ensure excluded addresses are never selected, and each candidate is selected once.
+        let candidates = address_book.select_candidates(ips.len(), |ip| *ip == ips[1]);
+        assert_eq!(candidates.len(), ips.len() - 1);
+        assert!(!candidates.contains(&ips[1]));
+        let mut deduplicated = candidates.clone();
+        deduplicated.sort();
+        deduplicated.dedup();
+        assert_eq!(deduplicated.len(), candidates.len());
+
+        // Ensure the tried address is selected more often than any single new address.
+        let number_of_tried_selections = (0..100)
+            .filter(|_| address_book.select_candidates(1, |_| false) == vec![ips[0]])
+            .count();
+        assert!(number_of_tried_selections > 50);
+
+        // Ensure addresses with failures are not shared with peers.
+        address_book.record_failure(ips[2]);
+        let shared = address_book.sample_addresses(ips.len(), |_| false);
+        assert!(shared.contains(&ips[0]));
+        assert!(!shared.contains(&ips[2]));
+    }
+}
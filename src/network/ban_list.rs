@@ -0,0 +1,433 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::helpers::write_atomically;
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    fs,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+///
+/// A range of IP addresses in CIDR notation, such as `10.0.0.0/8`.
+/// A single IP address is a subnet with a full-length prefix.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Subnet {
+    /// The network address, with the bits beyond the prefix cleared.
+    ip: IpAddr,
+    /// The number of leading bits of the network address.
+    prefix_length: u8,
+}
+
+impl Subnet {
+    ///
+    /// Initializes a new subnet from the given IP and prefix length, clearing the bits of the IP beyond the prefix.
+    /// An IPv4-mapped IPv6 subnet (`::ffff:a.b.c.d/n`, where `n >= 96`) is stored as its IPv4 subnet (`a.b.c.d/(n - 96)`).
+    ///
+    pub fn new(ip: IpAddr, prefix_length: u8) -> Result<Self> {
+        let maximum_prefix_length = match ip {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        if prefix_length > maximum_prefix_length {
+            return Err(anyhow!("The prefix length of {} must be at most {}", ip, maximum_prefix_length));
+        }
+        let (ip, prefix_length) = match Self::to_canonical(ip) {
+            canonical_ip @ IpAddr::V4(_) if ip.is_ipv6() && prefix_length >= 96 => (canonical_ip, prefix_length - 96),
+            _ => (ip, prefix_length),
+        };
+        Ok(Self {
+            ip: Self::mask(ip, prefix_length),
+            prefix_length,
+        })
+    }
+
+    /// Returns `true` if the given IP is within this subnet.
+    /// An IPv4-mapped IPv6 address (`::ffff:a.b.c.d`) is treated as its IPv4 address.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let ip = Self::to_canonical(ip);
+        match (self.ip, ip) {
+            (IpAddr::V4(_), IpAddr::V4(_)) | (IpAddr::V6(_), IpAddr::V6(_)) => Self::mask(ip, self.prefix_length) == self.ip,
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if this subnet is a single IP address.
+    fn is_single_ip(&self) -> bool {
+        match self.ip {
+            IpAddr::V4(_) => self.prefix_length == 32,
+            IpAddr::V6(_) => self.prefix_length == 128,
+        }
+    }
+
+    /// Returns the IPv4 address of the given IP, if it is an IPv4-mapped IPv6 address, and the given IP otherwise.
+    fn to_canonical(ip: IpAddr) -> IpAddr {
+        match ip {
+            IpAddr::V6(ipv6) => match ipv6.segments() {
+                [0, 0, 0, 0, 0, 0xffff, _, _] => ipv6.to_ipv4().map(IpAddr::V4).unwrap_or(ip),
+                _ => ip,
+            },
+            IpAddr::V4(_) => ip,
+        }
+    }
+
+    /// Returns the given IP with the bits beyond the given prefix length cleared.
+    fn mask(ip: IpAddr, prefix_length: u8) -> IpAddr {
+        match ip {
+            IpAddr::V4(ip) => {
+                let mask = u32::MAX.checked_shl(32 - prefix_length as u32).unwrap_or(0);
+                IpAddr::V4(Ipv4Addr::from(u32::from(ip) & mask))
+            }
+            IpAddr::V6(ip) => {
+                let mask = u128::MAX.checked_shl(128 - prefix_length as u32).unwrap_or(0);
+                IpAddr::V6(Ipv6Addr::from(u128::from(ip) & mask))
+            }
+        }
+    }
+}
+
+impl From<IpAddr> for Subnet {
+    fn from(ip: IpAddr) -> Self {
+        // An IPv4-mapped IPv6 address is stored as its IPv4 address.
+        let ip = Self::to_canonical(ip);
+        match ip {
+            IpAddr::V4(_) => Self { ip, prefix_length: 32 },
+            IpAddr::V6(_) => Self { ip, prefix_length: 128 },
+        }
+    }
+}
+
+impl FromStr for Subnet {
+    type Err = anyhow::Error;
+
+    fn from_str(subnet: &str) -> Result<Self, Self::Err> {
+        match subnet.split_once('/') {
+            Some((ip, prefix_length)) => Self::new(
+                ip.parse().map_err(|_| anyhow!("Invalid IP address in '{}'", subnet))?,
+                prefix_length
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid prefix length in '{}'", subnet))?,
+            ),
+            None => Ok(Self::from(
+                IpAddr::from_str(subnet).map_err(|_| anyhow!("Invalid IP address '{}'", subnet))?,
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Subnet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.is_single_ip() {
+            true => write!(f, "{}", self.ip),
+            false => write!(f, "{}/{}", self.ip, self.prefix_length),
+        }
+    }
+}
+
+impl Serialize for Subnet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Subnet {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::from_str(&String::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
+///
+/// A ban of an IP address or subnet, with an optional expiry and reason.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Ban {
+    /// The banned IP address or subnet.
+    pub subnet: Subnet,
+    /// The UNIX timestamp at which the ban is lifted, or `None` if the ban is permanent.
+    pub expiry: Option<i64>,
+    /// The reason for the ban, if one was given.
+    pub reason: Option<String>,
+}
+
+impl Ban {
+    /// Returns `true` if the ban has been lifted.
+    pub fn is_expired(&self) -> bool {
+        match self.expiry {
+            Some(expiry) => expiry <= Utc::now().timestamp(),
+            None => false,
+        }
+    }
+}
+
+///
+/// A list of banned IP addresses and subnets, which are refused on inbound and outbound connections.
+///
+/// The bans added at runtime are persisted to disk, while the bans from the config file
+/// are reloaded on each start, so that the config file remains the source of truth for them.
+///
+#[derive(Debug, Default)]
+pub struct BanList {
+    /// The path of the ban list on disk, if it is persisted.
+    path: Option<PathBuf>,
+    /// The map of subnets to the bans added at runtime.
+    bans: BTreeMap<Subnet, Ban>,
+    /// The map of subnets to the bans from the config file.
+    configured_bans: BTreeMap<Subnet, Ban>,
+}
+
+impl BanList {
+    ///
+    /// Initializes a new, empty ban list that is not persisted.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Opens the ban list at the given path, creating it if it does not exist.
+    ///
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let mut ban_list = Self::new();
+        ban_list.path = Some(path.to_path_buf());
+
+        match path.exists() {
+            true => {
+                let bans: Vec<Ban> = serde_json::from_slice(&fs::read(path)?)?;
+                ban_list.bans = bans.into_iter().map(|ban| (ban.subnet, ban)).collect();
+            }
+            false => {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                ban_list.save()?;
+            }
+        }
+        Ok(ban_list)
+    }
+
+    ///
+    /// Loads the bans from the JSON config file at the given path.
+    ///
+    pub fn load_config<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let bans: Vec<Ban> = serde_json::from_slice(
+            &fs::read(path).map_err(|error| anyhow!("Failed to read the ban config '{}': {}", path.display(), error))?,
+        )?;
+        self.configured_bans = bans.into_iter().map(|ban| (ban.subnet, ban)).collect();
+        Ok(())
+    }
+
+    ///
+    /// Writes the bans added at runtime to disk, if the ban list is persisted.
+    ///
+    pub fn save(&self) -> Result<()> {
+        if let Some(path) = &self.path {
+            let bans: Vec<&Ban> = self.bans.values().collect();
+            write_atomically(path, &serde_json::to_vec(&bans)?)?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Returns `true` if the given IP is within an active ban.
+    ///
+    pub fn is_banned(&self, ip: IpAddr) -> bool {
+        self.bans().any(|ban| ban.subnet.contains(ip))
+    }
+
+    ///
+    /// Returns the active bans, including the bans from the config file.
+    ///
+    pub fn bans(&self) -> impl Iterator<Item = &Ban> {
+        self.configured_bans
+            .values()
+            .chain(self.bans.values())
+            .filter(|ban| !ban.is_expired())
+    }
+
+    ///
+    /// Returns the number of active bans.
+    ///
+    pub fn len(&self) -> usize {
+        self.bans().count()
+    }
+
+    ///
+    /// Returns `true` if there are no active bans.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.bans().next().is_none()
+    }
+
+    ///
+    /// Adds the given ban, replacing any existing ban of the same subnet, and persists the ban list.
+    ///
+    pub fn ban(&mut self, ban: Ban) -> Result<()> {
+        if ban.is_expired() {
+            return Err(anyhow!("The ban of {} has already expired", ban.subnet));
+        }
+        if self.configured_bans.contains_key(&ban.subnet) {
+            return Err(anyhow!("{} is already banned in the ban config", ban.subnet));
+        }
+        self.bans.insert(ban.subnet, ban);
+        self.save()
+    }
+
+    ///
+    /// Removes the ban of the given subnet, and persists the ban list.
+    /// Returns `true` if the subnet was banned.
+    ///
+    pub fn unban(&mut self, subnet: &Subnet) -> Result<bool> {
+        if self.configured_bans.contains_key(subnet) {
+            return Err(anyhow!("{} is banned in the ban config, and must be removed from it", subnet));
+        }
+        match self.bans.remove(subnet) {
+            Some(ban) => {
+                self.save()?;
+                Ok(!ban.is_expired())
+            }
+            None => Ok(false),
+        }
+    }
+
+    ///
+    /// Removes the expired bans, and persists the ban list if any were removed.
+    ///
+    pub fn remove_expired(&mut self) -> Result<()> {
+        let number_of_bans = self.bans.len();
+        self.bans.retain(|_, ban| !ban.is_expired());
+        self.configured_bans.retain(|_, ban| !ban.is_expired());
+        match self.bans.len() != number_of_bans {
+            true => self.save(),
+            false => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subnet() {
+        // Ensure a single IP address only contains itself.
+        let subnet = Subnet::from_str("192.168.1.10").unwrap();
+        assert_eq!(subnet.to_string(), "192.168.1.10");
+        assert!(subnet.contains("192.168.1.10".parse().unwrap()));
+        assert!(!subnet.contains("192.168.1.11".parse().unwrap()));
+
+        // Ensure a CIDR range is normalized, and contains the addresses within its prefix.
+        let subnet = Subnet::from_str("10.1.2.3/8").unwrap();
+        assert_eq!(subnet.to_string(), "10.0.0.0/8");
+        assert!(subnet.contains("10.255.0.1".parse().unwrap()));
+        assert!(!subnet.contains("11.0.0.1".parse().unwrap()));
+        assert!(!subnet.contains("::a00:1".parse().unwrap()));
+
+        // Ensure an IPv4-mapped IPv6 address is matched as its IPv4 address.
+        assert!(subnet.contains("::ffff:10.255.0.1".parse().unwrap()));
+        assert!(!subnet.contains("::ffff:11.0.0.1".parse().unwrap()));
+        let subnet = Subnet::from_str("192.168.1.10").unwrap();
+        assert!(subnet.contains("::ffff:192.168.1.10".parse().unwrap()));
+
+        let subnet = Subnet::from_str("2001:db8::/32").unwrap();
+        assert!(subnet.contains("2001:db8:ffff::1".parse().unwrap()));
+        assert!(!subnet.contains("2001:db9::1".parse().unwrap()));
+
+        // Ensure a zero-length prefix contains every address of its family.
+        assert!(Subnet::from_str("0.0.0.0/0").unwrap().contains("1.2.3.4".parse().unwrap()));
+
+        // Ensure malformed subnets are rejected.
+        assert!(Subnet::from_str("10.0.0.0/33").is_err());
+        assert!(Subnet::from_str("10.0.0/8").is_err());
+        assert!(Subnet::from_str("10.0.0.0/").is_err());
+    }
+
+    #[test]
+    fn test_mapped_subnet() {
+        // Ensure an IPv4-mapped IPv6 subnet is stored as its IPv4 subnet.
+        let subnet = Subnet::from_str("::ffff:1.2.3.0/120").unwrap();
+        assert_eq!(subnet, Subnet::from_str("1.2.3.0/24").unwrap());
+        assert_eq!(subnet.to_string(), "1.2.3.0/24");
+        assert!(subnet.contains("1.2.3.4".parse().unwrap()));
+        assert!(subnet.contains("::ffff:1.2.3.4".parse().unwrap()));
+
+        // Ensure an IPv4-mapped IPv6 address is stored as its IPv4 address.
+        let subnet = Subnet::from_str("::ffff:1.2.3.4").unwrap();
+        assert_eq!(subnet, Subnet::from_str("1.2.3.4").unwrap());
+        assert_eq!(subnet.to_string(), "1.2.3.4");
+
+        // Ensure a subnet that is wider than the IPv4-mapped range is kept as an IPv6 subnet.
+        let subnet = Subnet::from_str("::ffff:0.0.0.0/95").unwrap();
+        assert_eq!(subnet.to_string(), "::fffe:0:0/95");
+    }
+
+    #[test]
+    fn test_ban_list() {
+        let directory = tempfile::tempdir().expect("Failed to open temporary directory");
+        let path = directory.path().join("bans");
+
+        let subnet = Subnet::from_str("10.0.0.0/8").unwrap();
+        let expired = Subnet::from_str("192.168.0.1").unwrap();
+        {
+            let mut ban_list = BanList::open(&path).unwrap();
+            assert!(ban_list.is_empty());
+
+            ban_list
+                .ban(Ban {
+                    subnet,
+                    expiry: None,
+                    reason: Some("spam".to_string()),
+                })
+                .unwrap();
+            assert!(ban_list.is_banned("10.0.0.1".parse().unwrap()));
+            assert!(!ban_list.is_banned("192.168.0.1".parse().unwrap()));
+
+            // Ensure a ban that has already expired is rejected.
+            let ban = Ban {
+                subnet: expired,
+                expiry: Some(Utc::now().timestamp() - 1),
+                reason: None,
+            };
+            assert!(ban_list.ban(ban).is_err());
+        }
+
+        // Ensure the ban list is restored from disk.
+        let mut ban_list = BanList::open(&path).unwrap();
+        assert_eq!(ban_list.len(), 1);
+        assert!(ban_list.is_banned("10.0.0.1".parse().unwrap()));
+
+        // Ensure the bans from the config file apply, but cannot be lifted at runtime.
+        let config_path = directory.path().join("bans.json");
+        fs::write(&config_path, r#"[{ "subnet": "192.168.0.0/16", "reason": "abusive host" }]"#).unwrap();
+        ban_list.load_config(&config_path).unwrap();
+        assert!(ban_list.is_banned("192.168.5.5".parse().unwrap()));
+        assert!(ban_list.unban(&Subnet::from_str("192.168.0.0/16").unwrap()).is_err());
+
+        // Ensure an unban is persisted, and that the bans from the config file are not.
+        assert!(ban_list.unban(&subnet).unwrap());
+        assert!(!ban_list.unban(&subnet).unwrap());
+        assert!(BanList::open(&path).unwrap().is_empty());
+    }
+}
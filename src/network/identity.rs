@@ -0,0 +1,231 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{helpers::write_atomically, NodeKeypair, NodePublicKey};
+use snarkvm::dpc::prelude::*;
+
+use anyhow::{anyhow, Result};
+use rand::thread_rng;
+use serde::{Deserialize, Serialize};
+use std::{fmt, fs, path::Path, str::FromStr};
+
+/// The ID of a peer, which is the address of its node identity.
+pub type PeerId<N> = Address<N>;
+
+///
+/// The persistent identity of a node, which peers use to recognize the node across sessions.
+///
+#[derive(Clone)]
+pub struct NodeIdentity<N: Network> {
+    /// The private key that signs the challenge nonces of peers.
+    private_key: PrivateKey<N>,
+    /// The peer ID of the node.
+    peer_id: PeerId<N>,
+    /// The keypair that encrypts and authenticates the connections of the node.
+    noise_keypair: NodeKeypair,
+}
+
+/// The on-disk format of a node identity.
+#[derive(Serialize, Deserialize)]
+struct NodeIdentityFile {
+    private_key: String,
+    noise_private_key: String,
+    noise_public_key: String,
+}
+
+impl<N: Network> NodeIdentity<N> {
+    /// Samples a new node identity, which is not persisted.
+    pub fn new() -> Result<Self> {
+        let private_key = PrivateKey::new(&mut thread_rng());
+        Ok(Self {
+            peer_id: Address::from_private_key(&private_key),
+            private_key,
+            noise_keypair: NodeKeypair::new()?,
+        })
+    }
+
+    ///
+    /// Opens the node identity at the given path.
+    /// If the node identity does not exist, a new one is sampled and written to the given path.
+    /// A node identity whose Noise public key does not match its Noise private key is refused.
+    ///
+    /// On Unix, the node identity is only readable by its owner, and a node identity that is
+    /// accessible by other users is refused, as it contains the private keys of the node.
+    ///
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if path.exists() {
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+
+                let mode = fs::metadata(path)?.permissions().mode() & 0o777;
+                if mode & 0o077 != 0 {
+                    return Err(anyhow!(
+                        "The node identity at {} is accessible by other users (mode {:o}), restrict it to its owner with `chmod 600`",
+                        path.display(),
+                        mode
+                    ));
+                }
+            }
+
+            let file: NodeIdentityFile = serde_json::from_slice(&fs::read(path)?)?;
+            let private_key = PrivateKey::from_str(&file.private_key)?;
+
+            // Ensure the Noise public key is the one derived from the Noise private key.
+            let noise_keypair = NodeKeypair::from_private_key(&hex::decode(file.noise_private_key)?)?;
+            if noise_keypair.public_key().to_string() != file.noise_public_key {
+                return Err(anyhow!(
+                    "The node identity at {} has a Noise public key that does not match its Noise private key",
+                    path.display()
+                ));
+            }
+
+            Ok(Self {
+                peer_id: Address::from_private_key(&private_key),
+                private_key,
+                noise_keypair,
+            })
+        } else {
+            let identity = Self::new()?;
+            let file = NodeIdentityFile {
+                private_key: identity.private_key.to_string(),
+                noise_private_key: hex::encode(identity.noise_keypair.private_key()),
+                noise_public_key: identity.noise_keypair.public_key().to_string(),
+            };
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            write_atomically(path, &serde_json::to_vec_pretty(&file)?)?;
+
+            Ok(identity)
+        }
+    }
+
+    /// Returns the peer ID of the node.
+    pub fn peer_id(&self) -> PeerId<N> {
+        self.peer_id
+    }
+
+    /// Returns the keypair that encrypts and authenticates the connections of the node.
+    pub fn noise_keypair(&self) -> &NodeKeypair {
+        &self.noise_keypair
+    }
+
+    ///
+    /// Signs the given challenge nonce of a peer.
+    ///
+    /// The signature also covers the Noise public key of this node, binding the peer ID
+    /// to the encrypted connection, so that it cannot be relayed to another connection.
+    ///
+    pub fn sign_challenge(&self, peer_nonce: u64) -> Result<N::AccountSignature> {
+        let message = challenge_message(peer_nonce, &self.noise_keypair.public_key());
+        Ok(self.private_key.sign(&message, &mut thread_rng())?)
+    }
+
+    ///
+    /// Returns `true` if the given signature is from the given peer ID, over the challenge nonce
+    /// of this node and the Noise public key of the peer.
+    ///
+    pub fn verify_challenge(
+        peer_id: &PeerId<N>,
+        local_nonce: u64,
+        peer_public_key: &NodePublicKey,
+        signature: &N::AccountSignature,
+    ) -> Result<bool> {
+        let message = challenge_message(local_nonce, peer_public_key);
+        match peer_id.verify_signature(&message, signature) {
+            Ok(is_valid) => Ok(is_valid),
+            Err(error) => Err(anyhow!("Failed to verify the challenge signature: {}", error)),
+        }
+    }
+}
+
+impl<N: Network> fmt::Debug for NodeIdentity<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("NodeIdentity").field("peer_id", &self.peer_id).finish()
+    }
+}
+
+/// Returns the message that is signed to answer a challenge := (nonce || noise_public_key), as little-endian bits.
+fn challenge_message(nonce: u64, noise_public_key: &NodePublicKey) -> Vec<bool> {
+    [&nonce.to_le_bytes()[..], noise_public_key.as_bytes()]
+        .concat()
+        .iter()
+        .flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::dpc::testnet2::Testnet2;
+
+    #[test]
+    fn test_open() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("identity");
+
+        // Ensure the identity is created on the first open, and persisted for the next one.
+        let identity = NodeIdentity::<Testnet2>::open(&path).unwrap();
+        assert!(path.exists());
+        let reopened = NodeIdentity::<Testnet2>::open(&path).unwrap();
+        assert_eq!(identity.peer_id(), reopened.peer_id());
+        assert_eq!(identity.noise_keypair().public_key(), reopened.noise_keypair().public_key());
+
+        // Ensure an identity with a Noise public key that does not match its Noise private key is refused.
+        let mut file: NodeIdentityFile = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        file.noise_public_key = NodeKeypair::new().unwrap().public_key().to_string();
+        fs::write(&path, serde_json::to_vec_pretty(&file).unwrap()).unwrap();
+        assert!(NodeIdentity::<Testnet2>::open(&path).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_open_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("identity");
+
+        // Ensure the identity is only readable by its owner.
+        NodeIdentity::<Testnet2>::open(&path).unwrap();
+        assert_eq!(0o600, fs::metadata(&path).unwrap().permissions().mode() & 0o777);
+        assert!(!path.with_extension("tmp").exists());
+
+        // Ensure an identity that is readable by other users is refused.
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(NodeIdentity::<Testnet2>::open(&path).is_err());
+    }
+
+    #[test]
+    fn test_challenge() {
+        let identity = NodeIdentity::<Testnet2>::new().unwrap();
+        let other = NodeIdentity::<Testnet2>::new().unwrap();
+
+        let nonce = 1234;
+        let signature = identity.sign_challenge(nonce).unwrap();
+        let public_key = identity.noise_keypair().public_key();
+
+        // Ensure the signature is only valid for the signer, nonce, and Noise public key.
+        assert!(NodeIdentity::verify_challenge(&identity.peer_id(), nonce, &public_key, &signature).unwrap());
+        assert!(!NodeIdentity::verify_challenge(&other.peer_id(), nonce, &public_key, &signature).unwrap());
+        assert!(!NodeIdentity::verify_challenge(&identity.peer_id(), nonce + 1, &public_key, &signature).unwrap());
+        let other_public_key = other.noise_keypair().public_key();
+        assert!(!NodeIdentity::verify_challenge(&identity.peer_id(), nonce, &other_public_key, &signature).unwrap());
+    }
+}
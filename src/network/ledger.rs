@@ -15,11 +15,12 @@
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
-    helpers::{block_requests::*, CircularMap, State, Status, Tasks},
+    helpers::{block_requests::*, AsyncLedgerReader, CircularMap, State, Status, Tasks},
     Data,
     Environment,
     LedgerReader,
     Message,
+    Misbehavior,
     NodeType,
     PeersRequest,
     PeersRouter,
@@ -50,6 +51,8 @@ use tokio::{
 
 /// The maximum number of unconfirmed blocks that can be held by the ledger.
 const MAXIMUM_UNCONFIRMED_BLOCKS: u32 = 250;
+/// The maximum number of in-order blocks that are staged before they are imported in bulk.
+const MAXIMUM_STAGED_BLOCKS: usize = 50;
 
 /// Shorthand for the parent half of the `Ledger` message channel.
 pub(crate) type LedgerRouter<N> = mpsc::Sender<LedgerRequest<N>>;
@@ -66,12 +69,10 @@ pub enum LedgerRequest<N: Network> {
     BlockResponse(SocketAddr, Block<N>, ProverRouter<N>),
     /// Disconnect := (peer_ip, message)
     Disconnect(SocketAddr, String),
-    /// Failure := (peer_ip, failure)
-    Failure(SocketAddr, String),
     /// Heartbeat := (prover_router)
     Heartbeat(ProverRouter<N>),
-    /// Pong := (peer_ip, node_type, status, is_fork, block_locators)
-    Pong(SocketAddr, NodeType, State, Option<bool>, BlockLocators<N>),
+    /// Pong := (peer_ip, node_type, status, is_fork, pruned_block_height, block_locators)
+    Pong(SocketAddr, NodeType, State, Option<bool>, u32, BlockLocators<N>),
     /// UnconfirmedBlock := (peer_ip, block, prover_router)
     UnconfirmedBlock(SocketAddr, Block<N>, ProverRouter<N>),
 }
@@ -119,7 +120,8 @@ impl<N: Network> From<(u32, Option<N::BlockHash>)> for BlockRequest<N> {
     }
 }
 
-pub type PeersState<N> = HashMap<SocketAddr, Option<(NodeType, State, Option<bool>, u32, BlockLocators<N>)>>;
+/// PeersState := peer_ip => (node_type, status, is_fork, latest_block_height, pruned_block_height, block_locators)
+pub type PeersState<N> = HashMap<SocketAddr, Option<(NodeType, State, Option<bool>, u32, u32, BlockLocators<N>)>>;
 
 ///
 /// A ledger for a specific network on the node server.
@@ -134,11 +136,17 @@ pub struct Ledger<N: Network, E: Environment> {
     /// The canonical chain of blocks in read-only mode.
     #[allow(unused)]
     canon_reader: Arc<LedgerState<N>>,
+    /// The canonical chain of blocks, for storage calls from asynchronous tasks.
+    canon_async: AsyncLedgerReader<N>,
     /// A lock to ensure methods that need to be mutually-exclusive are enforced.
     /// In this context, `add_block`, and `revert_to_block_height` must be mutually-exclusive.
     canon_lock: Arc<Mutex<()>>,
     /// A map of previous block hashes to unconfirmed blocks.
     unconfirmed_blocks: RwLock<CircularMap<N::BlockHash, Block<N>, { MAXIMUM_UNCONFIRMED_BLOCKS }>>,
+    /// The in-order blocks received while syncing, which extend the canonical chain once imported in bulk.
+    staged_blocks: RwLock<Vec<Block<N>>>,
+    /// The blocks reverted while syncing, which are recorded as a reorg once the canonical chain outweighs them.
+    pending_reorg: RwLock<Vec<Block<N>>>,
     /// The map of each peer to their ledger state := (node_type, status, is_fork, latest_block_height, block_locators).
     peers_state: RwLock<PeersState<N>>,
     /// The map of each peer to their block requests := HashMap<(block_height, block_hash), timestamp>
@@ -148,8 +156,6 @@ pub struct Ledger<N: Network, E: Environment> {
     block_requests_lock: Arc<Mutex<()>>,
     /// The timestamp of the last successful block update.
     last_block_update_timestamp: RwLock<Instant>,
-    /// The map of each peer to their failure messages := (failure_message, timestamp).
-    failures: RwLock<HashMap<SocketAddr, Vec<(String, i64)>>>,
     /// The status of the node.
     status: Status,
     /// A terminator bit for the prover.
@@ -163,6 +169,7 @@ impl<N: Network, E: Environment> Ledger<N, E> {
     pub async fn open<S: Storage, P: AsRef<Path> + Copy>(
         tasks: &mut Tasks<JoinHandle<()>>,
         path: P,
+        pruning_depth: Option<u32>,
         status: &Status,
         terminator: &Arc<AtomicBool>,
         peers_router: PeersRouter<N, E>,
@@ -170,18 +177,30 @@ impl<N: Network, E: Environment> Ledger<N, E> {
         // Initialize an mpsc channel for sending requests to the `Ledger` struct.
         let (ledger_router, mut ledger_handler) = mpsc::channel(1024);
 
+        // Open the canonical ledger, in pruned mode if a pruning depth is specified.
+        let canon = Arc::new(match pruning_depth {
+            Some(pruning_depth) => LedgerState::open_pruned_writer::<S, P>(path, pruning_depth)?,
+            None => LedgerState::open_writer::<S, P>(path)?,
+        });
+
+        // Open the canonical ledger in read-only mode, whose lag behind the canonical ledger is reported in the storage stats.
+        let canon_reader = LedgerState::open_reader::<S, P>(path)?;
+        let canon_async = AsyncLedgerReader::new(canon.clone())?.with_reader(canon_reader.clone());
+
         // Initialize the ledger.
         let ledger = Arc::new(Self {
             ledger_router,
-            canon: Arc::new(LedgerState::open_writer::<S, P>(path)?),
-            canon_reader: LedgerState::open_reader::<S, P>(path)?,
+            canon,
+            canon_reader,
+            canon_async,
             canon_lock: Arc::new(Mutex::new(())),
             unconfirmed_blocks: Default::default(),
+            staged_blocks: Default::default(),
+            pending_reorg: Default::default(),
             peers_state: Default::default(),
             block_requests: Default::default(),
             block_requests_lock: Arc::new(Mutex::new(())),
             last_block_update_timestamp: RwLock::new(Instant::now()),
-            failures: Default::default(),
             status: status.clone(),
             terminator: terminator.clone(),
             peers_router,
@@ -218,6 +237,11 @@ impl<N: Network, E: Environment> Ledger<N, E> {
         // self.canon_reader.clone()
     }
 
+    /// Returns an instance of the asynchronous ledger reader.
+    pub fn async_reader(&self) -> AsyncLedgerReader<N> {
+        self.canon_async.clone()
+    }
+
     /// Returns an instance of the ledger router.
     pub fn router(&self) -> LedgerRouter<N> {
         self.ledger_router.clone()
@@ -230,10 +254,17 @@ impl<N: Network, E: Environment> Ledger<N, E> {
         self.terminator.store(true, Ordering::SeqCst);
         trace!("[ShuttingDown] Terminator bit has been enabled");
 
-        // Clear the unconfirmed blocks.
+        // Clear the unconfirmed blocks and the staged blocks.
         self.unconfirmed_blocks.write().await.clear();
+        self.staged_blocks.write().await.clear();
         trace!("[ShuttingDown] Pending queue has been cleared");
 
+        // Record the pending reorg, as the canonical chain will not advance any further.
+        {
+            let _canon_lock = self.canon_lock.lock().await;
+            self.record_pending_reorg(true).await;
+        }
+
         // Disconnect all connected peers.
         let connected_peers = self.peers_state.read().await.keys().copied().collect::<Vec<_>>();
         for peer_ip in connected_peers {
@@ -259,8 +290,14 @@ impl<N: Network, E: Environment> Ledger<N, E> {
             LedgerRequest::BlockResponse(peer_ip, block, prover_router) => {
                 // Remove the block request from the ledger.
                 if self.remove_block_request(peer_ip, block.height()).await {
-                    // On success, process the block response.
-                    self.add_block(block, &prover_router).await;
+                    // On success, process the block response, staging it for a bulk import while syncing.
+                    match self.status.is_syncing() {
+                        true => self.stage_block(block, &prover_router).await,
+                        false => {
+                            self.import_staged_blocks(&prover_router).await;
+                            self.add_block(block, &prover_router).await;
+                        }
+                    }
                     // Check if syncing with this peer is complete.
                     if self
                         .block_requests
@@ -271,16 +308,14 @@ impl<N: Network, E: Environment> Ledger<N, E> {
                         .unwrap_or(false)
                     {
                         trace!("All block requests with {} have been processed", peer_ip);
-                        self.update_block_requests().await;
+                        self.import_staged_blocks(&prover_router).await;
+                        self.update_block_requests(&prover_router).await;
                     }
                 }
             }
             LedgerRequest::Disconnect(peer_ip, message) => {
                 self.disconnect(peer_ip, &message).await;
             }
-            LedgerRequest::Failure(peer_ip, failure) => {
-                self.add_failure(peer_ip, failure).await;
-            }
             LedgerRequest::Heartbeat(prover_router) => {
                 // Update for sync nodes.
                 self.update_sync_nodes().await;
@@ -290,12 +325,8 @@ impl<N: Network, E: Environment> Ledger<N, E> {
                 self.update_status().await;
                 // Remove expired block requests.
                 self.remove_expired_block_requests().await;
-                // Remove expired failures.
-                self.remove_expired_failures().await;
-                // Disconnect from peers with frequent failures.
-                self.disconnect_from_failing_peers().await;
                 // Update the block requests.
-                self.update_block_requests().await;
+                self.update_block_requests(&prover_router).await;
 
                 debug!(
                     "Status Report (type = {}, status = {}, block_height = {}, cumulative_weight = {}, block_requests = {}, connected_peers = {})",
@@ -307,11 +338,12 @@ impl<N: Network, E: Environment> Ledger<N, E> {
                     self.peers_state.read().await.len()
                 );
             }
-            LedgerRequest::Pong(peer_ip, node_type, status, is_fork, block_locators) => {
+            LedgerRequest::Pong(peer_ip, node_type, status, is_fork, pruned_block_height, block_locators) => {
                 // Ensure the peer has been initialized in the ledger.
                 self.initialize_peer(peer_ip).await;
                 // Process the pong.
-                self.update_peer(peer_ip, node_type, status, is_fork, block_locators).await;
+                self.update_peer(peer_ip, node_type, status, is_fork, pruned_block_height, block_locators)
+                    .await;
             }
             LedgerRequest::UnconfirmedBlock(peer_ip, block, prover_router) => {
                 // Ensure the node is not peering.
@@ -391,7 +423,7 @@ impl<N: Network, E: Environment> Ledger<N, E> {
 
             // Check if any of the peers are ahead and have a larger block height.
             for (peer_ip, peer_state) in peers_state.iter() {
-                if let Some((node_type, status, Some(_), block_height, block_locators)) = peer_state {
+                if let Some((node_type, status, Some(_), block_height, _, block_locators)) = peer_state {
                     // Retrieve the cumulative weight, defaulting to the block height if it does not exist.
                     let cumulative_weight = match block_locators.get_cumulative_weight(*block_height) {
                         Some(cumulative_weight) => cumulative_weight,
@@ -422,6 +454,9 @@ impl<N: Network, E: Environment> Ledger<N, E> {
     /// Attempt to fast-forward the ledger with unconfirmed blocks.
     ///
     async fn update_ledger(&self, prover_router: &ProverRouter<N>) {
+        // Import the staged blocks, so the unconfirmed blocks may build on them.
+        self.import_staged_blocks(prover_router).await;
+
         // Check for candidate blocks to fast forward the ledger.
         let mut block_hash = self.canon.latest_block_hash();
         let unconfirmed_blocks_snapshot = self.unconfirmed_blocks.read().await.clone();
@@ -444,6 +479,7 @@ impl<N: Network, E: Environment> Ledger<N, E> {
 
             trace!("Ledger state has become stale, clearing queue and reverting by one block");
             self.unconfirmed_blocks.write().await.clear();
+            self.staged_blocks.write().await.clear();
 
             // Reset the memory pool of its transactions.
             if let Err(error) = prover_router.send(ProverRequest::MemoryPoolClear(None)).await {
@@ -455,7 +491,7 @@ impl<N: Network, E: Environment> Ledger<N, E> {
                 .await
                 .values_mut()
                 .for_each(|requests| *requests = Default::default());
-            self.revert_to_block_height(self.canon.latest_block_height().saturating_sub(1))
+            self.revert_to_block_height(self.canon.latest_block_height().saturating_sub(1), prover_router)
                 .await;
         }
     }
@@ -490,7 +526,7 @@ impl<N: Network, E: Environment> Ledger<N, E> {
             let latest_cumulative_weight = self.canon.latest_cumulative_weight();
             // Iterate through the connected peers, to determine if the ledger state is out of date.
             for (_, peer_state) in self.peers_state.read().await.iter() {
-                if let Some((_, _, Some(_), block_height, block_locators)) = peer_state {
+                if let Some((_, _, Some(_), block_height, _, block_locators)) = peer_state {
                     // Retrieve the cumulative weight, defaulting to the block height if it does not exist.
                     let cumulative_weight = match block_locators.get_cumulative_weight(*block_height) {
                         Some(cumulative_weight) => cumulative_weight,
@@ -522,7 +558,8 @@ impl<N: Network, E: Environment> Ledger<N, E> {
     ///
     /// Adds the given block:
     ///     1) as the next block in the ledger if the block height increments by one, or
-    ///     2) to the pending queue for later use.
+    ///     2) to a side chain if its previous block is known, or
+    ///     3) to the pending queue for later use.
     ///
     /// Returns `true` if the given block is successfully added to the *canon* chain.
     ///
@@ -549,24 +586,8 @@ impl<N: Network, E: Environment> Ledger<N, E> {
             // Acquire the lock for the canon chain.
             let _canon_lock = self.canon_lock.lock().await;
 
-            // Ensure the block height is not part of a block request on a fork.
-            let mut is_block_on_fork = false;
-            'outer: for requests in self.block_requests.read().await.values() {
-                for request in requests.keys() {
-                    // If the unconfirmed block conflicts with a requested block on a fork, skip.
-                    if request.block_height == unconfirmed_block_height {
-                        if let Some(requested_block_hash) = request.block_hash {
-                            if unconfirmed_block.hash() != requested_block_hash {
-                                is_block_on_fork = true;
-                                break 'outer;
-                            }
-                        }
-                    }
-                }
-            }
-
             // If the unconfirmed block is not on a fork, attempt to add it as the next block.
-            match is_block_on_fork {
+            match self.is_block_on_fork(&unconfirmed_block).await {
                 // Filter out the undesirable unconfirmed blocks, if it exists.
                 true => self.unconfirmed_blocks.write().await.remove(&unconfirmed_previous_block_hash),
                 // Attempt to add the unconfirmed block as the next block in the canonical chain.
@@ -584,6 +605,8 @@ impl<N: Network, E: Environment> Ledger<N, E> {
                         self.terminator.store(true, Ordering::SeqCst);
                         // On success, filter the unconfirmed blocks of this block, if it exists.
                         self.unconfirmed_blocks.write().await.remove(&unconfirmed_previous_block_hash);
+                        // Record the pending reorg, if the canonical chain now outweighs the reverted blocks.
+                        self.record_pending_reorg(false).await;
 
                         // On success, filter the memory pool of its transactions, if they exist.
                         if let Err(error) = prover_router.send(ProverRequest::MemoryPoolClear(Some(unconfirmed_block))).await {
@@ -595,6 +618,14 @@ impl<N: Network, E: Environment> Ledger<N, E> {
                     Err(error) => warn!("{}", error),
                 },
             }
+        } else if self.canon.contains_block_hash(&unconfirmed_previous_block_hash).unwrap_or(false)
+            || self
+                .canon
+                .contains_side_chain_block(&unconfirmed_previous_block_hash)
+                .unwrap_or(false)
+        {
+            // Add the block to a side chain, as it forks from the canonical chain.
+            return self.add_side_chain_block(unconfirmed_block, prover_router).await;
         } else {
             // Add the block to the unconfirmed blocks.
             if self
@@ -615,10 +646,213 @@ impl<N: Network, E: Environment> Ledger<N, E> {
         false
     }
 
+    ///
+    /// Returns `true` if the given block conflicts with a requested block on a fork.
+    ///
+    async fn is_block_on_fork(&self, block: &Block<N>) -> bool {
+        for requests in self.block_requests.read().await.values() {
+            for request in requests.keys() {
+                if request.block_height == block.height() {
+                    if let Some(requested_block_hash) = request.block_hash {
+                        if block.hash() != requested_block_hash {
+                            return true;
+                        }
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    ///
+    /// Stages the given block for a bulk import, if it extends the staged blocks or the canonical chain.
+    /// Otherwise, the staged blocks are imported, and the given block is added individually.
+    ///
+    async fn stage_block(&self, block: Block<N>, prover_router: &ProverRouter<N>) {
+        let mut staged_blocks = self.staged_blocks.write().await;
+
+        // Determine if the block extends the last staged block, or the canonical chain if none are staged.
+        let (previous_block_height, previous_block_hash) = match staged_blocks.last() {
+            Some(staged_block) => (staged_block.height(), staged_block.hash()),
+            None => (self.canon.latest_block_height(), self.canon.latest_block_hash()),
+        };
+
+        if block.height() == previous_block_height + 1 && block.previous_block_hash() == previous_block_hash {
+            staged_blocks.push(block);
+            trace!("Staged block {} for a bulk import", previous_block_height + 1);
+
+            // Import the staged blocks once the limit is reached.
+            if staged_blocks.len() >= MAXIMUM_STAGED_BLOCKS {
+                drop(staged_blocks);
+                self.import_staged_blocks(prover_router).await;
+            }
+        } else {
+            drop(staged_blocks);
+            self.import_staged_blocks(prover_router).await;
+            self.add_block(block, prover_router).await;
+        }
+    }
+
+    ///
+    /// Imports the staged blocks into the canonical chain as a single batch, returning `true` on success.
+    ///
+    /// The staged blocks from the first block on a fork onwards, or all of the staged blocks
+    /// if the bulk import fails, are added individually instead.
+    ///
+    async fn import_staged_blocks(&self, prover_router: &ProverRouter<N>) -> bool {
+        let mut remaining_blocks = std::mem::take(&mut *self.staged_blocks.write().await);
+        if remaining_blocks.is_empty() {
+            return false;
+        }
+
+        let mut is_success = false;
+        {
+            // Acquire the lock for block requests.
+            let _block_requests_lock = self.block_requests_lock.lock().await;
+            // Acquire the lock for the canon chain.
+            let _canon_lock = self.canon_lock.lock().await;
+
+            // Select the staged blocks up to the first block on a fork.
+            let mut number_of_blocks = 0;
+            for block in &remaining_blocks {
+                if self.is_block_on_fork(block).await {
+                    break;
+                }
+                number_of_blocks += 1;
+            }
+            let blocks = remaining_blocks.drain(..number_of_blocks).collect::<Vec<_>>();
+
+            if !blocks.is_empty() {
+                match self.canon.add_next_blocks(&blocks) {
+                    Ok(()) => {
+                        info!(
+                            "Ledger successfully advanced to block {} ({}) with {} blocks",
+                            self.canon.latest_block_height(),
+                            self.canon.latest_block_hash(),
+                            blocks.len()
+                        );
+
+                        // Update the timestamp of the last block increment.
+                        *self.last_block_update_timestamp.write().await = Instant::now();
+                        // Set the terminator bit to `true` to ensure the miner updates state.
+                        self.terminator.store(true, Ordering::SeqCst);
+
+                        // On success, filter the unconfirmed blocks of these blocks, if they exist.
+                        let mut unconfirmed_blocks = self.unconfirmed_blocks.write().await;
+                        for block in &blocks {
+                            unconfirmed_blocks.remove(&block.previous_block_hash());
+                        }
+                        drop(unconfirmed_blocks);
+                        // Record the pending reorg, if the canonical chain now outweighs the reverted blocks.
+                        self.record_pending_reorg(false).await;
+
+                        // On success, filter the memory pool of their transactions, if they exist.
+                        for block in blocks {
+                            if let Err(error) = prover_router.send(ProverRequest::MemoryPoolClear(Some(block))).await {
+                                error!("[MemoryPoolClear]: {}", error);
+                            }
+                        }
+                        is_success = true;
+                    }
+                    Err(error) => {
+                        warn!("Failed to import {} blocks in bulk - {}", blocks.len(), error);
+                        remaining_blocks.splice(0..0, blocks);
+                    }
+                }
+            }
+        }
+
+        // Add the remaining blocks individually.
+        for block in remaining_blocks {
+            self.add_block(block, prover_router).await;
+        }
+        is_success
+    }
+
+    ///
+    /// Adds the given block to a side chain, along with the unconfirmed blocks that descend from it,
+    /// and switches the canonical chain to the heaviest side chain, if it outweighs the canonical chain.
+    ///
+    /// Returns `true` if the canonical chain is switched to a side chain.
+    ///
+    async fn add_side_chain_block(&self, block: Block<N>, prover_router: &ProverRouter<N>) -> bool {
+        // Acquire the lock for block requests.
+        let _block_requests_lock = self.block_requests_lock.lock().await;
+        // Acquire the lock for the canon chain.
+        let _canon_lock = self.canon_lock.lock().await;
+
+        // Add the block to a side chain, followed by the unconfirmed blocks that descend from it.
+        let mut unconfirmed_blocks = self.unconfirmed_blocks.write().await;
+        let mut next_block = Some(block);
+        while let Some(block) = next_block.take() {
+            if let Err(error) = self.canon.add_side_chain_block(&block) {
+                trace!("{}", error);
+                break;
+            }
+            trace!("Added block {} ({}) to a side chain", block.height(), block.hash());
+
+            next_block = unconfirmed_blocks.get(&block.hash()).cloned();
+            unconfirmed_blocks.remove(&block.hash());
+        }
+        drop(unconfirmed_blocks);
+
+        // Determine the heaviest side chain, and ensure it outweighs the canonical chain.
+        let (block_hash, block_height, cumulative_weight) = match self.canon.get_side_chain_tips() {
+            Ok(tips) => match tips.into_iter().max_by_key(|(_, _, cumulative_weight)| *cumulative_weight) {
+                Some(tip) => tip,
+                None => return false,
+            },
+            Err(error) => {
+                warn!("{}", error);
+                return false;
+            }
+        };
+        if cumulative_weight <= self.canon.latest_cumulative_weight() {
+            return false;
+        }
+
+        // Switch the canonical chain to the heaviest side chain.
+        match self.canon.switch_to_side_chain(&block_hash) {
+            Ok((reverted_blocks, added_blocks)) => {
+                info!(
+                    "Ledger successfully switched to a side chain at block {} ({})",
+                    self.canon.latest_block_height(),
+                    self.canon.latest_block_hash()
+                );
+
+                // Update the timestamp of the last block increment.
+                *self.last_block_update_timestamp.write().await = Instant::now();
+                // Set the terminator bit to `true` to ensure the miner updates state.
+                self.terminator.store(true, Ordering::SeqCst);
+                // Record the pending reorg, if the canonical chain now outweighs the reverted blocks.
+                self.record_pending_reorg(false).await;
+
+                // Return the transactions of the reverted blocks to the memory pool.
+                if let Err(error) = prover_router.send(ProverRequest::MemoryPoolRevert(reverted_blocks)).await {
+                    error!("[MemoryPoolRevert]: {}", error);
+                }
+                // Filter the memory pool of the transactions of the added blocks.
+                for block in added_blocks {
+                    if let Err(error) = prover_router.send(ProverRequest::MemoryPoolClear(Some(block))).await {
+                        error!("[MemoryPoolClear]: {}", error);
+                    }
+                }
+                true
+            }
+            Err(error) => {
+                warn!(
+                    "Failed to switch to the side chain at block {} ({}) - {}",
+                    block_height, block_hash, error
+                );
+                false
+            }
+        }
+    }
+
     ///
     /// Reverts the ledger state back to height `block_height`, returning `true` on success.
     ///
-    async fn revert_to_block_height(&self, block_height: u32) -> bool {
+    async fn revert_to_block_height(&self, block_height: u32, prover_router: &ProverRouter<N>) -> bool {
         // Acquire the lock for the canon chain.
         let _canon_lock = self.canon_lock.lock().await;
 
@@ -635,9 +869,27 @@ impl<N: Network, E: Environment> Ledger<N, E> {
                 let mut unconfirmed_blocks = self.unconfirmed_blocks.write().await;
 
                 // Ensure the removed blocks are not in the unconfirmed blocks.
-                for removed_block in removed_blocks {
+                for removed_block in &removed_blocks {
                     unconfirmed_blocks.remove(&removed_block.previous_block_hash());
                 }
+                drop(unconfirmed_blocks);
+
+                // Prepend the removed blocks to the pending reorg, skipping those that were added after it began,
+                // so that the reorg is recorded from the latest block before it, once the canonical chain outweighs it.
+                let mut pending_reorg = self.pending_reorg.write().await;
+                let first_block_height = pending_reorg.first().map(|block| block.height()).unwrap_or(u32::MAX);
+                let reverted_blocks = removed_blocks
+                    .iter()
+                    .filter(|block| block.height() < first_block_height)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                pending_reorg.splice(0..0, reverted_blocks);
+                drop(pending_reorg);
+
+                // Return the transactions of the removed blocks to the memory pool.
+                if let Err(error) = prover_router.send(ProverRequest::MemoryPoolRevert(removed_blocks)).await {
+                    error!("[MemoryPoolRevert]: {}", error);
+                }
                 true
             }
             Err(error) => {
@@ -645,14 +897,37 @@ impl<N: Network, E: Environment> Ledger<N, E> {
 
                 // Set the terminator bit to `true` to ensure the miner resets state.
                 self.terminator.store(true, Ordering::SeqCst);
-                // Reset the unconfirmed blocks.
+                // Reset the unconfirmed blocks and the staged blocks.
                 self.unconfirmed_blocks.write().await.clear();
+                self.staged_blocks.write().await.clear();
 
                 false
             }
         }
     }
 
+    ///
+    /// Records the pending reorg in the journal, if the canonical chain outweighs the reverted blocks,
+    /// or if `is_final` is `true`. If the canonical chain contains the reverted blocks again, the pending
+    /// reorg is discarded instead, as the chain was not replaced. The canon lock must be held by the caller.
+    ///
+    async fn record_pending_reorg(&self, is_final: bool) {
+        let mut pending_reorg = self.pending_reorg.write().await;
+        let last_block = match pending_reorg.last() {
+            Some(block) => block,
+            None => return,
+        };
+
+        if self.canon.contains_block_hash(&last_block.hash()).unwrap_or(false) {
+            pending_reorg.clear();
+        } else if is_final || self.canon.latest_cumulative_weight() > last_block.cumulative_weight() {
+            if let Err(error) = self.canon.add_reorg(&pending_reorg) {
+                warn!("Failed to record the reorg from block {} - {}", last_block.height(), error);
+            }
+            pending_reorg.clear();
+        }
+    }
+
     ///
     /// Adds an entry for the given peer IP to every data structure in `State`.
     ///
@@ -664,7 +939,6 @@ impl<N: Network, E: Environment> Ledger<N, E> {
         if !peer_state_exists {
             self.peers_state.write().await.entry(peer_ip).or_insert(None);
             self.block_requests.write().await.entry(peer_ip).or_insert_with(Default::default);
-            self.failures.write().await.entry(peer_ip).or_insert_with(Default::default);
         }
     }
 
@@ -674,7 +948,6 @@ impl<N: Network, E: Environment> Ledger<N, E> {
     async fn remove_peer(&self, peer_ip: &SocketAddr) {
         self.peers_state.write().await.remove(peer_ip);
         self.block_requests.write().await.remove(peer_ip);
-        self.failures.write().await.remove(peer_ip);
     }
 
     ///
@@ -686,19 +959,19 @@ impl<N: Network, E: Environment> Ledger<N, E> {
         node_type: NodeType,
         status: State,
         is_fork: Option<bool>,
+        pruned_block_height: u32,
         block_locators: BlockLocators<N>,
     ) {
         // Ensure the list of block locators is not empty.
         if block_locators.is_empty() {
-            self.add_failure(peer_ip, "Received a sync response with no block locators".to_string())
-                .await;
+            self.add_misbehavior(peer_ip, Misbehavior::InvalidBlockLocators).await;
         } else {
             // Ensure the peer provided well-formed block locators.
             match self.canon.check_block_locators(&block_locators) {
                 Ok(is_valid) => {
                     if !is_valid {
                         warn!("Invalid block locators from {}", peer_ip);
-                        self.add_failure(peer_ip, "Invalid block locators".to_string()).await;
+                        self.add_misbehavior(peer_ip, Misbehavior::InvalidBlockLocators).await;
                         return;
                     }
                 }
@@ -715,9 +988,8 @@ impl<N: Network, E: Environment> Ledger<N, E> {
                 // Ensure the block hash corresponds with the block height, if the block hash exists in this ledger.
                 if let Ok(expected_block_height) = self.canon.get_block_height(block_hash) {
                     if expected_block_height != *block_height {
-                        let error = format!("Invalid block height {} for block hash {}", expected_block_height, block_hash);
-                        trace!("{}", error);
-                        self.add_failure(peer_ip, error).await;
+                        trace!("Invalid block height {} for block hash {}", expected_block_height, block_hash);
+                        self.add_misbehavior(peer_ip, Misbehavior::InvalidBlockLocators).await;
                         return;
                     } else {
                         // Update the common ancestor, as this block hash exists in this ledger.
@@ -764,13 +1036,29 @@ impl<N: Network, E: Environment> Ledger<N, E> {
                 _ => "unknown".to_string(),
             };
             debug!(
-                "Peer {} is at block {} (type = {}, status = {}, is_fork = {}, cumulative_weight = {}, common_ancestor = {})",
-                peer_ip, latest_block_height_of_peer, node_type, status, fork_status, cumulative_weight, common_ancestor,
+                "Peer {} is at block {} (type = {}, status = {}, is_fork = {}, cumulative_weight = {}, common_ancestor = {}, pruned = {})",
+                peer_ip,
+                latest_block_height_of_peer,
+                node_type,
+                status,
+                fork_status,
+                cumulative_weight,
+                common_ancestor,
+                pruned_block_height,
             );
 
             match self.peers_state.write().await.get_mut(&peer_ip) {
-                Some(peer_state) => *peer_state = Some((node_type, status, is_fork, latest_block_height_of_peer, block_locators)),
-                None => self.add_failure(peer_ip, format!("Missing ledger state for {}", peer_ip)).await,
+                Some(peer_state) => {
+                    *peer_state = Some((
+                        node_type,
+                        status,
+                        is_fork,
+                        latest_block_height_of_peer,
+                        pruned_block_height,
+                        block_locators,
+                    ))
+                }
+                None => error!("Missing ledger state for {}", peer_ip),
             };
         }
     }
@@ -778,7 +1066,7 @@ impl<N: Network, E: Environment> Ledger<N, E> {
     ///
     /// Proceeds to send block requests to a connected peer, if the ledger is out of date.
     ///
-    async fn update_block_requests(&self) {
+    async fn update_block_requests(&self, prover_router: &ProverRouter<N>) {
         // Ensure the ledger is not awaiting responses from outstanding block requests.
         if self.number_of_block_requests().await > 0 {
             return;
@@ -815,13 +1103,13 @@ impl<N: Network, E: Environment> Ledger<N, E> {
                 Ok(ret) => ret,
                 Err(error) => {
                     trace!("{}", error);
-                    self.add_failure(peer_ip, error).await;
+                    self.add_misbehavior(peer_ip, Misbehavior::InvalidBlockLocators).await;
                     return;
                 }
             };
 
             // Case 2 - Prepare to send block requests, as the peer is ahead of this ledger.
-            let (start_block_height, end_block_height, ledger_is_on_fork) = match handle_block_requests::<N, E>(
+            let (mut start_block_height, mut end_block_height, ledger_is_on_fork) = match handle_block_requests::<N, E>(
                 latest_block_height,
                 latest_cumulative_weight,
                 peer_ip,
@@ -847,10 +1135,32 @@ impl<N: Network, E: Environment> Ledger<N, E> {
 
             // Revert the ledger, if it is on a fork.
             if ledger_is_on_fork {
-                // If the revert operation fails, abort.
-                if !self.revert_to_block_height(maximum_common_ancestor).await {
-                    warn!("Ledger failed to revert to block {}", maximum_common_ancestor);
-                    return;
+                // If the blocks of the fork are already held in a side chain, request only the blocks after them.
+                // The ledger switches to the side chain without a revert, once it outweighs the canonical chain.
+                let side_chain_block_height = maximum_block_locators
+                    .iter()
+                    .filter(|(block_height, (block_hash, _))| {
+                        **block_height > maximum_common_ancestor && self.canon.contains_side_chain_block(block_hash).unwrap_or(false)
+                    })
+                    .map(|(block_height, _)| *block_height)
+                    .max();
+
+                match side_chain_block_height {
+                    Some(side_chain_block_height) => {
+                        let number_of_block_requests = end_block_height - start_block_height;
+                        start_block_height = side_chain_block_height + 1;
+                        end_block_height = std::cmp::min(start_block_height + number_of_block_requests, maximum_block_height);
+                        if start_block_height > end_block_height {
+                            return;
+                        }
+                    }
+                    // If the revert operation fails, abort.
+                    None => {
+                        if !self.revert_to_block_height(maximum_common_ancestor, prover_router).await {
+                            warn!("Ledger failed to revert to block {}", maximum_common_ancestor);
+                            return;
+                        }
+                    }
                 }
             }
 
@@ -872,7 +1182,7 @@ impl<N: Network, E: Environment> Ledger<N, E> {
                     }
                 }
             } else {
-                self.add_failure(peer_ip, format!("Missing block requests for {}", peer_ip)).await;
+                error!("Missing block requests for {}", peer_ip);
                 missing_block_requests = true;
             }
 
@@ -952,7 +1262,7 @@ impl<N: Network, E: Environment> Ledger<N, E> {
     ) {
         match locked_block_requests.insert((block_height, block_hash).into(), Utc::now().timestamp()) {
             None => debug!("Requesting block {} from {}", block_height, peer_ip),
-            Some(_old_request) => self.add_failure(peer_ip, format!("Duplicate block request for {}", peer_ip)).await,
+            Some(_old_request) => warn!("Duplicate block request for {}", peer_ip),
         }
     }
 
@@ -973,17 +1283,14 @@ impl<N: Network, E: Environment> Ledger<N, E> {
     async fn remove_block_request(&self, peer_ip: SocketAddr, block_height: u32) -> bool {
         // Ensure the block height corresponds to a requested block.
         if !self.contains_block_request(peer_ip, block_height).await {
-            self.add_failure(peer_ip, "Received an invalid block response".to_string()).await;
+            self.add_misbehavior(peer_ip, Misbehavior::UnsolicitedBlockResponse).await;
             false
         } else {
             if let Some(requests) = self.block_requests.write().await.get_mut(&peer_ip) {
                 let is_success = requests.remove(&block_height.into()).is_some();
                 match is_success {
                     true => return true,
-                    false => {
-                        self.add_failure(peer_ip, format!("Non-existent block request from {}", peer_ip))
-                            .await
-                    }
+                    false => self.add_misbehavior(peer_ip, Misbehavior::UnsolicitedBlockResponse).await,
                 }
             }
             false
@@ -991,53 +1298,32 @@ impl<N: Network, E: Environment> Ledger<N, E> {
     }
 
     ///
-    /// Removes block requests that have expired.
+    /// Removes block requests that have expired, and reports a timeout for each peer that failed to respond.
     ///
     async fn remove_expired_block_requests(&self) {
         // Clear all block requests that have lived longer than `E::RADIO_SILENCE_IN_SECS`.
         let now = Utc::now().timestamp();
-        self.block_requests.write().await.iter_mut().for_each(|(_peer, block_requests)| {
-            block_requests.retain(|_, time_of_request| now.saturating_sub(*time_of_request) < E::RADIO_SILENCE_IN_SECS as i64)
+        let mut timed_out_peers = Vec::new();
+        self.block_requests.write().await.iter_mut().for_each(|(peer_ip, block_requests)| {
+            let number_of_block_requests = block_requests.len();
+            block_requests.retain(|_, time_of_request| now.saturating_sub(*time_of_request) < E::RADIO_SILENCE_IN_SECS as i64);
+            if block_requests.len() < number_of_block_requests {
+                timed_out_peers.push(*peer_ip);
+            }
         });
-    }
-
-    ///
-    /// Adds the given failure message to the specified peer IP.
-    ///
-    async fn add_failure(&self, peer_ip: SocketAddr, failure: String) {
-        trace!("Adding failure for {}: {}", peer_ip, failure);
-        match self.failures.write().await.get_mut(&peer_ip) {
-            Some(failures) => failures.push((failure, Utc::now().timestamp())),
-            None => error!("Missing failure entry for {}", peer_ip),
-        };
-    }
 
-    ///
-    /// Removes failures that have expired.
-    ///
-    async fn remove_expired_failures(&self) {
-        // Clear all failures that have lived longer than `E::FAILURE_EXPIRY_TIME_IN_SECS`.
-        let now = Utc::now().timestamp();
-        self.failures.write().await.iter_mut().for_each(|(_, failures)| {
-            failures.retain(|(_, time_of_fail)| now.saturating_sub(*time_of_fail) < E::FAILURE_EXPIRY_TIME_IN_SECS as i64)
-        });
+        for peer_ip in timed_out_peers {
+            self.add_misbehavior(peer_ip, Misbehavior::Timeout).await;
+        }
     }
 
     ///
-    /// Disconnects from connected peers who exhibit frequent failures.
+    /// Reports the given misbehavior of the specified peer IP to the peers, which maintain its reputation.
     ///
-    async fn disconnect_from_failing_peers(&self) {
-        let peers_to_disconnect = self
-            .failures
-            .read()
-            .await
-            .iter()
-            .filter(|(_, failures)| failures.len() > E::MAXIMUM_NUMBER_OF_FAILURES)
-            .map(|(peer_ip, _)| *peer_ip)
-            .collect::<Vec<_>>();
-
-        for peer_ip in peers_to_disconnect {
-            self.disconnect(peer_ip, "exceeded failure limit").await;
+    async fn add_misbehavior(&self, peer_ip: SocketAddr, misbehavior: Misbehavior) {
+        trace!("Adding misbehavior for {}: {}", peer_ip, misbehavior);
+        if let Err(error) = self.peers_router.send(PeersRequest::PeerMisbehaved(peer_ip, misbehavior)).await {
+            warn!("[PeerMisbehaved] {}", error);
         }
     }
 }
@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{helpers::State, Environment, NodeType};
+use crate::{helpers::State, Environment, NodeType, PeerId};
 use snarkos_storage::BlockLocators;
 use snarkvm::prelude::*;
 
@@ -81,10 +81,10 @@ pub enum Message<N: Network, E: Environment> {
     BlockRequest(u32, u32),
     /// BlockResponse := (block)
     BlockResponse(Data<Block<N>>),
-    /// ChallengeRequest := (version, fork_depth, node_type, status, listener_port, nonce, cumulative_weight)
-    ChallengeRequest(u32, u32, NodeType, State, u16, u64, u128),
-    /// ChallengeResponse := (block_header)
-    ChallengeResponse(Data<BlockHeader<N>>),
+    /// ChallengeRequest := (version, fork_depth, node_type, status, listener_port, nonce, cumulative_weight, peer_id)
+    ChallengeRequest(u32, u32, NodeType, State, u16, u64, u128, PeerId<N>),
+    /// ChallengeResponse := (nonce_signature, block_header)
+    ChallengeResponse(N::AccountSignature, Data<BlockHeader<N>>),
     /// Disconnect := ()
     Disconnect,
     /// PeerRequest := ()
@@ -93,8 +93,8 @@ pub enum Message<N: Network, E: Environment> {
     PeerResponse(Vec<SocketAddr>),
     /// Ping := (version, fork_depth, node_type, status, block_hash, block_header)
     Ping(u32, u32, NodeType, State, N::BlockHash, Data<BlockHeader<N>>),
-    /// Pong := (is_fork, block_locators)
-    Pong(Option<bool>, Data<BlockLocators<N>>),
+    /// Pong := (is_fork, pruned_block_height, block_locators)
+    Pong(Option<bool>, u32, Data<BlockLocators<N>>),
     /// UnconfirmedBlock := (block_height, block_hash, block)
     UnconfirmedBlock(u32, N::BlockHash, Data<Block<N>>),
     /// UnconfirmedTransaction := (transaction)
@@ -149,10 +149,19 @@ impl<N: Network, E: Environment> Message<N, E> {
         match self {
             Self::BlockRequest(start_block_height, end_block_height) => Ok(to_bytes_le![start_block_height, end_block_height]?),
             Self::BlockResponse(block) => Ok(block.serialize_blocking()?),
-            Self::ChallengeRequest(version, fork_depth, node_type, status, listener_port, nonce, cumulative_weight) => Ok(
-                bincode::serialize(&(version, fork_depth, node_type, status, listener_port, nonce, cumulative_weight))?,
-            ),
-            Self::ChallengeResponse(block_header) => Ok(block_header.serialize_blocking()?),
+            Self::ChallengeRequest(version, fork_depth, node_type, status, listener_port, nonce, cumulative_weight, peer_id) => {
+                Ok(bincode::serialize(&(
+                    version,
+                    fork_depth,
+                    node_type,
+                    status,
+                    listener_port,
+                    nonce,
+                    cumulative_weight,
+                    peer_id,
+                ))?)
+            }
+            Self::ChallengeResponse(signature, block_header) => Ok(bincode::serialize(&(signature, block_header.serialize_blocking()?))?),
             Self::Disconnect => Ok(vec![]),
             Self::PeerRequest => Ok(vec![]),
             Self::PeerResponse(peer_ips) => Ok(bincode::serialize(peer_ips)?),
@@ -160,7 +169,7 @@ impl<N: Network, E: Environment> Message<N, E> {
                 let non_deferred = bincode::serialize(&(version, fork_depth, node_type, status, block_hash))?;
                 Ok([non_deferred, block_header.serialize_blocking()?].concat())
             }
-            Self::Pong(is_fork, block_locators) => {
+            Self::Pong(is_fork, pruned_block_height, block_locators) => {
                 let serialized_is_fork: u8 = match is_fork {
                     None => 0,
                     Some(fork) => match fork {
@@ -169,7 +178,12 @@ impl<N: Network, E: Environment> Message<N, E> {
                     },
                 };
 
-                Ok([vec![serialized_is_fork], block_locators.serialize_blocking()?].concat())
+                Ok([
+                    vec![serialized_is_fork],
+                    pruned_block_height.to_le_bytes().to_vec(),
+                    block_locators.serialize_blocking()?,
+                ]
+                .concat())
             }
             Self::UnconfirmedBlock(block_height, block_hash, block) => Ok([
                 block_height.to_le_bytes().to_vec(),
@@ -204,10 +218,23 @@ impl<N: Network, E: Environment> Message<N, E> {
             0 => Self::BlockRequest(bincode::deserialize(&data[0..4])?, bincode::deserialize(&data[4..8])?),
             1 => Self::BlockResponse(Data::Buffer(data.to_vec())),
             2 => {
-                let (version, fork_depth, node_type, status, listener_port, nonce, cumulative_weight) = bincode::deserialize(data)?;
-                Self::ChallengeRequest(version, fork_depth, node_type, status, listener_port, nonce, cumulative_weight)
+                let (version, fork_depth, node_type, status, listener_port, nonce, cumulative_weight, peer_id) =
+                    bincode::deserialize(data)?;
+                Self::ChallengeRequest(
+                    version,
+                    fork_depth,
+                    node_type,
+                    status,
+                    listener_port,
+                    nonce,
+                    cumulative_weight,
+                    peer_id,
+                )
+            }
+            3 => {
+                let (signature, block_header): (_, Vec<u8>) = bincode::deserialize(data)?;
+                Self::ChallengeResponse(signature, Data::Buffer(block_header))
             }
-            3 => Self::ChallengeResponse(Data::Buffer(data.to_vec())),
             4 => match data.is_empty() {
                 true => Self::Disconnect,
                 false => return Err(anyhow!("Invalid 'Disconnect' message: {:?} {:?}", buffer, data)),
@@ -231,7 +258,14 @@ impl<N: Network, E: Environment> Message<N, E> {
                     _ => return Err(anyhow!("Invalid 'Pong' message: {:?} {:?}", buffer, data)),
                 };
 
-                Self::Pong(is_fork, Data::Buffer(data[1..].to_vec()))
+                let pruned_block_height = data.get(1..5).ok_or_else(|| anyhow!("Invalid 'Pong' message"))?;
+                let block_locators = data.get(5..).ok_or_else(|| anyhow!("Invalid 'Pong' message"))?;
+
+                Self::Pong(
+                    is_fork,
+                    bincode::deserialize(pruned_block_height)?,
+                    Data::Buffer(block_locators.to_vec()),
+                )
             }
             9 => Self::UnconfirmedBlock(
                 bincode::deserialize(&data[0..4])?,
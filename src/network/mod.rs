@@ -14,17 +14,32 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
+pub mod address_book;
+pub use address_book::*;
+
+pub mod ban_list;
+pub use ban_list::*;
+
+pub mod identity;
+pub use identity::*;
+
 pub(crate) mod ledger;
 pub(crate) use ledger::{LedgerRequest, LedgerRouter};
 
 pub mod message;
 pub use message::*;
 
+pub mod noise;
+pub use noise::*;
+
 pub(crate) mod peers;
 pub(crate) use peers::*;
 
 pub mod prover;
 pub use prover::*;
 
+pub mod reputation;
+pub use reputation::*;
+
 pub mod server;
 pub use server::{LedgerReader, Server};
@@ -0,0 +1,339 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{Environment, Message};
+use snarkvm::prelude::*;
+
+use ::bytes::{Buf, BufMut, BytesMut};
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use snow::{params::NoiseParams, Builder, TransportState};
+use std::{convert::TryInto, fmt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio_util::codec::{Decoder, Encoder};
+
+/// The Noise protocol used to encrypt and authenticate peer connections.
+/// The `XX` pattern transmits the static key of both parties, so each node proves possession of its identity key.
+static NOISE_PARAMS: Lazy<NoiseParams> = Lazy::new(|| "Noise_XX_25519_ChaChaPoly_BLAKE2s".parse().unwrap());
+
+/// The maximum size of a single Noise message, as defined by the Noise protocol.
+const MAXIMUM_NOISE_MESSAGE_SIZE: usize = 65535;
+/// The size of the authentication tag appended to each encrypted Noise message.
+const NOISE_TAG_SIZE: usize = 16;
+/// The maximum size of the plaintext carried in a single Noise message.
+const MAXIMUM_NOISE_PAYLOAD_SIZE: usize = MAXIMUM_NOISE_MESSAGE_SIZE - NOISE_TAG_SIZE;
+
+///
+/// The public key of a node identity.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NodePublicKey([u8; 32]);
+
+impl NodePublicKey {
+    /// Returns the public key from the given bytes.
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        match bytes.try_into() {
+            Ok(public_key) => Ok(Self(public_key)),
+            Err(_) => Err(anyhow!("Invalid node public key of length {}", bytes.len())),
+        }
+    }
+
+    /// Returns the public key as bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Display for NodePublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.0))
+    }
+}
+
+///
+/// The long-lived identity keypair of a node, used to authenticate its peer connections.
+///
+#[derive(Clone)]
+pub struct NodeKeypair {
+    private_key: Vec<u8>,
+    public_key: NodePublicKey,
+}
+
+impl NodeKeypair {
+    /// Samples a new node identity keypair.
+    pub fn new() -> Result<Self> {
+        let keypair = Builder::new(NOISE_PARAMS.clone()).generate_keypair()?;
+        Ok(Self {
+            private_key: keypair.private,
+            public_key: NodePublicKey::from_bytes(&keypair.public)?,
+        })
+    }
+
+    ///
+    /// Returns the keypair of the given private key bytes, deriving its public key.
+    ///
+    /// The public key is derived by running the first two messages of a Noise handshake in memory,
+    /// as the second message transmits the static key of the responder to the initiator.
+    ///
+    pub(crate) fn from_private_key(private_key: &[u8]) -> Result<Self> {
+        if private_key.len() != 32 {
+            return Err(anyhow!("Invalid node private key of length {}", private_key.len()));
+        }
+
+        let mut responder = Builder::new(NOISE_PARAMS.clone())
+            .local_private_key(private_key)
+            .build_responder()?;
+        let initiator_keypair = Builder::new(NOISE_PARAMS.clone()).generate_keypair()?;
+        let mut initiator = Builder::new(NOISE_PARAMS.clone())
+            .local_private_key(&initiator_keypair.private)
+            .build_initiator()?;
+
+        let mut message = vec![0u8; MAXIMUM_NOISE_MESSAGE_SIZE];
+        let mut payload = vec![0u8; MAXIMUM_NOISE_MESSAGE_SIZE];
+        let length = initiator.write_message(&[], &mut message)?;
+        responder.read_message(&message[..length], &mut payload)?;
+        let length = responder.write_message(&[], &mut message)?;
+        initiator.read_message(&message[..length], &mut payload)?;
+
+        match initiator.get_remote_static() {
+            Some(public_key) => Ok(Self {
+                private_key: private_key.to_vec(),
+                public_key: NodePublicKey::from_bytes(public_key)?,
+            }),
+            None => Err(anyhow!("Failed to derive the node public key")),
+        }
+    }
+
+    /// Returns the private key of the node identity.
+    pub(crate) fn private_key(&self) -> &[u8] {
+        &self.private_key
+    }
+
+    /// Returns the public key of the node identity.
+    pub fn public_key(&self) -> NodePublicKey {
+        self.public_key
+    }
+}
+
+impl fmt::Debug for NodeKeypair {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("NodeKeypair").field("public_key", &self.public_key).finish()
+    }
+}
+
+///
+/// A codec that encrypts and authenticates each `Message` sent over a peer connection.
+///
+/// Each serialized message is split into Noise messages of at most 65535 bytes,
+/// where each Noise message is prefixed with its length as a big-endian `u16`.
+///
+pub struct NoiseCodec<N: Network, E: Environment> {
+    /// The Noise transport, set once the handshake has completed.
+    transport: Option<Box<TransportState>>,
+    /// The codec for the plaintext messages.
+    message_codec: Message<N, E>,
+    /// The decrypted bytes that have not yet formed a complete message.
+    plaintext: BytesMut,
+    /// The scratch buffer for encrypting and decrypting Noise messages.
+    buffer: Vec<u8>,
+}
+
+impl<N: Network, E: Environment> NoiseCodec<N, E> {
+    ///
+    /// Performs the Noise handshake with the peer, returning the codec for the connection
+    /// and the public key of the peer upon success.
+    ///
+    /// The initiator of the connection sends the first and last of the three handshake messages.
+    ///
+    pub async fn handshake<S: AsyncRead + AsyncWrite + Unpin>(
+        stream: &mut S,
+        local_keypair: &NodeKeypair,
+        is_initiator: bool,
+    ) -> Result<(Self, NodePublicKey)> {
+        let builder = Builder::new(NOISE_PARAMS.clone()).local_private_key(&local_keypair.private_key);
+        let mut noise = match is_initiator {
+            true => builder.build_initiator()?,
+            false => builder.build_responder()?,
+        };
+
+        let mut buffer = vec![0u8; MAXIMUM_NOISE_MESSAGE_SIZE];
+        while !noise.is_handshake_finished() {
+            match noise.is_my_turn() {
+                true => {
+                    let length = noise.write_message(&[], &mut buffer)?;
+                    stream.write_u16(length as u16).await?;
+                    stream.write_all(&buffer[..length]).await?;
+                    stream.flush().await?;
+                }
+                false => {
+                    let length = stream.read_u16().await? as usize;
+                    let mut message = vec![0u8; length];
+                    stream.read_exact(&mut message).await?;
+                    noise.read_message(&message, &mut buffer)?;
+                }
+            }
+        }
+
+        // Retrieve the public key of the peer, which it has proven possession of.
+        let peer_public_key = match noise.get_remote_static() {
+            Some(public_key) => NodePublicKey::from_bytes(public_key)?,
+            None => return Err(anyhow!("Noise handshake completed without a peer public key")),
+        };
+
+        let codec = Self {
+            transport: Some(Box::new(noise.into_transport_mode()?)),
+            message_codec: Message::PeerRequest,
+            plaintext: Default::default(),
+            buffer,
+        };
+        Ok((codec, peer_public_key))
+    }
+}
+
+impl<N: Network, E: Environment> Default for NoiseCodec<N, E> {
+    /// Returns a codec that rejects all messages until the handshake has completed.
+    fn default() -> Self {
+        Self {
+            transport: None,
+            message_codec: Message::PeerRequest,
+            plaintext: Default::default(),
+            buffer: Default::default(),
+        }
+    }
+}
+
+impl<N: Network, E: Environment> Encoder<Message<N, E>> for NoiseCodec<N, E> {
+    type Error = anyhow::Error;
+
+    fn encode(&mut self, message: Message<N, E>, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let transport = match self.transport.as_mut() {
+            Some(transport) => transport,
+            None => return Err(anyhow!("Attempted to send '{}' before the Noise handshake", message.name())),
+        };
+
+        // Encode the message into a length-delimited plaintext.
+        let mut plaintext = BytesMut::new();
+        self.message_codec.encode(message, &mut plaintext)?;
+
+        // Encrypt the plaintext into one or more Noise messages.
+        for chunk in plaintext.chunks(MAXIMUM_NOISE_PAYLOAD_SIZE) {
+            let length = transport.write_message(chunk, &mut self.buffer)?;
+            dst.reserve(2 + length);
+            dst.put_u16(length as u16);
+            dst.extend_from_slice(&self.buffer[..length]);
+        }
+        Ok(())
+    }
+}
+
+impl<N: Network, E: Environment> Decoder for NoiseCodec<N, E> {
+    type Error = std::io::Error;
+    type Item = Message<N, E>;
+
+    fn decode(&mut self, source: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let transport = match self.transport.as_mut() {
+            Some(transport) => transport,
+            None => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "Received a message before the Noise handshake",
+                ));
+            }
+        };
+
+        loop {
+            // Return the next message, if the decrypted bytes contain it in full.
+            if let Some(message) = self.message_codec.decode(&mut self.plaintext)? {
+                return Ok(Some(message));
+            }
+
+            // Ensure there is enough bytes to read the length marker of the next Noise message.
+            if source.len() < 2 {
+                return Ok(None);
+            }
+            let length = u16::from_be_bytes([source[0], source[1]]) as usize;
+
+            if source.len() < 2 + length {
+                // The full Noise message has not yet arrived.
+                source.reserve(2 + length - source.len());
+                return Ok(None);
+            }
+
+            // Decrypt the Noise message, which fails if it was not authenticated by the peer.
+            let num_bytes = match transport.read_message(&source[2..][..length], &mut self.buffer) {
+                Ok(num_bytes) => num_bytes,
+                Err(error) => return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, error)),
+            };
+            self.plaintext.extend_from_slice(&self.buffer[..num_bytes]);
+
+            // Use `advance` to modify the source such that it no longer contains this Noise message.
+            source.advance(2 + length);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Client;
+    use snarkvm::dpc::testnet2::Testnet2;
+
+    type TestCodec = NoiseCodec<Testnet2, Client<Testnet2>>;
+
+    #[tokio::test]
+    async fn test_handshake() {
+        let initiator_keypair = NodeKeypair::new().unwrap();
+        let responder_keypair = NodeKeypair::new().unwrap();
+
+        let (mut initiator_stream, mut responder_stream) = tokio::io::duplex(MAXIMUM_NOISE_MESSAGE_SIZE);
+        let (initiator, responder) = tokio::join!(
+            TestCodec::handshake(&mut initiator_stream, &initiator_keypair, true),
+            TestCodec::handshake(&mut responder_stream, &responder_keypair, false)
+        );
+        let (mut initiator_codec, initiator_peer_public_key) = initiator.unwrap();
+        let (mut responder_codec, responder_peer_public_key) = responder.unwrap();
+
+        // Ensure each node learns the public key of the other.
+        assert_eq!(initiator_peer_public_key, responder_keypair.public_key());
+        assert_eq!(responder_peer_public_key, initiator_keypair.public_key());
+
+        // Ensure messages are encrypted and decrypted in both directions.
+        for _ in 0..2 {
+            let mut buffer = BytesMut::new();
+            initiator_codec.encode(Message::PeerRequest, &mut buffer).unwrap();
+            assert!(matches!(responder_codec.decode(&mut buffer), Ok(Some(Message::PeerRequest))));
+            assert!(buffer.is_empty());
+
+            let mut buffer = BytesMut::new();
+            responder_codec.encode(Message::Disconnect, &mut buffer).unwrap();
+            assert!(matches!(initiator_codec.decode(&mut buffer), Ok(Some(Message::Disconnect))));
+        }
+
+        // Ensure a tampered message is rejected.
+        let mut buffer = BytesMut::new();
+        initiator_codec.encode(Message::PeerRequest, &mut buffer).unwrap();
+        let last = buffer.len() - 1;
+        buffer[last] ^= 1;
+        assert!(responder_codec.decode(&mut buffer).is_err());
+    }
+
+    #[test]
+    fn test_codec_requires_handshake() {
+        let mut codec = TestCodec::default();
+        assert!(codec.encode(Message::PeerRequest, &mut BytesMut::new()).is_err());
+        assert!(codec.decode(&mut BytesMut::from(&[0u8, 1, 0][..])).is_err());
+    }
+}
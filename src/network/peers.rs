@@ -15,16 +15,25 @@
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
-    helpers::{State, Status, Tasks},
+    helpers::{AsyncLedgerReader, State, Status, Tasks},
+    AddressBook,
+    Ban,
+    BanList,
     Data,
     Environment,
-    LedgerReader,
     LedgerRequest,
     LedgerRouter,
     Message,
+    Misbehavior,
+    NodeIdentity,
     NodeType,
+    NoiseCodec,
+    PeerId,
+    PeerReputation,
     ProverRequest,
     ProverRouter,
+    Standing,
+    Subnet,
 };
 use snarkvm::dpc::prelude::*;
 
@@ -33,7 +42,7 @@ use futures::SinkExt;
 use rand::{prelude::IteratorRandom, rngs::OsRng, thread_rng, Rng};
 use std::{
     collections::{HashMap, HashSet},
-    net::SocketAddr,
+    net::{IpAddr, SocketAddr},
     sync::Arc,
     time::{Duration, Instant, SystemTime},
 };
@@ -67,25 +76,29 @@ type ConnectionResult = oneshot::Sender<Result<()>>;
 #[derive(Debug)]
 pub enum PeersRequest<N: Network, E: Environment> {
     /// Connect := (peer_ip, ledger_reader, ledger_router, prover_router, connection_result)
-    Connect(SocketAddr, LedgerReader<N>, LedgerRouter<N>, ProverRouter<N>, ConnectionResult),
+    Connect(SocketAddr, AsyncLedgerReader<N>, LedgerRouter<N>, ProverRouter<N>, ConnectionResult),
     /// Heartbeat := (ledger_reader, ledger_router, prover_router)
-    Heartbeat(LedgerReader<N>, LedgerRouter<N>, ProverRouter<N>),
+    Heartbeat(AsyncLedgerReader<N>, LedgerRouter<N>, ProverRouter<N>),
     /// MessagePropagate := (peer_ip, message)
     MessagePropagate(SocketAddr, Message<N, E>),
     /// MessageSend := (peer_ip, message)
     MessageSend(SocketAddr, Message<N, E>),
     /// PeerConnecting := (stream, peer_ip, ledger_reader, ledger_router, prover_router)
-    PeerConnecting(TcpStream, SocketAddr, LedgerReader<N>, LedgerRouter<N>, ProverRouter<N>),
-    /// PeerConnected := (peer_ip, peer_nonce, outbound_router)
-    PeerConnected(SocketAddr, u64, OutboundRouter<N, E>),
+    PeerConnecting(TcpStream, SocketAddr, AsyncLedgerReader<N>, LedgerRouter<N>, ProverRouter<N>),
+    /// PeerConnected := (peer_ip, peer_id, is_initiator, outbound_router)
+    PeerConnected(SocketAddr, PeerId<N>, bool, OutboundRouter<N, E>),
+    /// PeerConnectionFailed := (peer_ip)
+    PeerConnectionFailed(SocketAddr),
+    /// PeerMisbehaved := (peer_ip, misbehavior)
+    PeerMisbehaved(SocketAddr, Misbehavior),
     /// PeerDisconnected := (peer_ip)
     PeerDisconnected(SocketAddr),
     /// PeerRestricted := (peer_ip)
     PeerRestricted(SocketAddr),
     /// SendPeerResponse := (peer_ip)
     SendPeerResponse(SocketAddr),
-    /// ReceivePeerResponse := (\[peer_ip\])
-    ReceivePeerResponse(Vec<SocketAddr>),
+    /// ReceivePeerResponse := (peer_ip, \[peer_ip\])
+    ReceivePeerResponse(SocketAddr, Vec<SocketAddr>),
 }
 
 ///
@@ -98,14 +111,20 @@ pub struct Peers<N: Network, E: Environment> {
     local_ip: SocketAddr,
     /// The local nonce for this node session.
     local_nonce: u64,
+    /// The local identity of this node.
+    local_identity: NodeIdentity<N>,
     /// The local status of this node.
     local_status: Status,
-    /// The map connected peer IPs to their nonce and outbound message router.
-    connected_peers: RwLock<HashMap<SocketAddr, (u64, OutboundRouter<N, E>)>>,
-    /// The set of candidate peer IPs.
-    candidate_peers: RwLock<HashSet<SocketAddr>>,
-    /// The set of restricted peer IPs.
-    restricted_peers: RwLock<HashMap<SocketAddr, Instant>>,
+    /// The map of connected peer IDs to their IP and outbound message router.
+    connected_peers: RwLock<HashMap<PeerId<N>, (SocketAddr, OutboundRouter<N, E>)>>,
+    /// The address book of known peers, from which candidate peers are selected.
+    address_book: RwLock<AddressBook<E>>,
+    /// The list of banned IP addresses and subnets, which are refused on inbound and outbound connections.
+    ban_list: RwLock<BanList>,
+    /// The map of restricted peer IDs to the timestamp of their restriction.
+    restricted_peers: RwLock<HashMap<PeerId<N>, Instant>>,
+    /// The map of peer IDs to their reputation, which outlives their connections until it decays.
+    reputations: RwLock<HashMap<PeerId<N>, PeerReputation>>,
     /// The map of peers to their first-seen port number, number of attempts, and timestamp of the last inbound connection request.
     seen_inbound_connections: RwLock<HashMap<SocketAddr, ((u16, u32), SystemTime)>>,
     /// The map of peers to the timestamp of their last outbound connection request.
@@ -120,6 +139,9 @@ impl<N: Network, E: Environment> Peers<N, E> {
         tasks: Tasks<JoinHandle<()>>,
         local_ip: SocketAddr,
         local_nonce: Option<u64>,
+        local_identity: NodeIdentity<N>,
+        address_book: AddressBook<E>,
+        ban_list: BanList,
         local_status: &Status,
     ) -> Arc<Self> {
         // Initialize an mpsc channel for sending requests to the `Peers` struct.
@@ -136,10 +158,13 @@ impl<N: Network, E: Environment> Peers<N, E> {
             peers_router,
             local_ip,
             local_nonce,
+            local_identity,
             local_status: local_status.clone(),
             connected_peers: Default::default(),
-            candidate_peers: Default::default(),
+            address_book: RwLock::new(address_book),
+            ban_list: RwLock::new(ban_list),
             restricted_peers: Default::default(),
+            reputations: Default::default(),
             seen_inbound_connections: Default::default(),
             seen_outbound_connections: Default::default(),
         });
@@ -179,31 +204,87 @@ impl<N: Network, E: Environment> Peers<N, E> {
     /// Returns `true` if the node is connected to the given IP.
     ///
     pub async fn is_connected_to(&self, ip: SocketAddr) -> bool {
-        self.connected_peers.read().await.contains_key(&ip)
+        self.connected_peers.read().await.values().any(|(peer_ip, _)| *peer_ip == ip)
     }
 
     ///
-    /// Returns `true` if the given IP is restricted.
+    /// Returns the peer ID of the connected peer with the given IP, if it exists.
     ///
-    pub async fn is_restricted(&self, ip: SocketAddr) -> bool {
-        match self.restricted_peers.read().await.get(&ip) {
+    pub async fn connected_peer_id(&self, ip: SocketAddr) -> Option<PeerId<N>> {
+        self.connected_peers
+            .read()
+            .await
+            .iter()
+            .find(|(_, (peer_ip, _))| *peer_ip == ip)
+            .map(|(peer_id, _)| *peer_id)
+    }
+
+    ///
+    /// Returns the peer ID of this node.
+    ///
+    pub fn local_peer_id(&self) -> PeerId<N> {
+        self.local_identity.peer_id()
+    }
+
+    ///
+    /// Returns `true` if the given peer ID is restricted.
+    ///
+    pub async fn is_restricted(&self, peer_id: &PeerId<N>) -> bool {
+        match self.restricted_peers.read().await.get(peer_id) {
             Some(timestamp) => timestamp.elapsed().as_secs() < E::RADIO_SILENCE_IN_SECS,
             None => false,
         }
     }
 
+    ///
+    /// Returns `true` if the given IP is banned.
+    ///
+    pub async fn is_banned(&self, ip: IpAddr) -> bool {
+        self.ban_list.read().await.is_banned(ip)
+    }
+
+    ///
+    /// Returns the list of active bans.
+    ///
+    pub async fn banned(&self) -> Vec<Ban> {
+        self.ban_list.read().await.bans().cloned().collect()
+    }
+
+    ///
+    /// Bans the given IP address or subnet, and disconnects from the connected peers within it.
+    ///
+    pub async fn ban(&self, ban: Ban) -> Result<()> {
+        let subnet = ban.subnet;
+        self.ban_list.write().await.ban(ban)?;
+
+        for peer_ip in self.connected_peers().await {
+            if subnet.contains(peer_ip.ip()) {
+                info!("Disconnecting from {} (banned)", peer_ip);
+                self.send(peer_ip, Message::Disconnect).await;
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Lifts the ban of the given IP address or subnet, returning `true` if it was banned.
+    ///
+    pub async fn unban(&self, subnet: &Subnet) -> Result<bool> {
+        self.ban_list.write().await.unban(subnet)
+    }
+
     ///
     /// Returns the list of connected peers.
     ///
     pub async fn connected_peers(&self) -> Vec<SocketAddr> {
-        self.connected_peers.read().await.keys().copied().collect()
+        self.connected_peers.read().await.values().map(|(peer_ip, _)| *peer_ip).collect()
     }
 
     ///
-    /// Returns the list of candidate peers.
+    /// Returns the list of candidate peers, which are the known peers in the address book.
     ///
     pub async fn candidate_peers(&self) -> HashSet<SocketAddr> {
-        self.candidate_peers.read().await.clone()
+        self.address_book.read().await.addresses().copied().collect()
     }
 
     ///
@@ -211,7 +292,7 @@ impl<N: Network, E: Environment> Peers<N, E> {
     /// Returns the number of connected sync nodes.
     ///
     pub async fn connected_sync_nodes(&self) -> HashSet<SocketAddr> {
-        let connected_peers: HashSet<SocketAddr> = self.connected_peers.read().await.keys().into_iter().copied().collect();
+        let connected_peers: HashSet<SocketAddr> = self.connected_peers().await.into_iter().collect();
         let sync_nodes: HashSet<SocketAddr> = E::SYNC_NODES.iter().map(|ip| ip.parse().unwrap()).collect();
         connected_peers.intersection(&sync_nodes).copied().collect()
     }
@@ -221,7 +302,7 @@ impl<N: Network, E: Environment> Peers<N, E> {
     /// Returns the number of connected sync nodes.
     ///
     pub async fn number_of_connected_sync_nodes(&self) -> usize {
-        let connected_peers: HashSet<SocketAddr> = self.connected_peers.read().await.keys().into_iter().copied().collect();
+        let connected_peers: HashSet<SocketAddr> = self.connected_peers().await.into_iter().collect();
         let sync_nodes: HashSet<SocketAddr> = E::SYNC_NODES.iter().map(|ip| ip.parse().unwrap()).collect();
         connected_peers.intersection(&sync_nodes).count()
     }
@@ -237,21 +318,119 @@ impl<N: Network, E: Environment> Peers<N, E> {
     /// Returns the number of candidate peers.
     ///
     pub async fn number_of_candidate_peers(&self) -> usize {
-        self.candidate_peers.read().await.len()
+        self.address_book.read().await.len()
+    }
+
+    ///
+    /// Returns the number of candidate peers this node has successfully connected to.
+    ///
+    pub async fn number_of_tried_peers(&self) -> usize {
+        self.address_book.read().await.number_of_tried()
     }
 
     ///
-    /// Returns the list of nonces for the connected peers.
+    /// Returns the list of peer IDs for the connected peers.
     ///
-    pub(crate) async fn connected_nonces(&self) -> Vec<u64> {
+    pub(crate) async fn connected_peer_ids(&self) -> Vec<PeerId<N>> {
+        self.connected_peers.read().await.keys().copied().collect()
+    }
+
+    ///
+    /// Returns the list of peer IDs for the restricted peers, including the banned peers.
+    ///
+    pub(crate) async fn restricted_peer_ids(&self) -> Vec<PeerId<N>> {
+        let mut restricted_peer_ids: Vec<PeerId<N>> = self
+            .restricted_peers
+            .read()
+            .await
+            .iter()
+            .filter(|(_, timestamp)| timestamp.elapsed().as_secs() < E::RADIO_SILENCE_IN_SECS)
+            .map(|(peer_id, _)| *peer_id)
+            .collect();
+        restricted_peer_ids.extend(
+            self.reputations
+                .read()
+                .await
+                .iter()
+                .filter(|(_, reputation)| reputation.standing::<E>() == Standing::Banned)
+                .map(|(peer_id, _)| *peer_id),
+        );
+        restricted_peer_ids
+    }
+
+    ///
+    /// Returns the IP, peer ID, and reputation of each connected peer.
+    ///
+    pub async fn connected_peer_reputations(&self) -> Vec<(SocketAddr, PeerId<N>, PeerReputation)> {
+        let reputations = self.reputations.read().await;
         self.connected_peers
             .read()
             .await
-            .values()
-            .map(|(peer_nonce, _)| *peer_nonce)
+            .iter()
+            .map(|(peer_id, (peer_ip, _))| (*peer_ip, *peer_id, reputations.get(peer_id).cloned().unwrap_or_default()))
             .collect()
     }
 
+    ///
+    /// Records the given misbehavior of the connected peer with the given IP, and disconnects
+    /// from the peer if its reputation score has reached the disconnect or ban threshold.
+    ///
+    async fn add_misbehavior(&self, peer_ip: SocketAddr, misbehavior: Misbehavior) {
+        let peer_id = match self.connected_peer_id(peer_ip).await {
+            Some(peer_id) => peer_id,
+            None => {
+                trace!("Skipping misbehavior of a non-connected peer {} ({})", peer_ip, misbehavior);
+                return;
+            }
+        };
+
+        let mut reputations = self.reputations.write().await;
+        let reputation = reputations.entry(peer_id).or_default();
+        let standing = reputation.record::<E>(misbehavior);
+        let score = reputation.score::<E>();
+        drop(reputations);
+
+        debug!(
+            "Peer {} misbehaved ({}), its reputation score is {:.1}",
+            peer_ip, misbehavior, score
+        );
+        match standing {
+            Standing::Good | Standing::Deprioritized => (),
+            Standing::Disconnected => {
+                info!("Disconnecting from {} (reputation score of {:.1})", peer_ip, score);
+                self.send(peer_ip, Message::Disconnect).await;
+            }
+            Standing::Banned => {
+                info!("Disconnecting from and banning {} (reputation score of {:.1})", peer_ip, score);
+                self.send(peer_ip, Message::Disconnect).await;
+            }
+        }
+    }
+
+    ///
+    /// Removes the reputations of disconnected peers that have decayed to a perfect score.
+    ///
+    async fn remove_expired_reputations(&self) {
+        let connected_peer_ids = self.connected_peer_ids().await;
+        self.reputations
+            .write()
+            .await
+            .retain(|peer_id, reputation| reputation.score::<E>() >= 1.0 || connected_peer_ids.contains(peer_id));
+    }
+
+    ///
+    /// Restricts the connected peer with the given IP, by its peer ID.
+    ///
+    async fn restrict(&self, peer_ip: SocketAddr) {
+        match self.connected_peer_id(peer_ip).await {
+            Some(peer_id) => {
+                // Add an entry for this `Peer` in the restricted peers.
+                self.restricted_peers.write().await.insert(peer_id, Instant::now());
+            }
+            None => warn!("Attempted to restrict a non-connected peer {}", peer_ip),
+        }
+    }
+
     ///
     /// Performs the given `request` to the peers.
     /// All requests must go through this `update`, so that a unified view is preserved.
@@ -265,6 +444,10 @@ impl<N: Network, E: Environment> Peers<N, E> {
                 {
                     debug!("Skipping connection request to {} (attempted to self-connect)", peer_ip);
                 }
+                // Ensure the peer IP is not banned.
+                else if self.is_banned(peer_ip.ip()).await {
+                    debug!("Skipping connection request to {} (banned)", peer_ip);
+                }
                 // Ensure the node does not surpass the maximum number of peer connections.
                 else if self.number_of_connected_peers().await >= E::MAXIMUM_NUMBER_OF_PEERS {
                     debug!("Skipping connection request to {} (maximum peers reached)", peer_ip);
@@ -273,10 +456,6 @@ impl<N: Network, E: Environment> Peers<N, E> {
                 else if self.is_connected_to(peer_ip).await {
                     debug!("Skipping connection request to {} (already connected)", peer_ip);
                 }
-                // Ensure the peer is not restricted.
-                else if self.is_restricted(peer_ip).await {
-                    debug!("Skipping connection request to {} (restricted)", peer_ip);
-                }
                 // Attempt to open a TCP stream.
                 else {
                     // Lock seen_outbound_connections for further processing.
@@ -295,6 +474,9 @@ impl<N: Network, E: Environment> Peers<N, E> {
                         // Release the lock over seen_outbound_connections.
                         drop(seen_outbound_connections);
 
+                        // Record the connection attempt in the address book.
+                        self.address_book.write().await.record_attempt(peer_ip);
+
                         // Initialize the peer handler.
                         match timeout(Duration::from_millis(E::CONNECTION_TIMEOUT_IN_MILLIS), TcpStream::connect(peer_ip)).await {
                             Ok(stream) => match stream {
@@ -303,12 +485,14 @@ impl<N: Network, E: Environment> Peers<N, E> {
                                         stream,
                                         self.local_ip,
                                         self.local_nonce,
+                                        self.local_identity.clone(),
                                         self.local_status.clone(),
                                         &self.peers_router,
                                         ledger_reader,
                                         ledger_router,
                                         prover_router,
-                                        self.connected_nonces().await,
+                                        self.connected_peer_ids().await,
+                                        self.restricted_peer_ids().await,
                                         Some(connection_result),
                                         tasks.clone(),
                                     )
@@ -316,18 +500,29 @@ impl<N: Network, E: Environment> Peers<N, E> {
                                 }
                                 Err(error) => {
                                     trace!("Failed to connect to '{}': '{:?}'", peer_ip, error);
-                                    self.candidate_peers.write().await.remove(&peer_ip);
+                                    self.address_book.write().await.record_failure(peer_ip);
                                 }
                             },
                             Err(error) => {
                                 error!("Unable to reach '{}': '{:?}'", peer_ip, error);
-                                self.candidate_peers.write().await.remove(&peer_ip);
+                                self.address_book.write().await.record_failure(peer_ip);
                             }
                         };
                     }
                 }
             }
             PeersRequest::Heartbeat(ledger_reader, ledger_router, prover_router) => {
+                // Persist the address book, if it is due.
+                if let Err(error) = self.address_book.write().await.save_periodically() {
+                    warn!("Failed to save the address book: {}", error);
+                }
+                // Remove the reputations that have decayed.
+                self.remove_expired_reputations().await;
+                // Remove the bans that have expired.
+                if let Err(error) = self.ban_list.write().await.remove_expired() {
+                    warn!("Failed to save the ban list: {}", error);
+                }
+
                 // Obtain the number of connected peers.
                 let number_of_connected_peers = self.number_of_connected_peers().await;
 
@@ -335,27 +530,31 @@ impl<N: Network, E: Environment> Peers<N, E> {
                 if number_of_connected_peers > E::MAXIMUM_NUMBER_OF_PEERS {
                     debug!("Exceeded maximum number of connected peers");
 
-                    // Determine the peers to disconnect from.
+                    // Determine the peers to disconnect from, starting with the peers with the worst reputation.
                     let num_excess_peers = number_of_connected_peers.saturating_sub(E::MAXIMUM_NUMBER_OF_PEERS);
-                    let peer_ips_to_disconnect = self
-                        .connected_peers
-                        .read()
+                    let mut connected_peers = self
+                        .connected_peer_reputations()
                         .await
-                        .iter()
-                        .filter(|(&peer_ip, _)| {
+                        .into_iter()
+                        .map(|(peer_ip, _, reputation)| (peer_ip, reputation.score::<E>()))
+                        .collect::<Vec<_>>();
+                    connected_peers.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+                    let peer_ips_to_disconnect = connected_peers
+                        .into_iter()
+                        .map(|(peer_ip, _)| peer_ip)
+                        .filter(|peer_ip| {
                             let peer_str = peer_ip.to_string();
                             !E::SYNC_NODES.contains(&peer_str.as_str()) && !E::BEACON_NODES.contains(&peer_str.as_str())
                         })
                         .take(num_excess_peers)
-                        .map(|(&peer_ip, _)| peer_ip)
                         .collect::<Vec<SocketAddr>>();
 
                     // Proceed to send disconnect requests to these peers.
                     for peer_ip in peer_ips_to_disconnect {
                         info!("Disconnecting from {} (exceeded maximum connections)", peer_ip);
-                        self.send(peer_ip, Message::Disconnect).await;
                         // Add an entry for this `Peer` in the restricted peers.
-                        self.restricted_peers.write().await.insert(peer_ip, Instant::now());
+                        self.restrict(peer_ip).await;
+                        self.send(peer_ip, Message::Disconnect).await;
                     }
                 }
 
@@ -372,9 +571,9 @@ impl<N: Network, E: Environment> Peers<N, E> {
                         .choose_multiple(&mut OsRng::default(), num_excess_sync_nodes)
                     {
                         info!("Disconnecting from {} (exceeded maximum connections)", peer_ip);
-                        self.send(peer_ip, Message::Disconnect).await;
                         // Add an entry for this `Peer` in the restricted peers.
-                        self.restricted_peers.write().await.insert(peer_ip, Instant::now());
+                        self.restrict(peer_ip).await;
+                        self.send(peer_ip, Message::Disconnect).await;
                     }
                 }
 
@@ -382,9 +581,17 @@ impl<N: Network, E: Environment> Peers<N, E> {
                 match number_of_connected_peers < E::MINIMUM_NUMBER_OF_PEERS {
                     true => {
                         trace!("Sending request for more peer connections");
-                        // Request more peers if the number of connected peers is below the threshold.
-                        for peer_ip in self.connected_peers().await.iter().choose_multiple(&mut OsRng::default(), 3) {
-                            self.send(*peer_ip, Message::PeerRequest).await;
+                        // Request more peers if the number of connected peers is below the threshold,
+                        // skipping the peers that are deprioritized for their reputation.
+                        for peer_ip in self
+                            .connected_peer_reputations()
+                            .await
+                            .into_iter()
+                            .filter(|(_, _, reputation)| reputation.standing::<E>() == Standing::Good)
+                            .map(|(peer_ip, _, _)| peer_ip)
+                            .choose_multiple(&mut OsRng::default(), 3)
+                        {
+                            self.send(peer_ip, Message::PeerRequest).await;
                         }
                     }
                     false => return,
@@ -393,23 +600,27 @@ impl<N: Network, E: Environment> Peers<N, E> {
                 // Add the sync nodes to the list of candidate peers.
                 let sync_nodes: Vec<SocketAddr> = E::SYNC_NODES.iter().map(|ip| ip.parse().unwrap()).collect();
                 if number_of_connected_sync_nodes == 0 {
-                    self.add_candidate_peers(&sync_nodes).await;
+                    self.add_candidate_peers(&sync_nodes, None).await;
                 }
 
                 // Add the beacon nodes to the list of candidate peers.
                 let beacon_nodes: Vec<SocketAddr> = E::BEACON_NODES.iter().map(|ip| ip.parse().unwrap()).collect();
-                self.add_candidate_peers(&beacon_nodes).await;
+                self.add_candidate_peers(&beacon_nodes, None).await;
 
                 // Attempt to connect to more peers if the number of connected peers is below the minimum threshold.
-                // Select the peers randomly from the list of candidate peers.
+                // Select the peers from the address book, with a bias towards peers that were previously reachable.
                 let midpoint_number_of_peers = E::MINIMUM_NUMBER_OF_PEERS.saturating_add(E::MAXIMUM_NUMBER_OF_PEERS) / 2;
-                for peer_ip in self
-                    .candidate_peers()
+                let connected_peers: HashSet<SocketAddr> = self.connected_peers().await.into_iter().collect();
+                let ban_list = self.ban_list.read().await;
+                let candidate_peers = self
+                    .address_book
+                    .read()
                     .await
-                    .iter()
-                    .copied()
-                    .choose_multiple(&mut OsRng::default(), midpoint_number_of_peers)
-                {
+                    .select_candidates(midpoint_number_of_peers, |peer_ip| {
+                        connected_peers.contains(peer_ip) || ban_list.is_banned(peer_ip.ip())
+                    });
+                drop(ban_list);
+                for peer_ip in candidate_peers {
                     // Ensure this node is not connected to more than the permitted number of sync nodes.
                     if sync_nodes.contains(&peer_ip) && number_of_connected_sync_nodes >= 1 {
                         continue;
@@ -453,10 +664,6 @@ impl<N: Network, E: Environment> Peers<N, E> {
                 else if self.is_connected_to(peer_ip).await {
                     debug!("Dropping connection request from {} (already connected)", peer_ip);
                 }
-                // Ensure the peer is not restricted.
-                else if self.is_restricted(peer_ip).await {
-                    debug!("Dropping connection request from {} (restricted)", peer_ip);
-                }
                 // Spawn a handler to be run asynchronously.
                 else {
                     // Sanitize the port from the peer, if it is a remote IP address.
@@ -489,8 +696,6 @@ impl<N: Network, E: Environment> Peers<N, E> {
                     // Ensure the connecting peer has not surpassed the connection attempt limit.
                     if *initial_port < peer_port && *num_attempts > E::MAXIMUM_CONNECTION_FAILURES {
                         trace!("Dropping connection request from {} (tried {} secs ago)", peer_ip, elapsed);
-                        // Add an entry for this `Peer` in the restricted peers.
-                        self.restricted_peers.write().await.insert(peer_ip, Instant::now());
                     } else {
                         debug!("Received a connection request from {}", peer_ip);
                         // Update the number of attempts for this peer.
@@ -504,12 +709,14 @@ impl<N: Network, E: Environment> Peers<N, E> {
                             stream,
                             self.local_ip,
                             self.local_nonce,
+                            self.local_identity.clone(),
                             self.local_status.clone(),
                             &self.peers_router,
                             ledger_reader,
                             ledger_router,
                             prover_router,
-                            self.connected_nonces().await,
+                            self.connected_peer_ids().await,
+                            self.restricted_peer_ids().await,
                             None,
                             tasks.clone(),
                         )
@@ -517,55 +724,80 @@ impl<N: Network, E: Environment> Peers<N, E> {
                     }
                 }
             }
-            PeersRequest::PeerConnected(peer_ip, peer_nonce, outbound) => {
+            PeersRequest::PeerConnected(peer_ip, peer_id, is_initiator, outbound) => {
                 // Add an entry for this `Peer` in the connected peers.
-                self.connected_peers.write().await.insert(peer_ip, (peer_nonce, outbound));
-                // Remove an entry for this `Peer` in the candidate peers, if it exists.
-                self.candidate_peers.write().await.remove(&peer_ip);
+                self.connected_peers.write().await.insert(peer_id, (peer_ip, outbound));
+                // Update the address book, where only the addresses this node has connected to are considered tried.
+                match is_initiator {
+                    true => self.address_book.write().await.record_success(peer_ip),
+                    false => self.add_candidate_peers(&[peer_ip], None).await,
+                }
+            }
+            PeersRequest::PeerConnectionFailed(peer_ip) => {
+                // Record the failed connection in the address book.
+                self.address_book.write().await.record_failure(peer_ip);
+            }
+            PeersRequest::PeerMisbehaved(peer_ip, misbehavior) => {
+                // Update the reputation of the peer.
+                self.add_misbehavior(peer_ip, misbehavior).await;
             }
             PeersRequest::PeerDisconnected(peer_ip) => {
                 // Remove an entry for this `Peer` in the connected peers, if it exists.
-                self.connected_peers.write().await.remove(&peer_ip);
-                // Add an entry for this `Peer` in the candidate peers.
-                self.candidate_peers.write().await.insert(peer_ip);
+                self.connected_peers.write().await.retain(|_, (ip, _)| *ip != peer_ip);
+                // Add an entry for this `Peer` in the address book, if it does not exist.
+                self.add_candidate_peers(&[peer_ip], None).await;
             }
             PeersRequest::PeerRestricted(peer_ip) => {
-                // Remove an entry for this `Peer` in the connected peers, if it exists.
-                self.connected_peers.write().await.remove(&peer_ip);
                 // Add an entry for this `Peer` in the restricted peers.
-                self.restricted_peers.write().await.insert(peer_ip, Instant::now());
+                self.restrict(peer_ip).await;
+                // Remove an entry for this `Peer` in the connected peers, if it exists.
+                self.connected_peers.write().await.retain(|_, (ip, _)| *ip != peer_ip);
             }
             PeersRequest::SendPeerResponse(recipient) => {
-                // Send a `PeerResponse` message.
-                let connected_peers = self.connected_peers().await;
-                self.send(recipient, Message::PeerResponse(connected_peers)).await;
+                // Send a `PeerResponse` message, with a sample of the known peers in the address book.
+                let peer_ips = self
+                    .address_book
+                    .read()
+                    .await
+                    .sample_addresses(E::MAXIMUM_PEER_RESPONSE_SIZE, |peer_ip| *peer_ip == recipient);
+                self.send(recipient, Message::PeerResponse(peer_ips)).await;
             }
-            PeersRequest::ReceivePeerResponse(peer_ips) => {
-                self.add_candidate_peers(&peer_ips).await;
+            PeersRequest::ReceivePeerResponse(peer_ip, peer_ips) => {
+                self.add_candidate_peers(&peer_ips, Some(peer_ip.ip())).await;
             }
         }
     }
 
     ///
-    /// Adds the given peer IPs to the set of candidate peers.
+    /// Adds the given peer IPs to the address book, which were shared by the peer at the given source IP, if any.
     ///
-    /// This method skips adding any given peers if the combined size exceeds the threshold,
+    /// This method skips adding any given peers if their number exceeds the size of a `PeerResponse`,
     /// as the peer providing this list could be subverting the protocol.
     ///
-    async fn add_candidate_peers(&self, peers: &[SocketAddr]) {
-        // Acquire the candidate peers write lock.
-        let mut candidate_peers = self.candidate_peers.write().await;
-        // Ensure the combined number of peers does not surpass the threshold.
-        if candidate_peers.len() + peers.len() < E::MAXIMUM_CANDIDATE_PEERS {
-            // Proceed to insert each new candidate peer IP.
-            for peer_ip in peers.iter().take(E::MAXIMUM_CANDIDATE_PEERS) {
-                // Ensure the peer is not self and is a new candidate peer.
-                let is_self = *peer_ip == self.local_ip
-                    || (peer_ip.ip().is_unspecified() || peer_ip.ip().is_loopback()) && peer_ip.port() == self.local_ip.port();
-                if !is_self && !self.is_connected_to(*peer_ip).await {
-                    candidate_peers.insert(*peer_ip);
-                }
-            }
+    async fn add_candidate_peers(&self, peers: &[SocketAddr], source: Option<IpAddr>) {
+        // Ensure the number of peers does not surpass the threshold.
+        if peers.len() > E::MAXIMUM_PEER_RESPONSE_SIZE {
+            return;
+        }
+        // Filter out the peer IPs of this node.
+        let peers: Vec<SocketAddr> = peers
+            .iter()
+            .filter(|peer_ip| {
+                **peer_ip != self.local_ip
+                    && !((peer_ip.ip().is_unspecified() || peer_ip.ip().is_loopback()) && peer_ip.port() == self.local_ip.port())
+            })
+            .copied()
+            .collect();
+        // Proceed to insert each new candidate peer IP.
+        self.address_book.write().await.add_addresses(&peers, source);
+    }
+
+    ///
+    /// Writes the address book to disk.
+    ///
+    pub(crate) async fn save_address_book(&self) {
+        if let Err(error) = self.address_book.write().await.save() {
+            warn!("Failed to save the address book: {}", error);
         }
     }
 
@@ -573,12 +805,18 @@ impl<N: Network, E: Environment> Peers<N, E> {
     /// Sends the given message to specified peer.
     ///
     async fn send(&self, peer: SocketAddr, message: Message<N, E>) {
-        let target_peer = self.connected_peers.read().await.get(&peer).cloned();
+        let target_peer = self
+            .connected_peers
+            .read()
+            .await
+            .values()
+            .find(|(peer_ip, _)| *peer_ip == peer)
+            .map(|(_, outbound)| outbound.clone());
         match target_peer {
-            Some((_, outbound)) => {
+            Some(outbound) => {
                 if let Err(error) = outbound.send(message).await {
                     trace!("Outbound channel failed: {}", error);
-                    self.connected_peers.write().await.remove(&peer);
+                    self.connected_peers.write().await.retain(|_, (peer_ip, _)| *peer_ip != peer);
                 }
             }
             None => warn!("Attempted to send to a non-connected peer {}", peer),
@@ -616,8 +854,9 @@ impl<N: Network, E: Environment> Peers<N, E> {
     ///
     #[cfg(feature = "test")]
     pub async fn reset_known_peers(&self) {
-        self.candidate_peers.write().await.clear();
+        self.address_book.write().await.clear();
         self.restricted_peers.write().await.clear();
+        self.reputations.write().await.clear();
         self.seen_inbound_connections.write().await.clear();
         self.seen_outbound_connections.write().await.clear();
     }
@@ -639,8 +878,8 @@ struct Peer<N: Network, E: Environment> {
     block_header: BlockHeader<N>,
     /// The timestamp of the last message received from this peer.
     last_seen: Instant,
-    /// The TCP socket that handles sending and receiving data with this peer.
-    outbound_socket: Framed<TcpStream, Message<N, E>>,
+    /// The encrypted TCP socket that handles sending and receiving data with this peer.
+    outbound_socket: Framed<TcpStream, NoiseCodec<N, E>>,
     /// The `outbound_handler` half of the MPSC message channel, used to receive messages from peers.
     /// When a message is received on this `OutboundHandler`, it will be written to the socket.
     outbound_handler: OutboundHandler<N, E>,
@@ -656,28 +895,40 @@ struct Peer<N: Network, E: Environment> {
 
 impl<N: Network, E: Environment> Peer<N, E> {
     /// Create a new instance of `Peer`.
+    #[allow(clippy::too_many_arguments)]
     async fn new(
         stream: TcpStream,
         local_ip: SocketAddr,
         local_nonce: u64,
+        local_identity: &NodeIdentity<N>,
+        is_initiator: bool,
         local_status: &Status,
         peers_router: &PeersRouter<N, E>,
-        ledger_reader: &LedgerReader<N>,
-        connected_nonces: &[u64],
+        ledger_reader: &AsyncLedgerReader<N>,
+        connected_peer_ids: &[PeerId<N>],
+        restricted_peer_ids: &[PeerId<N>],
     ) -> Result<Self> {
+        // Retrieve the address of the peer, to report a handshake that times out.
+        let peer_addr = stream.peer_addr()?;
         // Construct the socket.
-        let mut outbound_socket = Framed::new(stream, Message::<N, E>::PeerRequest);
+        let mut outbound_socket = Framed::new(stream, NoiseCodec::<N, E>::default());
 
-        // Perform the handshake before proceeding.
-        let (peer_ip, peer_nonce, node_type, status) = Peer::handshake(
+        // Perform the handshake before proceeding, and treat a handshake that does not complete in time as a failed connection.
+        let handshake = Peer::handshake(
             &mut outbound_socket,
             local_ip,
             local_nonce,
+            local_identity,
+            is_initiator,
             local_status,
             ledger_reader.latest_cumulative_weight(),
-            connected_nonces,
-        )
-        .await?;
+            connected_peer_ids,
+            restricted_peer_ids,
+        );
+        let (peer_ip, peer_id, node_type, status) = match timeout(Duration::from_millis(E::CONNECTION_TIMEOUT_IN_MILLIS), handshake).await {
+            Ok(result) => result?,
+            Err(_) => return Err(anyhow!("Handshake with {} timed out after {} ms", peer_addr, E::CONNECTION_TIMEOUT_IN_MILLIS)),
+        };
 
         // Send the first `Ping` message to the peer.
         let message = Message::Ping(
@@ -696,7 +947,7 @@ impl<N: Network, E: Environment> Peer<N, E> {
 
         // Add an entry for this `Peer` in the connected peers.
         peers_router
-            .send(PeersRequest::PeerConnected(peer_ip, peer_nonce, outbound_router))
+            .send(PeersRequest::PeerConnected(peer_ip, peer_id, is_initiator, outbound_router))
             .await?;
 
         Ok(Peer {
@@ -727,18 +978,34 @@ impl<N: Network, E: Environment> Peer<N, E> {
         Ok(())
     }
 
-    /// Performs the handshake protocol, returning the listener IP and nonce of the peer upon success.
+    /// Performs the handshake protocol, returning the listener IP and peer ID of the peer upon success.
+    #[allow(clippy::too_many_arguments)]
     async fn handshake(
-        outbound_socket: &mut Framed<TcpStream, Message<N, E>>,
+        outbound_socket: &mut Framed<TcpStream, NoiseCodec<N, E>>,
         local_ip: SocketAddr,
         local_nonce: u64,
+        local_identity: &NodeIdentity<N>,
+        is_initiator: bool,
         local_status: &Status,
         local_cumulative_weight: u128,
-        connected_nonces: &[u64],
-    ) -> Result<(SocketAddr, u64, NodeType, Status)> {
+        connected_peer_ids: &[PeerId<N>],
+        restricted_peer_ids: &[PeerId<N>],
+    ) -> Result<(SocketAddr, PeerId<N>, NodeType, Status)> {
         // Get the IP address of the peer.
         let mut peer_ip = outbound_socket.get_ref().peer_addr()?;
 
+        // Perform the Noise handshake, which encrypts the connection and authenticates the identity of the peer.
+        let local_keypair = local_identity.noise_keypair();
+        let (codec, peer_public_key) = match NoiseCodec::handshake(outbound_socket.get_mut(), local_keypair, is_initiator).await {
+            Ok(result) => result,
+            Err(error) => return Err(anyhow!("Noise handshake with {} failed: {}", peer_ip, error)),
+        };
+        // Ensure the peer is not this node.
+        if peer_public_key == local_keypair.public_key() {
+            return Err(anyhow!("Attempted to connect to self (public key = {})", peer_public_key));
+        }
+        *outbound_socket.codec_mut() = codec;
+
         // Retrieve the genesis block header.
         let genesis_header = N::genesis_block().header();
 
@@ -751,12 +1018,13 @@ impl<N: Network, E: Environment> Peer<N, E> {
             local_ip.port(),
             local_nonce,
             local_cumulative_weight,
+            local_identity.peer_id(),
         );
         trace!("Sending '{}-A' to {}", message.name(), peer_ip);
         outbound_socket.send(message).await?;
 
         // Wait for the counterparty challenge request to come in.
-        let (peer_nonce, node_type, status) = match outbound_socket.next().await {
+        let (peer_id, node_type, status) = match outbound_socket.next().await {
             Some(Ok(message)) => {
                 // Process the message.
                 trace!("Received '{}-B' from {}", message.name(), peer_ip);
@@ -769,6 +1037,7 @@ impl<N: Network, E: Environment> Peer<N, E> {
                         listener_port,
                         peer_nonce,
                         peer_cumulative_weight,
+                        peer_id,
                     ) => {
                         // Ensure the message protocol version is not outdated.
                         if version < E::MESSAGE_VERSION {
@@ -800,12 +1069,16 @@ impl<N: Network, E: Environment> Peer<N, E> {
                             return Err(anyhow!("Dropping {} as this node is ahead", peer_ip));
                         }
                         // Ensure the peer is not this node.
-                        if local_nonce == peer_nonce {
-                            return Err(anyhow!("Attempted to connect to self (nonce = {})", peer_nonce));
+                        if peer_id == local_identity.peer_id() {
+                            return Err(anyhow!("Attempted to connect to self (peer ID = {})", peer_id));
                         }
                         // Ensure the peer is not already connected to this node.
-                        if connected_nonces.contains(&peer_nonce) {
-                            return Err(anyhow!("Already connected to a peer with nonce {}", peer_nonce));
+                        if connected_peer_ids.contains(&peer_id) {
+                            return Err(anyhow!("Already connected to a peer with ID {}", peer_id));
+                        }
+                        // Ensure the peer is not restricted.
+                        if restricted_peer_ids.contains(&peer_id) {
+                            return Err(anyhow!("Dropping {} (restricted peer ID {})", peer_ip, peer_id));
                         }
                         // Verify the listener port.
                         if peer_ip.port() != listener_port {
@@ -822,8 +1095,9 @@ impl<N: Network, E: Environment> Peer<N, E> {
                                 return Err(anyhow!("Unable to reach '{}': '{}'", peer_ip, error));
                             }
                         }
-                        // Send the challenge response.
-                        let message = Message::ChallengeResponse(Data::Object(genesis_header.clone()));
+                        // Send the challenge response, signing the nonce of the peer.
+                        let signature = local_identity.sign_challenge(peer_nonce)?;
+                        let message = Message::ChallengeResponse(signature, Data::Object(genesis_header.clone()));
                         trace!("Sending '{}-B' to {}", message.name(), peer_ip);
                         outbound_socket.send(message).await?;
 
@@ -831,7 +1105,7 @@ impl<N: Network, E: Environment> Peer<N, E> {
                         let status = Status::new();
                         status.update(peer_status);
 
-                        (peer_nonce, node_type, status)
+                        (peer_id, node_type, status)
                     }
                     message => {
                         return Err(anyhow!(
@@ -854,11 +1128,19 @@ impl<N: Network, E: Environment> Peer<N, E> {
                 // Process the message.
                 trace!("Received '{}-A' from {}", message.name(), peer_ip);
                 match message {
-                    Message::ChallengeResponse(block_header) => {
+                    Message::ChallengeResponse(signature, block_header) => {
+                        // Ensure the peer has signed the nonce of this node with the claimed peer ID.
+                        if !NodeIdentity::verify_challenge(&peer_id, local_nonce, &peer_public_key, &signature)? {
+                            return Err(anyhow!(
+                                "Challenge response from {} has an invalid signature for {}",
+                                peer_ip,
+                                peer_id
+                            ));
+                        }
                         // Perform the deferred non-blocking deserialization of the block header.
                         let block_header = block_header.deserialize().await?;
                         match &block_header == genesis_header {
-                            true => Ok((peer_ip, peer_nonce, node_type, status)),
+                            true => Ok((peer_ip, peer_id, node_type, status)),
                             false => Err(anyhow!("Challenge response from {} failed, received '{}'", peer_ip, block_header)),
                         }
                     }
@@ -882,17 +1164,27 @@ impl<N: Network, E: Environment> Peer<N, E> {
         stream: TcpStream,
         local_ip: SocketAddr,
         local_nonce: u64,
+        local_identity: NodeIdentity<N>,
         local_status: Status,
         peers_router: &PeersRouter<N, E>,
-        ledger_reader: LedgerReader<N>,
+        ledger_reader: AsyncLedgerReader<N>,
         ledger_router: LedgerRouter<N>,
         prover_router: ProverRouter<N>,
-        connected_nonces: Vec<u64>,
+        connected_peer_ids: Vec<PeerId<N>>,
+        restricted_peer_ids: Vec<PeerId<N>>,
         connection_result: Option<ConnectionResult>,
         tasks: Tasks<task::JoinHandle<()>>,
     ) {
         let peers_router = peers_router.clone();
 
+        // The node that opened the connection initiates the handshake.
+        let is_initiator = connection_result.is_some();
+        // Retrieve the address this node connected to, if this node initiated the connection.
+        let initiated_peer_ip = match is_initiator {
+            true => stream.peer_addr().ok(),
+            false => None,
+        };
+
         let tasks_clone = tasks.clone();
         tasks.append(task::spawn(async move {
             // Register our peer with state which internally sets up some channels.
@@ -900,10 +1192,13 @@ impl<N: Network, E: Environment> Peer<N, E> {
                 stream,
                 local_ip,
                 local_nonce,
+                &local_identity,
+                is_initiator,
                 &local_status,
                 &peers_router,
                 &ledger_reader,
-                &connected_nonces,
+                &connected_peer_ids,
+                &restricted_peer_ids,
             )
             .await
             {
@@ -918,6 +1213,12 @@ impl<N: Network, E: Environment> Peer<N, E> {
                 }
                 Err(error) => {
                     trace!("{}", error);
+                    // If this node initiated the connection, record the failed connection in the address book.
+                    if let Some(peer_ip) = initiated_peer_ip {
+                        if let Err(error) = peers_router.send(PeersRequest::PeerConnectionFailed(peer_ip)).await {
+                            warn!("[PeerConnectionFailed] {}", error);
+                        }
+                    }
                     // If the optional connection result router is given, report a failed connection result.
                     if let Some(router) = connection_result {
                         if router.send(Err(error)).is_err() {
@@ -1020,31 +1321,30 @@ impl<N: Network, E: Environment> Peer<N, E> {
                             trace!("Received '{}' from {}", message.name(), peer_ip);
                             match message {
                                 Message::BlockRequest(start_block_height, end_block_height) => {
-                                    // Ensure the request is within the accepted limits.
+                                    // Ensure the request is well-formed, within the accepted limits, and within the canonical chain.
                                     let number_of_blocks = end_block_height.saturating_sub(start_block_height);
-                                    if number_of_blocks > E::MAXIMUM_BLOCK_REQUEST {
-                                        // Route a `Failure` to the ledger.
-                                        let failure = format!("Attempted to request {} blocks", number_of_blocks);
-                                        if let Err(error) = ledger_router.send(LedgerRequest::Failure(peer_ip, failure)).await {
-                                            warn!("[Failure] {}", error);
+                                    let latest_block_height = ledger_reader.latest_block_height();
+                                    if start_block_height > end_block_height || number_of_blocks > E::MAXIMUM_BLOCK_REQUEST || end_block_height > latest_block_height {
+                                        // Route a `PeerMisbehaved` to the peers.
+                                        warn!("Peer {} attempted to request blocks {} to {} (latest block is {})", peer_ip, start_block_height, end_block_height, latest_block_height);
+                                        if let Err(error) = peers_router.send(PeersRequest::PeerMisbehaved(peer_ip, Misbehavior::MalformedMessage)).await {
+                                            warn!("[PeerMisbehaved] {}", error);
                                         }
                                         continue;
                                     }
-                                    // Retrieve the requested blocks.
-                                    let blocks = match ledger_reader.get_blocks(start_block_height, end_block_height) {
+                                    // Retrieve the requested blocks, in serialized form.
+                                    // A failure here is local to this node, so the peer is not penalized for it.
+                                    let blocks = match ledger_reader.run(move |ledger| ledger.get_serialized_blocks(start_block_height, end_block_height)).await {
                                         Ok(blocks) => blocks,
                                         Err(error) => {
-                                            // Route a `Failure` to the ledger.
-                                            if let Err(error) = ledger_router.send(LedgerRequest::Failure(peer_ip, format!("{}", error))).await {
-                                                warn!("[Failure] {}", error);
-                                            }
+                                            warn!("Failed to retrieve blocks {} to {} for {}: {}", start_block_height, end_block_height, peer_ip, error);
                                             continue;
                                         }
                                     };
                                     // Send a `BlockResponse` message for each block to the peer.
-                                    for block in blocks {
-                                        debug!("Sending 'BlockResponse {}' to {}", block.height(), peer_ip);
-                                        if let Err(error) = peer.outbound_socket.send(Message::BlockResponse(Data::Object(block))).await {
+                                    for (block_height, block) in (start_block_height..=end_block_height).zip(blocks) {
+                                        debug!("Sending 'BlockResponse {}' to {}", block_height, peer_ip);
+                                        if let Err(error) = peer.outbound_socket.send(Message::BlockResponse(Data::Buffer(block))).await {
                                             warn!("[BlockResponse] {}", error);
                                             break;
                                         }
@@ -1052,14 +1352,23 @@ impl<N: Network, E: Environment> Peer<N, E> {
                                 },
                                 Message::BlockResponse(block) => {
                                     // Perform the deferred non-blocking deserialization of the block.
-                                    match block.deserialize().await {
+                                    let request = match block.deserialize().await {
+                                        // Ensure the block header is valid, on a dedicated thread, as its proof of work is expensive to verify.
+                                        Ok(block) => match task::spawn_blocking(move || (block.header().is_valid(), block)).await {
+                                            Ok((true, block)) => Ok(LedgerRequest::BlockResponse(peer_ip, block, prover_router.clone())),
+                                            _ => Err(Misbehavior::InvalidBlock),
+                                        },
+                                        Err(_) => Err(Misbehavior::MalformedMessage),
+                                    };
+
+                                    match request {
                                         // Route the `BlockResponse` to the ledger.
-                                        Ok(block) => if let Err(error) = ledger_router.send(LedgerRequest::BlockResponse(peer_ip, block, prover_router.clone())).await {
+                                        Ok(request) => if let Err(error) = ledger_router.send(request).await {
                                             warn!("[BlockResponse] {}", error);
                                         },
-                                        // Route the `Failure` to the ledger.
-                                        Err(error) => if let Err(error) = ledger_router.send(LedgerRequest::Failure(peer_ip, format!("{}", error))).await {
-                                            warn!("[Failure] {}", error);
+                                        // Route the `PeerMisbehaved` to the peers.
+                                        Err(misbehavior) => if let Err(error) = peers_router.send(PeersRequest::PeerMisbehaved(peer_ip, misbehavior)).await {
+                                            warn!("[PeerMisbehaved] {}", error);
                                         }
                                     }
                                 }
@@ -1077,7 +1386,7 @@ impl<N: Network, E: Environment> Peer<N, E> {
                                 }
                                 Message::PeerResponse(peer_ips) => {
                                     // Adds the given peer IPs to the list of candidate peers.
-                                    if let Err(error) = peers_router.send(PeersRequest::ReceivePeerResponse(peer_ips)).await {
+                                    if let Err(error) = peers_router.send(PeersRequest::ReceivePeerResponse(peer_ip, peer_ips)).await {
                                         warn!("[PeerResponse] {}", error);
                                     }
                                 }
@@ -1118,27 +1427,30 @@ impl<N: Network, E: Environment> Peer<N, E> {
                                     peer.status.update(status);
 
                                     // Determine if the peer is on a fork (or unknown).
-                                    let is_fork = match ledger_reader.get_block_hash(peer.block_header.height()) {
+                                    let block_height = peer.block_header.height();
+                                    let is_fork = match ledger_reader.run(move |ledger| ledger.get_block_hash(block_height)).await {
                                         Ok(expected_block_hash) => Some(expected_block_hash != block_hash),
                                         Err(_) => None,
                                     };
                                     // Send a `Pong` message to the peer.
-                                    if let Err(error) = peer.send(Message::Pong(is_fork, Data::Object(ledger_reader.latest_block_locators()))).await {
+                                    if let Err(error) = peer.send(Message::Pong(is_fork, ledger_reader.pruned_block_height(), Data::Object(ledger_reader.latest_block_locators()))).await {
                                         warn!("[Pong] {}", error);
                                     }
                                 },
-                                Message::Pong(is_fork, block_locators) => {
+                                Message::Pong(is_fork, pruned_block_height, block_locators) => {
                                     // Perform the deferred non-blocking deserialization of block locators.
-                                    let request = match block_locators.deserialize().await {
+                                    match block_locators.deserialize().await {
                                         // Route the `Pong` to the ledger.
-                                        Ok(block_locators) => LedgerRequest::Pong(peer_ip, peer.node_type, peer.status.get(), is_fork, block_locators),
-                                        // Route the `Failure` to the ledger.
-                                        Err(error) => LedgerRequest::Failure(peer_ip, format!("{}", error)),
-                                    };
-
-                                    // Route the request to the ledger.
-                                    if let Err(error) = ledger_router.send(request).await {
-                                        warn!("[Pong] {}", error);
+                                        Ok(block_locators) => {
+                                            let request = LedgerRequest::Pong(peer_ip, peer.node_type, peer.status.get(), is_fork, pruned_block_height, block_locators);
+                                            if let Err(error) = ledger_router.send(request).await {
+                                                warn!("[Pong] {}", error);
+                                            }
+                                        }
+                                        // Route the `PeerMisbehaved` to the peers.
+                                        Err(_) => if let Err(error) = peers_router.send(PeersRequest::PeerMisbehaved(peer_ip, Misbehavior::MalformedMessage)).await {
+                                            warn!("[PeerMisbehaved] {}", error);
+                                        }
                                     }
 
                                     // Spawn an asynchronous task for the `Ping` request.
@@ -1198,18 +1510,25 @@ impl<N: Network, E: Environment> Peer<N, E> {
                                         let request = match block.deserialize().await {
                                             // Ensure the claimed block height and block hash matches in the deserialized block.
                                             Ok(block) => match block_height == block.height() && block_hash == block.hash() {
-                                                // Route the `UnconfirmedBlock` to the ledger.
-                                                true => LedgerRequest::UnconfirmedBlock(peer_ip, block, prover_router.clone()),
-                                                // Route the `Failure` to the ledger.
-                                                false => LedgerRequest::Failure(peer_ip, "Malformed UnconfirmedBlock message".to_string())
+                                                // Ensure the block header is valid, on a dedicated thread, as its proof of work is expensive to verify.
+                                                true => match task::spawn_blocking(move || (block.header().is_valid(), block)).await {
+                                                    Ok((true, block)) => Ok(LedgerRequest::UnconfirmedBlock(peer_ip, block, prover_router.clone())),
+                                                    _ => Err(Misbehavior::InvalidBlock),
+                                                },
+                                                false => Err(Misbehavior::MalformedMessage),
                                             },
-                                            // Route the `Failure` to the ledger.
-                                            Err(error) => LedgerRequest::Failure(peer_ip, format!("{}", error)),
+                                            Err(_) => Err(Misbehavior::MalformedMessage),
                                         };
 
-                                        // Route the request to the ledger.
-                                        if let Err(error) = ledger_router.send(request).await {
-                                            warn!("[UnconfirmedBlock] {}", error);
+                                        match request {
+                                            // Route the `UnconfirmedBlock` to the ledger.
+                                            Ok(request) => if let Err(error) = ledger_router.send(request).await {
+                                                warn!("[UnconfirmedBlock] {}", error);
+                                            },
+                                            // Route the `PeerMisbehaved` to the peers.
+                                            Err(misbehavior) => if let Err(error) = peers_router.send(PeersRequest::PeerMisbehaved(peer_ip, misbehavior)).await {
+                                                warn!("[PeerMisbehaved] {}", error);
+                                            }
                                         }
                                     }
                                 }
@@ -1249,7 +1568,13 @@ impl<N: Network, E: Environment> Peer<N, E> {
                             }
                         }
                         // An error occurred.
-                        Some(Err(error)) => error!("Failed to read message from {}: {}", peer_ip, error),
+                        Some(Err(error)) => {
+                            error!("Failed to read message from {}: {}", peer_ip, error);
+                            // Route a `PeerMisbehaved` to the peers.
+                            if let Err(error) = peers_router.send(PeersRequest::PeerMisbehaved(peer_ip, Misbehavior::MalformedMessage)).await {
+                                warn!("[PeerMisbehaved] {}", error);
+                            }
+                        }
                         // The stream has been disconnected.
                         None => break,
                     },
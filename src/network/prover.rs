@@ -28,10 +28,11 @@ use crate::{
 use snarkos_storage::{storage::Storage, ProverState};
 use snarkvm::dpc::prelude::*;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use rand::thread_rng;
 use rayon::{ThreadPool, ThreadPoolBuilder};
 use std::{
+    collections::HashSet,
     net::SocketAddr,
     path::Path,
     sync::{
@@ -58,6 +59,8 @@ type ProverHandler<N> = mpsc::Receiver<ProverRequest<N>>;
 pub enum ProverRequest<N: Network> {
     /// MemoryPoolClear := (block)
     MemoryPoolClear(Option<Block<N>>),
+    /// MemoryPoolRevert := (removed_blocks)
+    MemoryPoolRevert(Vec<Block<N>>),
     /// UnconfirmedTransaction := (peer_ip, transaction)
     UnconfirmedTransaction(SocketAddr, Transaction<N>),
 }
@@ -75,6 +78,8 @@ pub struct Prover<N: Network, E: Environment> {
     prover_router: ProverRouter<N>,
     /// The pool of unconfirmed transactions.
     memory_pool: Arc<RwLock<MemoryPool<N>>>,
+    /// If `true`, the unconfirmed transactions are persisted in storage across restarts.
+    persist_memory_pool: bool,
     /// The status of the node.
     status: Status,
     /// A terminator bit for the prover.
@@ -92,6 +97,7 @@ impl<N: Network, E: Environment> Prover<N, E> {
     pub async fn open<S: Storage, P: AsRef<Path> + Copy>(
         tasks: &mut Tasks<JoinHandle<()>>,
         path: P,
+        persist_memory_pool: bool,
         miner: Option<Address<N>>,
         local_ip: SocketAddr,
         status: &Status,
@@ -114,6 +120,7 @@ impl<N: Network, E: Environment> Prover<N, E> {
             miner: Arc::new(pool),
             prover_router,
             memory_pool: Arc::new(RwLock::new(MemoryPool::new())),
+            persist_memory_pool,
             status: status.clone(),
             terminator: terminator.clone(),
             peers_router,
@@ -121,6 +128,11 @@ impl<N: Network, E: Environment> Prover<N, E> {
             ledger_router,
         });
 
+        // Restore the unconfirmed transactions from storage, if the memory pool is persisted.
+        if persist_memory_pool {
+            prover.reload_memory_pool().await;
+        }
+
         // Initialize the handler for the prover.
         {
             let prover = prover.clone();
@@ -225,6 +237,11 @@ impl<N: Network, E: Environment> Prover<N, E> {
         self.memory_pool.clone()
     }
 
+    /// Returns an instance of the prover state.
+    pub(crate) fn state(&self) -> Arc<ProverState<N>> {
+        self.state.clone()
+    }
+
     /// Returns all coinbase records in storage.
     pub fn to_coinbase_records(&self) -> Vec<(u32, Record<N>)> {
         self.state.to_coinbase_records()
@@ -237,9 +254,51 @@ impl<N: Network, E: Environment> Prover<N, E> {
     pub(super) async fn update(&self, request: ProverRequest<N>) {
         match request {
             ProverRequest::MemoryPoolClear(block) => match block {
-                Some(block) => self.memory_pool.write().await.remove_transactions(block.transactions()),
-                None => *self.memory_pool.write().await = MemoryPool::new(),
+                Some(block) => {
+                    // Remove the confirmed transactions from the memory pool, which also evicts the transactions that conflict with them.
+                    let evicted_transaction_ids = {
+                        let mut memory_pool = self.memory_pool.write().await;
+                        let transaction_ids = |memory_pool: &MemoryPool<N>| {
+                            memory_pool
+                                .transactions()
+                                .iter()
+                                .map(|transaction| transaction.transaction_id())
+                                .collect::<HashSet<_>>()
+                        };
+                        let previous_transaction_ids = transaction_ids(&memory_pool);
+                        memory_pool.remove_transactions(block.transactions());
+                        let remaining_transaction_ids = transaction_ids(&memory_pool);
+                        previous_transaction_ids
+                            .difference(&remaining_transaction_ids)
+                            .copied()
+                            .collect::<Vec<_>>()
+                    };
+                    // Remove every evicted transaction from storage, if the memory pool is persisted.
+                    if self.persist_memory_pool {
+                        for transaction_id in evicted_transaction_ids {
+                            if let Err(error) = self.state.remove_unconfirmed_transaction(&transaction_id) {
+                                warn!("[MemoryPoolClear] {}", error);
+                            }
+                        }
+                    }
+                }
+                // If the memory pool is persisted, the unconfirmed transactions in storage are revalidated instead.
+                None => match self.persist_memory_pool {
+                    true => self.reload_memory_pool().await,
+                    false => *self.memory_pool.write().await = MemoryPool::new(),
+                },
             },
+            ProverRequest::MemoryPoolRevert(removed_blocks) => {
+                // Return the transactions of the removed blocks to the memory pool, excluding their coinbase transactions.
+                for block in removed_blocks {
+                    let coinbase_transaction_id = block.to_coinbase_transaction().map(|transaction| transaction.transaction_id()).ok();
+                    for transaction in block.transactions().iter() {
+                        if Some(transaction.transaction_id()) != coinbase_transaction_id {
+                            self.add_reverted_transaction(transaction).await;
+                        }
+                    }
+                }
+            }
             ProverRequest::UnconfirmedTransaction(peer_ip, transaction) => {
                 // Ensure the node is not peering.
                 if !self.status.is_peering() {
@@ -262,7 +321,13 @@ impl<N: Network, E: Environment> Prover<N, E> {
             // Attempt to add the unconfirmed transaction to the memory pool.
             match self.memory_pool.write().await.add_transaction(&transaction) {
                 Ok(()) => {
-                    // Upon success, propagate the unconfirmed transaction to the connected peers.
+                    // Upon success, store the unconfirmed transaction, if the memory pool is persisted.
+                    if self.persist_memory_pool {
+                        if let Err(error) = self.state.add_unconfirmed_transaction(&transaction) {
+                            warn!("[UnconfirmedTransaction] {}", error);
+                        }
+                    }
+                    // Propagate the unconfirmed transaction to the connected peers.
                     let request = PeersRequest::MessagePropagate(peer_ip, Message::UnconfirmedTransaction(transaction));
                     if let Err(error) = self.peers_router.send(request).await {
                         warn!("[UnconfirmedTransaction] {}", error);
@@ -272,4 +337,77 @@ impl<N: Network, E: Environment> Prover<N, E> {
             }
         }
     }
+
+    ///
+    /// Adds the given transaction of a reverted block back to the memory pool, if it remains valid.
+    ///
+    async fn add_reverted_transaction(&self, transaction: &Transaction<N>) {
+        let result = match self.check_unconfirmed_transaction(transaction) {
+            Ok(()) => self.memory_pool.write().await.add_transaction(transaction),
+            Err(error) => Err(error),
+        };
+        match result {
+            Ok(()) => {
+                debug!("Returning reverted transaction {} to memory pool", transaction.transaction_id());
+                // Store the unconfirmed transaction, if the memory pool is persisted.
+                if self.persist_memory_pool {
+                    if let Err(error) = self.state.add_unconfirmed_transaction(transaction) {
+                        warn!("[MemoryPoolRevert] {}", error);
+                    }
+                }
+            }
+            Err(error) => trace!("Skipping reverted transaction {} - {}", transaction.transaction_id(), error),
+        }
+    }
+
+    ///
+    /// Resets the memory pool to the unconfirmed transactions in storage, after revalidating them
+    /// against the current ledger. The transactions that are no longer valid are removed from storage.
+    ///
+    async fn reload_memory_pool(&self) {
+        let mut memory_pool = MemoryPool::new();
+        for transaction in self.state.to_unconfirmed_transactions() {
+            let result = match self.check_unconfirmed_transaction(&transaction) {
+                Ok(()) => memory_pool.add_transaction(&transaction),
+                Err(error) => Err(error),
+            };
+            if let Err(error) = result {
+                debug!(
+                    "Removing unconfirmed transaction {} from storage - {}",
+                    transaction.transaction_id(),
+                    error
+                );
+                if let Err(error) = self.state.remove_unconfirmed_transaction(&transaction.transaction_id()) {
+                    warn!("[MemoryPool] {}", error);
+                }
+            }
+        }
+
+        debug!(
+            "Restored {} unconfirmed transactions from storage",
+            memory_pool.transactions().len()
+        );
+        *self.memory_pool.write().await = memory_pool;
+    }
+
+    ///
+    /// Returns `Ok(())` if the given transaction, its serial numbers, and its commitments
+    /// do not already exist in the ledger.
+    ///
+    fn check_unconfirmed_transaction(&self, transaction: &Transaction<N>) -> Result<()> {
+        if self.ledger_reader.contains_transaction(&transaction.transaction_id())? {
+            return Err(anyhow!("Transaction {} already exists in the ledger", transaction.transaction_id()));
+        }
+        for serial_number in transaction.serial_numbers() {
+            if self.ledger_reader.contains_serial_number(serial_number)? {
+                return Err(anyhow!("Serial number {} already exists in the ledger", serial_number));
+            }
+        }
+        for commitment in transaction.commitments() {
+            if self.ledger_reader.contains_commitment(commitment)? {
+                return Err(anyhow!("Commitment {} already exists in the ledger", commitment));
+            }
+        }
+        Ok(())
+    }
 }
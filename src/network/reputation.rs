@@ -0,0 +1,189 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::Environment;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt};
+
+///
+/// An enum of the ways in which a peer may misbehave.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Misbehavior {
+    /// The peer sent a block that is invalid.
+    InvalidBlock,
+    /// The peer sent block locators that are empty, malformed, or inconsistent with the canonical chain.
+    InvalidBlockLocators,
+    /// The peer sent a block response that was not requested.
+    UnsolicitedBlockResponse,
+    /// The peer did not respond within the expected time.
+    Timeout,
+    /// The peer sent a message that could not be read, or that violates the protocol limits.
+    MalformedMessage,
+}
+
+impl Misbehavior {
+    /// Returns the penalty added to the reputation score of a peer for this misbehavior.
+    pub fn weight(&self) -> f64 {
+        match self {
+            Self::InvalidBlock => 100.0,
+            Self::InvalidBlockLocators => 25.0,
+            Self::UnsolicitedBlockResponse => 5.0,
+            Self::Timeout => 10.0,
+            Self::MalformedMessage => 20.0,
+        }
+    }
+}
+
+impl fmt::Display for Misbehavior {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidBlock => write!(f, "invalid block"),
+            Self::InvalidBlockLocators => write!(f, "invalid block locators"),
+            Self::UnsolicitedBlockResponse => write!(f, "unsolicited block response"),
+            Self::Timeout => write!(f, "timeout"),
+            Self::MalformedMessage => write!(f, "malformed message"),
+        }
+    }
+}
+
+///
+/// The standing of a peer, as determined by its reputation score.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Standing {
+    /// The peer is in good standing.
+    Good,
+    /// The peer is deprioritized, and is the first to be dropped when connections are needed.
+    Deprioritized,
+    /// The peer is disconnected.
+    Disconnected,
+    /// The peer is disconnected, and refused until its score decays below the ban threshold.
+    Banned,
+}
+
+///
+/// The reputation of a peer, which is a weighted score of its misbehaviors that decays over time.
+///
+/// A score of zero is a perfect reputation. Each misbehavior adds its weight to the score,
+/// and the score halves every `REPUTATION_HALF_LIFE_IN_SECS`.
+///
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PeerReputation {
+    /// The reputation score as of the last update.
+    score: f64,
+    /// The timestamp of the last update.
+    last_updated: i64,
+    /// The number of times each misbehavior was recorded.
+    misbehaviors: BTreeMap<Misbehavior, u32>,
+}
+
+impl PeerReputation {
+    /// Initializes a new reputation with a perfect score.
+    pub fn new() -> Self {
+        Self {
+            score: 0.0,
+            last_updated: Utc::now().timestamp(),
+            misbehaviors: Default::default(),
+        }
+    }
+
+    /// Returns the current reputation score, after decay.
+    pub fn score<E: Environment>(&self) -> f64 {
+        self.score_at::<E>(Utc::now().timestamp())
+    }
+
+    /// Returns the current standing of the peer.
+    pub fn standing<E: Environment>(&self) -> Standing {
+        let score = self.score::<E>();
+        if score >= E::REPUTATION_BAN_THRESHOLD {
+            Standing::Banned
+        } else if score >= E::REPUTATION_DISCONNECT_THRESHOLD {
+            Standing::Disconnected
+        } else if score >= E::REPUTATION_DEPRIORITIZE_THRESHOLD {
+            Standing::Deprioritized
+        } else {
+            Standing::Good
+        }
+    }
+
+    /// Returns the number of times each misbehavior was recorded.
+    pub fn misbehaviors(&self) -> &BTreeMap<Misbehavior, u32> {
+        &self.misbehaviors
+    }
+
+    /// Records the given misbehavior, returning the updated standing of the peer.
+    pub fn record<E: Environment>(&mut self, misbehavior: Misbehavior) -> Standing {
+        let now = Utc::now().timestamp();
+        self.score = self.score_at::<E>(now) + misbehavior.weight();
+        self.last_updated = now;
+        *self.misbehaviors.entry(misbehavior).or_insert(0) += 1;
+        self.standing::<E>()
+    }
+
+    /// Returns the reputation score at the given timestamp, after decay.
+    fn score_at<E: Environment>(&self, timestamp: i64) -> f64 {
+        let elapsed = timestamp.saturating_sub(self.last_updated).max(0) as f64;
+        self.score * 0.5f64.powf(elapsed / E::REPUTATION_HALF_LIFE_IN_SECS as f64)
+    }
+}
+
+impl Default for PeerReputation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Client;
+    use snarkvm::dpc::testnet2::Testnet2;
+
+    type E = Client<Testnet2>;
+
+    #[test]
+    fn test_record() {
+        let mut reputation = PeerReputation::new();
+        assert_eq!(reputation.standing::<E>(), Standing::Good);
+
+        // Ensure the weight of each misbehavior determines the standing.
+        assert_eq!(reputation.record::<E>(Misbehavior::Timeout), Standing::Good);
+        assert_eq!(reputation.record::<E>(Misbehavior::InvalidBlockLocators), Standing::Deprioritized);
+        assert_eq!(reputation.record::<E>(Misbehavior::InvalidBlock), Standing::Disconnected);
+        assert_eq!(reputation.record::<E>(Misbehavior::InvalidBlock), Standing::Banned);
+
+        assert_eq!(reputation.misbehaviors().get(&Misbehavior::InvalidBlock), Some(&2));
+        assert_eq!(reputation.misbehaviors().get(&Misbehavior::MalformedMessage), None);
+    }
+
+    #[test]
+    fn test_decay() {
+        let mut reputation = PeerReputation::new();
+        reputation.record::<E>(Misbehavior::InvalidBlock);
+
+        // Ensure the score halves after each half-life.
+        let half_life = <E as Environment>::REPUTATION_HALF_LIFE_IN_SECS as i64;
+        let score = reputation.score_at::<E>(reputation.last_updated);
+        assert!((reputation.score_at::<E>(reputation.last_updated + half_life) - score / 2.0).abs() < 1e-9);
+        assert!((reputation.score_at::<E>(reputation.last_updated + 2 * half_life) - score / 4.0).abs() < 1e-9);
+
+        // Ensure the score decays towards a perfect reputation.
+        assert!(reputation.score_at::<E>(reputation.last_updated + 100 * half_life) < 1e-9);
+    }
+}
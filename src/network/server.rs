@@ -16,19 +16,32 @@
 
 use crate::{
     display::notification_message,
-    helpers::{State, Status, Tasks},
+    helpers::{AsyncLedgerReader, State, Status, Tasks},
     ledger::{Ledger, LedgerRequest, LedgerRouter},
     peers::{Peers, PeersRequest, PeersRouter},
     prover::{Prover, ProverRouter},
     rpc::initialize_rpc_server,
+    AddressBook,
+    BanList,
     Environment,
     Node,
+    NodeIdentity,
     NodeType,
 };
-use snarkos_storage::{storage::rocksdb::RocksDB, LedgerState};
+#[cfg(feature = "sled")]
+use snarkos_storage::storage::sled::SledDB;
+use snarkos_storage::{
+    storage::{
+        memory::MemoryDB,
+        rocksdb::{RocksDB, RocksDBOptions},
+        Storage,
+    },
+    LedgerState,
+    ProverState,
+};
 use snarkvm::prelude::*;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use std::{
     net::SocketAddr,
     sync::{atomic::AtomicBool, Arc},
@@ -66,7 +79,37 @@ impl<N: Network, E: Environment> Server<N, E> {
     /// Starts the connection listener for peers.
     ///
     #[inline]
-    pub async fn initialize(node: &Node, miner: Option<Address<N>>, mut tasks: Tasks<task::JoinHandle<()>>) -> Result<Self> {
+    pub async fn initialize(node: &Node, miner: Option<Address<N>>, tasks: Tasks<task::JoinHandle<()>>) -> Result<Self> {
+        match node.storage.as_str() {
+            "memory" => Self::initialize_with_storage::<MemoryDB>(node, miner, tasks).await,
+            "rocksdb" => {
+                // Apply the RocksDB options of each map, if they are specified.
+                if let Some(path) = &node.rocksdb_config {
+                    RocksDB::configure(RocksDBOptions::load(path)?);
+                }
+                Self::initialize_with_storage::<RocksDB>(node, miner, tasks).await
+            }
+            #[cfg(feature = "sled")]
+            "sled" => Self::initialize_with_storage::<SledDB>(node, miner, tasks).await,
+            storage => Err(anyhow!("Unsupported storage backend: {}", storage)),
+        }
+    }
+
+    ///
+    /// Starts the connection listener for peers, with the ledger and prover opened from the given storage backend.
+    ///
+    async fn initialize_with_storage<S: Storage>(
+        node: &Node,
+        miner: Option<Address<N>>,
+        mut tasks: Tasks<task::JoinHandle<()>>,
+    ) -> Result<Self> {
+        // Ensure the pruning depth, if specified, retains enough blocks to resolve forks.
+        if let Some(pruning_depth) = node.prune {
+            if pruning_depth < E::MAXIMUM_FORK_DEPTH {
+                return Err(anyhow!("The pruning depth must be at least {} blocks", E::MAXIMUM_FORK_DEPTH));
+            }
+        }
+
         // Initialize a new TCP listener at the given IP.
         let (local_ip, listener) = match TcpListener::bind(node.node).await {
             Ok(listener) => (listener.local_addr().expect("Failed to fetch the local IP"), listener),
@@ -82,14 +125,25 @@ impl<N: Network, E: Environment> Server<N, E> {
         // Initialize the terminator bit.
         let terminator = Arc::new(AtomicBool::new(false));
 
+        // Initialize the persistent identity of the node.
+        let identity = NodeIdentity::open(node.identity_path(local_ip))?;
+        // Initialize the persistent address book of known peers.
+        let address_book = AddressBook::open(node.address_book_path(local_ip))?;
+        // Initialize the persistent ban list, and apply the bans of the config file, if it is specified.
+        let mut ban_list = BanList::open(node.ban_list_path(local_ip))?;
+        if let Some(path) = &node.ban_config {
+            ban_list.load_config(path)?;
+        }
         // Initialize a new instance for managing peers.
-        let peers = Peers::new(tasks.clone(), local_ip, None, &status).await;
+        let peers = Peers::new(tasks.clone(), local_ip, None, identity, address_book, ban_list, &status).await;
         // Initialize a new instance for managing the ledger.
-        let ledger = Ledger::<N, E>::open::<RocksDB, _>(&mut tasks, &ledger_storage_path, &status, &terminator, peers.router()).await?;
+        let ledger =
+            Ledger::<N, E>::open::<S, _>(&mut tasks, &ledger_storage_path, node.prune, &status, &terminator, peers.router()).await?;
         // Initialize a new instance for managing the prover.
-        let prover = Prover::open::<RocksDB, _>(
+        let prover = Prover::open::<S, _>(
             &mut tasks,
             &prover_storage_path,
+            node.persist_mempool,
             miner,
             local_ip,
             &status,
@@ -107,20 +161,21 @@ impl<N: Network, E: Environment> Server<N, E> {
             listener,
             peers.router(),
             peers.clone(),
-            ledger.reader(),
+            ledger.async_reader(),
             ledger.router(),
             prover.router(),
         )
         .await;
         // Initialize a new instance of the heartbeat.
-        Self::initialize_heartbeat(&mut tasks, peers.router(), ledger.reader(), ledger.router(), prover.router()).await;
+        Self::initialize_heartbeat(&mut tasks, peers.router(), ledger.async_reader(), ledger.router(), prover.router()).await;
         // Initialize a new instance of the RPC server.
         Self::initialize_rpc(
             &mut tasks,
             node,
             &status,
             &peers,
-            ledger.reader(),
+            ledger.async_reader(),
+            prover.state(),
             prover.router(),
             prover.memory_pool(),
         )
@@ -166,7 +221,7 @@ impl<N: Network, E: Environment> Server<N, E> {
             .router()
             .send(PeersRequest::Connect(
                 peer_ip,
-                self.ledger.reader(),
+                self.ledger.async_reader(),
                 self.ledger.router(),
                 self.prover.router(),
                 router,
@@ -186,6 +241,10 @@ impl<N: Network, E: Environment> Server<N, E> {
         // Update the node status.
         self.status.update(State::ShuttingDown);
 
+        // Persist the address book of known peers.
+        trace!("Proceeding to save the address book...");
+        self.peers.save_address_book().await;
+
         // Shut down the ledger.
         trace!("Proceeding to shut down the ledger...");
         let (canon_lock, block_requests_lock, storage_map_lock) = self.ledger.shut_down().await;
@@ -212,7 +271,7 @@ impl<N: Network, E: Environment> Server<N, E> {
         listener: TcpListener,
         peers_router: PeersRouter<N, E>,
         peers: Arc<Peers<N, E>>,
-        ledger_reader: LedgerReader<N>,
+        ledger_reader: AsyncLedgerReader<N>,
         ledger_router: LedgerRouter<N>,
         prover_router: ProverRouter<N>,
     ) {
@@ -227,6 +286,10 @@ impl<N: Network, E: Environment> Server<N, E> {
                 if peers.number_of_connected_peers().await < E::MAXIMUM_NUMBER_OF_PEERS {
                     // Asynchronously wait for an inbound TcpStream.
                     match listener.accept().await {
+                        // Refuse the inbound connection before the handshake, if the peer is banned.
+                        Ok((_, peer_ip)) if peers.is_banned(peer_ip.ip()).await => {
+                            debug!("Dropping connection request from {} (banned)", peer_ip);
+                        }
                         // Process the inbound connection request.
                         Ok((stream, peer_ip)) => {
                             let request = PeersRequest::PeerConnecting(
@@ -261,7 +324,7 @@ impl<N: Network, E: Environment> Server<N, E> {
     async fn initialize_heartbeat(
         tasks: &mut Tasks<task::JoinHandle<()>>,
         peers_router: PeersRouter<N, E>,
-        ledger_reader: LedgerReader<N>,
+        ledger_reader: AsyncLedgerReader<N>,
         ledger_router: LedgerRouter<N>,
         prover_router: ProverRouter<N>,
     ) {
@@ -297,7 +360,8 @@ impl<N: Network, E: Environment> Server<N, E> {
         node: &Node,
         status: &Status,
         peers: &Arc<Peers<N, E>>,
-        ledger_reader: LedgerReader<N>,
+        ledger_reader: AsyncLedgerReader<N>,
+        prover_state: Arc<ProverState<N>>,
         prover_router: ProverRouter<N>,
         memory_pool: Arc<RwLock<MemoryPool<N>>>,
     ) {
@@ -311,6 +375,7 @@ impl<N: Network, E: Environment> Server<N, E> {
                     status,
                     peers,
                     ledger_reader,
+                    prover_state,
                     prover_router,
                     memory_pool,
                 )
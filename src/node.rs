@@ -26,7 +26,7 @@ use crate::{
     NodeType,
     SyncNode,
 };
-use snarkos_storage::storage::rocksdb::RocksDB;
+use snarkos_storage::{storage::rocksdb::RocksDB, RepairTarget};
 use snarkvm::dpc::{prelude::*, testnet2::Testnet2};
 
 use anyhow::{anyhow, Result};
@@ -61,6 +61,21 @@ pub struct Node {
     /// Specify the password for the RPC server.
     #[structopt(default_value = "pass", long = "password")]
     pub rpc_password: String,
+    /// Specify the storage backend of the node [options: rocksdb, sled, memory]
+    #[structopt(default_value = "rocksdb", long = "storage", possible_values = &["rocksdb", "sled", "memory"])]
+    pub storage: String,
+    /// Specify the path to a JSON file with the RocksDB options of each map.
+    #[structopt(long = "rocksdb-config", parse(from_os_str))]
+    pub rocksdb_config: Option<PathBuf>,
+    /// Specify the path to a JSON file of IP addresses and CIDR ranges to ban, each with an optional expiry and reason.
+    #[structopt(long = "ban-config", parse(from_os_str))]
+    pub ban_config: Option<PathBuf>,
+    /// Specify the number of most recent blocks to keep in full, pruning the transitions of older blocks.
+    #[structopt(long = "prune")]
+    pub prune: Option<u32>,
+    /// If the flag is set, the node will persist its memory pool in storage across restarts.
+    #[structopt(long = "persist-mempool")]
+    pub persist_mempool: bool,
     /// Specify the verbosity of the node [options: 0, 1, 2, 3]
     #[structopt(default_value = "2", long = "verbosity")]
     pub verbosity: u8,
@@ -88,7 +103,7 @@ impl Node {
         // Parse optional subcommands first.
         match self.commands {
             Some(command) => {
-                println!("{}", command.parse()?);
+                println!("{}", command.parse().await?);
                 Ok(())
             }
             None => match (self.network, self.miner.is_some(), self.trial, self.sync) {
@@ -128,6 +143,54 @@ impl Node {
         }
     }
 
+    /// Returns the path of the node identity.
+    pub(crate) fn identity_path(&self, _local_ip: SocketAddr) -> PathBuf {
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "test")] {
+                // Tests may use any available ports, and removes the storage artifacts afterwards,
+                // so that there is no need to adhere to a specific number assignment logic.
+                PathBuf::from(format!("/tmp/snarkos-test-identity-{}", _local_ip.port()))
+            } else {
+                match self.dev {
+                    Some(id) => PathBuf::from(format!(".identity-{}-{}", self.network, id)),
+                    None => aleo_std::aleo_dir().join(format!("identity-{}", self.network)),
+                }
+            }
+        }
+    }
+
+    /// Returns the path of the address book of known peers.
+    pub(crate) fn address_book_path(&self, _local_ip: SocketAddr) -> PathBuf {
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "test")] {
+                // Tests may use any available ports, and removes the storage artifacts afterwards,
+                // so that there is no need to adhere to a specific number assignment logic.
+                PathBuf::from(format!("/tmp/snarkos-test-peers-{}", _local_ip.port()))
+            } else {
+                match self.dev {
+                    Some(id) => PathBuf::from(format!(".peers-{}-{}", self.network, id)),
+                    None => aleo_std::aleo_dir().join(format!("peers-{}", self.network)),
+                }
+            }
+        }
+    }
+
+    /// Returns the path of the list of banned IP addresses and subnets.
+    pub(crate) fn ban_list_path(&self, _local_ip: SocketAddr) -> PathBuf {
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "test")] {
+                // Tests may use any available ports, and removes the storage artifacts afterwards,
+                // so that there is no need to adhere to a specific number assignment logic.
+                PathBuf::from(format!("/tmp/snarkos-test-bans-{}", _local_ip.port()))
+            } else {
+                match self.dev {
+                    Some(id) => PathBuf::from(format!(".bans-{}-{}", self.network, id)),
+                    None => aleo_std::aleo_dir().join(format!("bans-{}", self.network)),
+                }
+            }
+        }
+    }
+
     async fn start_server<N: Network, E: Environment>(&self) -> Result<()> {
         let miner = match (E::NODE_TYPE, &self.miner) {
             (NodeType::Miner, Some(address)) => {
@@ -212,15 +275,18 @@ pub enum Command {
     Experimental(Experimental),
     #[structopt(name = "miner", about = "Miner commands and settings")]
     Miner(MinerSubcommand),
+    #[structopt(name = "ledger", about = "Ledger commands and settings")]
+    Ledger(LedgerSubcommand),
 }
 
 impl Command {
-    pub fn parse(self) -> Result<String> {
+    pub async fn parse(self) -> Result<String> {
         match self {
             Self::Clean(command) => command.parse(),
             Self::Update(command) => command.parse(),
             Self::Experimental(command) => command.parse(),
             Self::Miner(command) => command.parse(),
+            Self::Ledger(command) => command.parse().await,
         }
     }
 }
@@ -453,6 +519,319 @@ impl MinerStats {
     }
 }
 
+#[derive(StructOpt, Debug)]
+pub struct LedgerSubcommand {
+    #[structopt(subcommand)]
+    commands: LedgerCommands,
+}
+
+impl LedgerSubcommand {
+    pub async fn parse(self) -> Result<String> {
+        match self.commands {
+            LedgerCommands::Export(command) => command.parse(),
+            LedgerCommands::Import(command) => command.parse(),
+            LedgerCommands::Verify(command) => command.parse(),
+            LedgerCommands::Repair(command) => command.parse(),
+            LedgerCommands::Reorgs(command) => command.parse(),
+            LedgerCommands::Stats(command) => command.parse(),
+            LedgerCommands::Backup(command) => command.parse().await,
+            LedgerCommands::Restore(command) => command.parse(),
+        }
+    }
+}
+
+#[derive(StructOpt, Debug)]
+pub enum LedgerCommands {
+    #[structopt(name = "export", about = "Exports a snapshot of the ledger to a file")]
+    Export(ExportLedger),
+    #[structopt(name = "import", about = "Imports a snapshot of the ledger from a file")]
+    Import(ImportLedger),
+    #[structopt(name = "verify", about = "Verifies the integrity of the ledger in storage")]
+    Verify(VerifyLedger),
+    #[structopt(name = "repair", about = "Repairs the ledger indexes, and optionally reverts the ledger")]
+    Repair(RepairLedger),
+    #[structopt(name = "reorgs", about = "Lists the reorgs recorded in the reorg journal of the ledger")]
+    Reorgs(ReorgsLedger),
+    #[structopt(name = "stats", about = "Prints the size and health statistics of the ledger storage")]
+    Stats(StatsLedger),
+    #[structopt(name = "backup", about = "Backs up the ledger of a running node to a directory")]
+    Backup(BackupLedger),
+    #[structopt(name = "restore", about = "Restores the ledger from a backup directory")]
+    Restore(RestoreLedger),
+}
+
+#[derive(StructOpt, Debug)]
+pub struct ExportLedger {
+    /// Specify the path of the snapshot file to write.
+    #[structopt(parse(from_os_str))]
+    file: PathBuf,
+    /// Specify the network of the ledger to export.
+    #[structopt(default_value = "2", long = "network")]
+    network: u16,
+    /// Enables development mode, specify the unique ID of the local node to export.
+    #[structopt(long)]
+    dev: Option<u16>,
+}
+
+impl ExportLedger {
+    pub fn parse(self) -> Result<String> {
+        match self.network {
+            2 => self.export::<Testnet2>(),
+            network => Err(anyhow!("Unsupported network {}", network)),
+        }
+    }
+
+    fn export<N: Network>(&self) -> Result<String> {
+        // Open the ledger as a reader, so that a snapshot may be taken while the node is running.
+        let ledger = snarkos_storage::LedgerState::<N>::open_reader::<RocksDB, _>(aleo_std::aleo_ledger_dir(self.network, self.dev))?;
+
+        let file = std::fs::File::create(&self.file)?;
+        let header = ledger.export_snapshot(io::BufWriter::new(file))?;
+
+        Ok(format!(
+            "Successfully exported the ledger at block {} ({}) to {}",
+            header.block_height,
+            header.block_hash,
+            self.file.display()
+        ))
+    }
+}
+
+#[derive(StructOpt, Debug)]
+pub struct ImportLedger {
+    /// Specify the path of the snapshot file to read.
+    #[structopt(parse(from_os_str))]
+    file: PathBuf,
+    /// Specify the network of the ledger to import into.
+    #[structopt(default_value = "2", long = "network")]
+    network: u16,
+    /// Enables development mode, specify the unique ID of the local node to import into.
+    #[structopt(long)]
+    dev: Option<u16>,
+}
+
+impl ImportLedger {
+    pub fn parse(self) -> Result<String> {
+        match self.network {
+            2 => self.import::<Testnet2>(),
+            network => Err(anyhow!("Unsupported network {}", network)),
+        }
+    }
+
+    fn import<N: Network>(&self) -> Result<String> {
+        let file = std::fs::File::open(&self.file)?;
+        let path = aleo_std::aleo_ledger_dir(self.network, self.dev);
+        let ledger = snarkos_storage::LedgerState::<N>::import_snapshot::<RocksDB, _, _>(&path, io::BufReader::new(file))?;
+
+        Ok(format!(
+            "Successfully imported the ledger at block {} with ledger root {} ({})",
+            ledger.latest_block_height(),
+            ledger.latest_ledger_root(),
+            path.display()
+        ))
+    }
+}
+
+#[derive(StructOpt, Debug)]
+pub struct VerifyLedger {
+    /// Specify the network of the ledger to verify.
+    #[structopt(default_value = "2", long = "network")]
+    network: u16,
+    /// Enables development mode, specify the unique ID of the local node to verify.
+    #[structopt(long)]
+    dev: Option<u16>,
+}
+
+impl VerifyLedger {
+    pub fn parse(self) -> Result<String> {
+        match self.network {
+            2 => self.verify::<Testnet2>(),
+            network => Err(anyhow!("Unsupported network {}", network)),
+        }
+    }
+
+    fn verify<N: Network>(&self) -> Result<String> {
+        let path = aleo_std::aleo_ledger_dir(self.network, self.dev);
+        let report = snarkos_storage::LedgerState::<N>::verify::<RocksDB, _>(&path)?;
+
+        // Print the report as JSON, so that it may be processed by other tools.
+        Ok(serde_json::to_string_pretty(&report)?)
+    }
+}
+
+#[derive(StructOpt, Debug)]
+pub struct RepairLedger {
+    /// Specify the block height or block hash to revert the ledger to.
+    #[structopt(long = "to")]
+    to: Option<String>,
+    /// If the flag is set, the changes are reported without being written to storage.
+    #[structopt(long = "dry-run")]
+    dry_run: bool,
+    /// Specify the network of the ledger to repair.
+    #[structopt(default_value = "2", long = "network")]
+    network: u16,
+    /// Enables development mode, specify the unique ID of the local node to repair.
+    #[structopt(long)]
+    dev: Option<u16>,
+}
+
+impl RepairLedger {
+    pub fn parse(self) -> Result<String> {
+        match self.network {
+            2 => self.repair::<Testnet2>(),
+            network => Err(anyhow!("Unsupported network {}", network)),
+        }
+    }
+
+    fn repair<N: Network>(&self) -> Result<String> {
+        // Parse the target as a block height, or otherwise as a block hash.
+        let target = match &self.to {
+            Some(target) => match target.parse::<u32>() {
+                Ok(block_height) => Some(RepairTarget::BlockHeight(block_height)),
+                Err(_) => Some(RepairTarget::BlockHash(serde_json::from_value(serde_json::Value::String(
+                    target.clone(),
+                ))?)),
+            },
+            None => None,
+        };
+
+        let path = aleo_std::aleo_ledger_dir(self.network, self.dev);
+        let report = snarkos_storage::LedgerState::<N>::repair::<RocksDB, _>(&path, target, self.dry_run)?;
+
+        // Print the report as JSON, so that it may be processed by other tools.
+        Ok(serde_json::to_string_pretty(&report)?)
+    }
+}
+
+#[derive(StructOpt, Debug)]
+pub struct ReorgsLedger {
+    /// Specify the network of the ledger to list the reorgs of.
+    #[structopt(default_value = "2", long = "network")]
+    network: u16,
+    /// Enables development mode, specify the unique ID of the local node to list the reorgs of.
+    #[structopt(long)]
+    dev: Option<u16>,
+}
+
+impl ReorgsLedger {
+    pub fn parse(self) -> Result<String> {
+        match self.network {
+            2 => self.reorgs::<Testnet2>(),
+            network => Err(anyhow!("Unsupported network {}", network)),
+        }
+    }
+
+    fn reorgs<N: Network>(&self) -> Result<String> {
+        // Open the ledger as a reader, so that the reorgs may be listed while the node is running.
+        let ledger = snarkos_storage::LedgerState::<N>::open_reader::<RocksDB, _>(aleo_std::aleo_ledger_dir(self.network, self.dev))?;
+
+        // Print the reorgs as JSON, so that they may be processed by other tools.
+        Ok(serde_json::to_string_pretty(&ledger.get_reorgs()?)?)
+    }
+}
+
+#[derive(StructOpt, Debug)]
+pub struct StatsLedger {
+    /// Specify the network of the ledger to print the statistics of.
+    #[structopt(default_value = "2", long = "network")]
+    network: u16,
+    /// Enables development mode, specify the unique ID of the local node to print the statistics of.
+    #[structopt(long)]
+    dev: Option<u16>,
+}
+
+impl StatsLedger {
+    pub fn parse(self) -> Result<String> {
+        match self.network {
+            2 => self.stats::<Testnet2>(),
+            network => Err(anyhow!("Unsupported network {}", network)),
+        }
+    }
+
+    fn stats<N: Network>(&self) -> Result<String> {
+        // Open the ledger as a reader, so that the statistics may be printed while the node is running.
+        let ledger = snarkos_storage::LedgerState::<N>::open_reader::<RocksDB, _>(aleo_std::aleo_ledger_dir(self.network, self.dev))?;
+
+        // Print the statistics as JSON, so that they may be processed by other tools.
+        Ok(serde_json::to_string_pretty(&ledger.storage_stats()?)?)
+    }
+}
+
+#[derive(StructOpt, Debug)]
+pub struct BackupLedger {
+    /// Specify the path of the backup directory to write.
+    #[structopt(parse(from_os_str))]
+    dir: PathBuf,
+    /// Specify the IP address and port of the RPC server of the running node.
+    #[structopt(parse(try_from_str), default_value = "127.0.0.1:3032", long = "rpc")]
+    rpc: SocketAddr,
+    /// Specify the username for the RPC server.
+    #[structopt(default_value = "root", long = "username")]
+    rpc_username: String,
+    /// Specify the password for the RPC server.
+    #[structopt(default_value = "pass", long = "password")]
+    rpc_password: String,
+}
+
+impl BackupLedger {
+    pub async fn parse(self) -> Result<String> {
+        // The storage of a running node may only be checkpointed by the node itself, so the backup is requested over RPC.
+        let dir = std::env::current_dir()?.join(&self.dir);
+        let body = serde_json::json!({ "jsonrpc": "2.0", "id": "1", "method": "backup", "params": [dir] });
+        let credentials = base64::encode(format!("{}:{}", self.rpc_username, self.rpc_password));
+
+        let request = hyper::Request::post(format!("http://{}/", self.rpc))
+            .header(hyper::header::CONTENT_TYPE, "application/json")
+            .header(hyper::header::AUTHORIZATION, format!("Basic {}", credentials))
+            .body(hyper::Body::from(serde_json::to_vec(&body)?))?;
+        let response = hyper::Client::new().request(request).await?;
+        let response_bytes = hyper::body::to_bytes(response.into_body()).await?;
+        let mut response: serde_json::Value = serde_json::from_slice(&response_bytes)?;
+
+        if let Some(error) = response.get("error") {
+            return Err(anyhow!("Failed to back up the ledger to {}: {}", dir.display(), error));
+        }
+
+        // Print the manifest as JSON, so that it may be processed by other tools.
+        Ok(serde_json::to_string_pretty(&response["result"].take())?)
+    }
+}
+
+#[derive(StructOpt, Debug)]
+pub struct RestoreLedger {
+    /// Specify the path of the backup directory to read.
+    #[structopt(parse(from_os_str))]
+    dir: PathBuf,
+    /// Specify the network of the ledger to restore.
+    #[structopt(default_value = "2", long = "network")]
+    network: u16,
+    /// Enables development mode, specify the unique ID of the local node to restore.
+    #[structopt(long)]
+    dev: Option<u16>,
+}
+
+impl RestoreLedger {
+    pub fn parse(self) -> Result<String> {
+        match self.network {
+            2 => self.restore::<Testnet2>(),
+            network => Err(anyhow!("Unsupported network {}", network)),
+        }
+    }
+
+    fn restore<N: Network>(&self) -> Result<String> {
+        let ledger_path = aleo_std::aleo_ledger_dir(self.network, self.dev);
+        let prover_path = aleo_std::aleo_prover_dir(self.network, self.dev);
+        let manifest = snarkos_storage::LedgerState::<N>::restore_backup::<RocksDB, _, _, _>(&self.dir, &ledger_path, &prover_path)?;
+
+        Ok(format!(
+            "Successfully restored the ledger at block {} ({}) from {}",
+            manifest.block_height,
+            manifest.block_hash,
+            self.dir.display()
+        ))
+    }
+}
+
 // This function is responsible for handling OS signals in order for the node to be able to intercept them
 // and perform a clean shutdown.
 // note: only Ctrl-C is currently supported, but it should work on both Unix-family systems and Windows.
@@ -17,13 +17,13 @@
 //! Logic for instantiating the RPC server.
 
 use crate::{
-    helpers::Status,
+    helpers::{AsyncLedgerReader, Status},
     rpc::{rpc_impl::RpcImpl, rpc_trait::RpcFunctions},
     Environment,
-    LedgerReader,
     Peers,
     ProverRouter,
 };
+use snarkos_storage::ProverState;
 use snarkvm::dpc::{MemoryPool, Network};
 
 use hyper::{
@@ -55,7 +55,7 @@ pub struct Meta {
 
 impl Metadata for Meta {}
 
-const METHODS_EXPECTING_PARAMS: [&str; 12] = [
+const METHODS_EXPECTING_PARAMS: [&str; 15] = [
     // public
     "getblock",
     "getblocks",
@@ -70,7 +70,10 @@ const METHODS_EXPECTING_PARAMS: [&str; 12] = [
     "gettransition",
     "sendtransaction",
     // "validaterawtransaction",
-    // // private
+    // private
+    "backup",
+    "banpeer",
+    "unbanpeer",
     // "createrawtransaction",
     // "createtransaction",
     // "getrawrecord",
@@ -88,12 +91,21 @@ pub async fn initialize_rpc_server<N: Network, E: Environment>(
     password: String,
     status: &Status,
     peers: &Arc<Peers<N, E>>,
-    ledger: LedgerReader<N>,
+    ledger: AsyncLedgerReader<N>,
+    prover_state: Arc<ProverState<N>>,
     prover_router: ProverRouter<N>,
     memory_pool: Arc<RwLock<MemoryPool<N>>>,
 ) -> tokio::task::JoinHandle<()> {
     let credentials = RpcCredentials { username, password };
-    let rpc = RpcImpl::new(credentials, status.clone(), peers.clone(), ledger, prover_router, memory_pool);
+    let rpc = RpcImpl::new(
+        credentials,
+        status.clone(),
+        peers.clone(),
+        ledger,
+        prover_state,
+        prover_router,
+        memory_pool,
+    );
 
     let service = make_service_fn(move |conn: &AddrStream| {
         let caller = conn.remote_addr();
@@ -125,7 +137,7 @@ async fn handle_rpc<N: Network, E: Environment>(
         .headers()
         .get(hyper::header::AUTHORIZATION)
         .map(|h| h.to_str().unwrap_or("").to_owned());
-    let _meta = Meta { auth };
+    let meta = Meta { auth };
 
     // Save the headers.
     let headers = req.headers().clone();
@@ -299,6 +311,14 @@ async fn handle_rpc<N: Network, E: Environment>(
             let result = rpc.get_memory_pool().await.map_err(convert_crate_err);
             result_to_response(&req, result)
         }
+        "getreorgs" => {
+            let result = rpc.get_reorgs().await.map_err(convert_crate_err);
+            result_to_response(&req, result)
+        }
+        "getstoragestats" => {
+            let result = rpc.get_storage_stats().await.map_err(convert_crate_err);
+            result_to_response(&req, result)
+        }
         "gettransaction" => {
             let result = rpc.get_transaction(params.remove(0)).await.map_err(convert_crate_err);
             result_to_response(&req, result)
@@ -311,6 +331,10 @@ async fn handle_rpc<N: Network, E: Environment>(
             let result = rpc.get_connected_peers().await.map_err(convert_crate_err);
             result_to_response(&req, result)
         }
+        "getpeerinfo" => {
+            let result = rpc.get_peer_info().await.map_err(convert_crate_err);
+            result_to_response(&req, result)
+        }
         "getnodestate" => {
             let result = rpc.get_node_state().await.map_err(convert_crate_err);
             result_to_response(&req, result)
@@ -326,7 +350,42 @@ async fn handle_rpc<N: Network, E: Environment>(
         //     let result = rpc.get_block_template().await.map_err(convert_crate_err);
         //     result_to_response(&req, result)
         // }
-        // // private
+        // Private
+        "backup" => {
+            let result = match rpc.is_authorized(&meta) {
+                true => rpc.backup(params[0].as_str().unwrap_or("").into()).await.map_err(convert_crate_err),
+                false => Err(unauthorized_err()),
+            };
+            result_to_response(&req, result)
+        }
+        "banpeer" => {
+            let result = match rpc.is_authorized(&meta) {
+                true => rpc
+                    .ban_peer(
+                        params[0].as_str().unwrap_or("").into(),
+                        params.get(1).and_then(|duration| duration.as_u64()),
+                        params.get(2).and_then(|reason| reason.as_str()).map(|reason| reason.to_string()),
+                    )
+                    .await
+                    .map_err(convert_crate_err),
+                false => Err(unauthorized_err()),
+            };
+            result_to_response(&req, result)
+        }
+        "unbanpeer" => {
+            let result = match rpc.is_authorized(&meta) {
+                true => rpc.unban_peer(params[0].as_str().unwrap_or("").into()).await.map_err(convert_crate_err),
+                false => Err(unauthorized_err()),
+            };
+            result_to_response(&req, result)
+        }
+        "listbanned" => {
+            let result = match rpc.is_authorized(&meta) {
+                true => rpc.list_banned().await.map_err(convert_crate_err),
+                false => Err(unauthorized_err()),
+            };
+            result_to_response(&req, result)
+        }
         // "createaccount" => {
         //     let result = rpc
         //         .create_account_protected(Params::Array(params), meta)
@@ -401,6 +460,11 @@ fn convert_crate_err(err: crate::rpc::rpc_impl::RpcError) -> jrt::Error<String>
     error.set_data(err.to_string())
 }
 
+/// Returns the error for a request to a private endpoint without valid credentials.
+fn unauthorized_err() -> jrt::Error<String> {
+    jrt::Error::with_custom_msg(jrt::ErrorCode::ServerError(-32001), "unauthorized")
+}
+
 /// Converts the jsonrpc-core's Error into a jrt::RpcError
 #[allow(unused)]
 fn convert_core_err(err: jsonrpc_core::Error) -> jrt::Error<String> {
@@ -424,12 +488,23 @@ fn result_to_response<T: Serialize>(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{helpers::State, ledger::Ledger, Client, Prover};
+    use crate::{
+        helpers::{State, StorageMetrics},
+        ledger::Ledger,
+        AddressBook,
+        Ban,
+        BanList,
+        Client,
+        NodeIdentity,
+        Prover,
+    };
 
     use crate::helpers::Tasks;
     use snarkos_storage::{
-        storage::{rocksdb::RocksDB, Storage},
+        storage::{memory::MemoryDB, MapId, Storage, StorageStats},
+        BackupManifest,
         LedgerState,
+        Reorg,
     };
     use snarkvm::{
         dpc::{testnet2::Testnet2, AccountScheme, AleoAmount, Transaction, Transactions, Transition},
@@ -446,8 +521,9 @@ mod tests {
         sync::atomic::AtomicBool,
     };
 
-    fn temp_dir() -> std::path::PathBuf {
-        tempfile::tempdir().expect("Failed to open temporary directory").into_path()
+    /// Returns a unique storage path, which is never created on the filesystem.
+    fn temp_path() -> std::path::PathBuf {
+        format!("snarkos-test-rpc-{}", rand::random::<u64>()).into()
     }
 
     /// Returns a dummy caller IP address.
@@ -459,7 +535,7 @@ mod tests {
     fn new_ledger_state<N: Network, S: Storage, P: AsRef<Path>>(path: Option<P>) -> LedgerState<N> {
         match path {
             Some(path) => LedgerState::<N>::open_writer::<S, _>(path).expect("Failed to initialize ledger"),
-            None => LedgerState::<N>::open_writer::<S, _>(temp_dir()).expect("Failed to initialize ledger"),
+            None => LedgerState::<N>::open_writer::<S, _>(temp_path()).expect("Failed to initialize ledger"),
         }
     }
 
@@ -472,8 +548,8 @@ mod tests {
 
         // Derive the storage paths.
         let (ledger_path, prover_path) = match &path {
-            Some(p) => (p.as_ref().to_path_buf(), temp_dir()),
-            None => (temp_dir(), temp_dir()),
+            Some(p) => (p.as_ref().to_path_buf(), temp_path()),
+            None => (temp_path(), temp_path()),
         };
 
         // Initialize the node.
@@ -489,9 +565,18 @@ mod tests {
         let mut tasks = Tasks::new();
 
         // Initialize a new instance for managing peers.
-        let peers = Peers::new(tasks.clone(), local_ip, None, &status).await;
+        let peers = Peers::new(
+            tasks.clone(),
+            local_ip,
+            None,
+            NodeIdentity::new().unwrap(),
+            AddressBook::new(),
+            BanList::new(),
+            &status,
+        )
+        .await;
         // Initialize a new instance for managing the ledger.
-        let ledger = Ledger::<N, E>::open::<S, _>(&mut tasks, &ledger_path, &status, &terminator, peers.router())
+        let ledger = Ledger::<N, E>::open::<S, _>(&mut tasks, &ledger_path, None, &status, &terminator, peers.router())
             .await
             .expect("Failed to initialize ledger");
 
@@ -499,6 +584,7 @@ mod tests {
         let prover = Prover::open::<S, _>(
             &mut tasks,
             &prover_path,
+            false,
             None,
             local_ip,
             &status,
@@ -510,7 +596,15 @@ mod tests {
         .await
         .expect("Failed to initialize prover");
 
-        RpcImpl::<N, E>::new(credentials, status, peers, ledger.reader(), prover.router(), prover.memory_pool())
+        RpcImpl::<N, E>::new(
+            credentials,
+            status,
+            peers,
+            ledger.async_reader(),
+            prover.state(),
+            prover.router(),
+            prover.memory_pool(),
+        )
     }
 
     /// Initializes a new instance of the rpc.
@@ -520,8 +614,8 @@ mod tests {
     ) {
         // Derive the storage paths.
         let (ledger_path, prover_path) = match &path {
-            Some(p) => (p.as_ref().to_path_buf(), temp_dir()),
-            None => (temp_dir(), temp_dir()),
+            Some(p) => (p.as_ref().to_path_buf(), temp_path()),
+            None => (temp_path(), temp_path()),
         };
 
         // Initialize the node.
@@ -535,9 +629,18 @@ mod tests {
         let terminator = Arc::new(AtomicBool::new(false));
 
         // Initialize a new instance for managing peers.
-        let peers = Peers::new(tasks.clone(), local_ip, None, &status).await;
+        let peers = Peers::new(
+            tasks.clone(),
+            local_ip,
+            None,
+            NodeIdentity::new().unwrap(),
+            AddressBook::new(),
+            BanList::new(),
+            &status,
+        )
+        .await;
         // Initialize a new instance for managing the ledger.
-        let ledger = Ledger::<N, E>::open::<S, _>(tasks, &ledger_path, &status, &terminator, peers.router())
+        let ledger = Ledger::<N, E>::open::<S, _>(tasks, &ledger_path, None, &status, &terminator, peers.router())
             .await
             .expect("Failed to initialize ledger");
 
@@ -545,6 +648,7 @@ mod tests {
         let prover = Prover::open::<S, _>(
             tasks,
             &prover_path,
+            false,
             None,
             local_ip,
             &status,
@@ -563,7 +667,8 @@ mod tests {
                 "world".to_string(),
                 &status,
                 &peers,
-                ledger.reader(),
+                ledger.async_reader(),
+                prover.state(),
                 prover.router(),
                 prover.memory_pool(),
             )
@@ -584,7 +689,7 @@ mod tests {
     #[tokio::test]
     async fn test_handle_rpc() {
         // Initialize a new RPC.
-        let rpc = new_rpc::<Testnet2, Client<Testnet2>, RocksDB, PathBuf>(None).await;
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(None).await;
 
         // Initialize a new request with an empty body.
         let request = Request::new(Body::empty());
@@ -600,7 +705,7 @@ mod tests {
     #[tokio::test]
     async fn test_latest_block() {
         // Initialize a new RPC.
-        let rpc = new_rpc::<Testnet2, Client<Testnet2>, RocksDB, PathBuf>(None).await;
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(None).await;
 
         // Initialize a new request that calls the `latestblock` endpoint.
         let request = Request::new(Body::from(
@@ -627,7 +732,7 @@ mod tests {
     #[tokio::test]
     async fn test_latest_block_height() {
         // Initialize a new RPC.
-        let rpc = new_rpc::<Testnet2, Client<Testnet2>, RocksDB, PathBuf>(None).await;
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(None).await;
 
         // Initialize a new request that calls the `latestblockheight` endpoint.
         let request = Request::new(Body::from(
@@ -654,7 +759,7 @@ mod tests {
     #[tokio::test]
     async fn test_latest_block_hash() {
         // Initialize a new RPC.
-        let rpc = new_rpc::<Testnet2, Client<Testnet2>, RocksDB, PathBuf>(None).await;
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(None).await;
 
         // Initialize a new request that calls the `latestblockhash` endpoint.
         let request = Request::new(Body::from(
@@ -681,7 +786,7 @@ mod tests {
     #[tokio::test]
     async fn test_latest_block_header() {
         // Initialize a new RPC.
-        let rpc = new_rpc::<Testnet2, Client<Testnet2>, RocksDB, PathBuf>(None).await;
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(None).await;
 
         // Initialize a new request that calls the `latestblockheader` endpoint.
         let request = Request::new(Body::from(
@@ -708,7 +813,7 @@ mod tests {
     #[tokio::test]
     async fn test_latest_block_transactions() {
         // Initialize a new RPC.
-        let rpc = new_rpc::<Testnet2, Client<Testnet2>, RocksDB, PathBuf>(None).await;
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(None).await;
 
         // Initialize a new request that calls the `latestblocktransactions` endpoint.
         let request = Request::new(Body::from(
@@ -735,7 +840,7 @@ mod tests {
     #[tokio::test]
     async fn test_latest_ledger_root() {
         // Initialize a new RPC.
-        let rpc = new_rpc::<Testnet2, Client<Testnet2>, RocksDB, PathBuf>(None).await;
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(None).await;
 
         let expected = rpc.latest_ledger_root().await.unwrap();
 
@@ -763,7 +868,7 @@ mod tests {
     #[tokio::test]
     async fn test_get_block() {
         // Initialize a new RPC.
-        let rpc = new_rpc::<Testnet2, Client<Testnet2>, RocksDB, PathBuf>(None).await;
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(None).await;
 
         // Initialize a new request that calls the `getblock` endpoint.
         let request = Request::new(Body::from(
@@ -795,11 +900,11 @@ mod tests {
         let rng = &mut thread_rng();
         let terminator = AtomicBool::new(false);
 
-        // Initialize a new temporary directory.
-        let directory = temp_dir();
+        // Initialize a new temporary storage path.
+        let directory = temp_path();
 
-        // Initialize a new ledger state at the temporary directory.
-        let ledger_state = new_ledger_state::<Testnet2, RocksDB, PathBuf>(Some(directory.clone()));
+        // Initialize a new ledger state at the temporary storage path.
+        let ledger_state = new_ledger_state::<Testnet2, MemoryDB, PathBuf>(Some(directory.clone()));
         assert_eq!(0, ledger_state.latest_block_height());
 
         // Initialize a new account.
@@ -813,11 +918,11 @@ mod tests {
         ledger_state.add_next_block(&block_1).expect("Failed to add next block to ledger");
         assert_eq!(1, ledger_state.latest_block_height());
 
-        // Drop the handle to ledger_state. Note this does not remove the blocks in the temporary directory.
+        // Drop the handle to ledger_state. Note this does not remove the blocks from the temporary storage.
         drop(ledger_state);
 
         // Initialize a new RPC with the ledger state containing the genesis block and block_1.
-        let rpc = new_rpc::<Testnet2, Client<Testnet2>, RocksDB, PathBuf>(Some(directory.clone())).await;
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(Some(directory.clone())).await;
 
         // Initialize a new request that calls the `getblocks` endpoint.
         let request = Request::new(Body::from(
@@ -849,7 +954,7 @@ mod tests {
     #[tokio::test]
     async fn test_get_block_height() {
         // Initialize a new RPC.
-        let rpc = new_rpc::<Testnet2, Client<Testnet2>, RocksDB, PathBuf>(None).await;
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(None).await;
 
         // Get the genesis block hash.
         let block_hash = Testnet2::genesis_block().hash().to_string();
@@ -883,7 +988,7 @@ mod tests {
     #[tokio::test]
     async fn test_get_block_hash() {
         // Initialize a new RPC.
-        let rpc = new_rpc::<Testnet2, Client<Testnet2>, RocksDB, PathBuf>(None).await;
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(None).await;
 
         // Initialize a new request that calls the `getblockhash` endpoint.
         let request = Request::new(Body::from(
@@ -915,11 +1020,11 @@ mod tests {
         let rng = &mut thread_rng();
         let terminator = AtomicBool::new(false);
 
-        // Initialize a new temporary directory.
-        let directory = temp_dir();
+        // Initialize a new temporary storage path.
+        let directory = temp_path();
 
-        // Initialize a new ledger state at the temporary directory.
-        let ledger_state = new_ledger_state::<Testnet2, RocksDB, PathBuf>(Some(directory.clone()));
+        // Initialize a new ledger state at the temporary storage path.
+        let ledger_state = new_ledger_state::<Testnet2, MemoryDB, PathBuf>(Some(directory.clone()));
         assert_eq!(0, ledger_state.latest_block_height());
 
         // Initialize a new account.
@@ -933,11 +1038,11 @@ mod tests {
         ledger_state.add_next_block(&block_1).expect("Failed to add next block to ledger");
         assert_eq!(1, ledger_state.latest_block_height());
 
-        // Drop the handle to ledger_state. Note this does not remove the blocks in the temporary directory.
+        // Drop the handle to ledger_state. Note this does not remove the blocks from the temporary storage.
         drop(ledger_state);
 
         // Initialize a new RPC with the ledger state containing the genesis block and block_1.
-        let rpc = new_rpc::<Testnet2, Client<Testnet2>, RocksDB, PathBuf>(Some(directory.clone())).await;
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(Some(directory.clone())).await;
 
         // Initialize a new request that calls the `getblockhashes` endpoint.
         let request = Request::new(Body::from(
@@ -969,7 +1074,7 @@ mod tests {
     #[tokio::test]
     async fn test_get_block_header() {
         // Initialize a new RPC.
-        let rpc = new_rpc::<Testnet2, Client<Testnet2>, RocksDB, PathBuf>(None).await;
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(None).await;
 
         // Initialize a new request that calls the `getblockheader` endpoint.
         let request = Request::new(Body::from(
@@ -999,7 +1104,7 @@ mod tests {
     #[tokio::test]
     async fn test_get_block_template() {
         // Initialize a new RPC.
-        let rpc = new_rpc::<Testnet2, Client<Testnet2>, RocksDB, PathBuf>(None).await;
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(None).await;
 
         // Initialize the expected block template values.
         let expected_previous_block_hash = Testnet2::genesis_block().hash().to_string();
@@ -1036,7 +1141,7 @@ mod tests {
     #[tokio::test]
     async fn test_get_block_transactions() {
         // Initialize a new RPC.
-        let rpc = new_rpc::<Testnet2, Client<Testnet2>, RocksDB, PathBuf>(None).await;
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(None).await;
 
         // Initialize a new request that calls the `getblocktransactions` endpoint.
         let request = Request::new(Body::from(
@@ -1066,7 +1171,7 @@ mod tests {
     #[tokio::test]
     async fn test_get_ciphertext() {
         // Initialize a new RPC.
-        let rpc = new_rpc::<Testnet2, Client<Testnet2>, RocksDB, PathBuf>(None).await;
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(None).await;
 
         // Get the commitment from the genesis coinbase transaction.
         let commitment = Testnet2::genesis_block().to_coinbase_transaction().unwrap().transitions()[0]
@@ -1112,11 +1217,11 @@ mod tests {
         let mut rng = ChaChaRng::seed_from_u64(123456789);
         let terminator = AtomicBool::new(false);
 
-        // Initialize a new temporary directory.
-        let directory = temp_dir();
+        // Initialize a new temporary storage path.
+        let directory = temp_path();
 
-        // Initialize a new ledger state at the temporary directory.
-        let ledger_state = new_ledger_state::<Testnet2, RocksDB, PathBuf>(Some(directory.clone()));
+        // Initialize a new ledger state at the temporary storage path.
+        let ledger_state = new_ledger_state::<Testnet2, MemoryDB, PathBuf>(Some(directory.clone()));
         assert_eq!(0, ledger_state.latest_block_height());
 
         // Initialize a new account.
@@ -1138,11 +1243,11 @@ mod tests {
         // Get the ledger proof.
         let ledger_proof = ledger_state.get_ledger_inclusion_proof(record_commitment).unwrap();
 
-        // Drop the handle to ledger_state. Note this does not remove the blocks in the temporary directory.
+        // Drop the handle to ledger_state. Note this does not remove the blocks from the temporary storage.
         drop(ledger_state);
 
         // Initialize a new RPC with the ledger state containing the genesis block and block_1.
-        let rpc = new_rpc::<Testnet2, Client<Testnet2>, RocksDB, PathBuf>(Some(directory.clone())).await;
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(Some(directory.clone())).await;
 
         // Initialize a new request that calls the `getledgerproof` endpoint.
         let request = Request::new(Body::from(format!(
@@ -1173,7 +1278,7 @@ mod tests {
     #[tokio::test]
     async fn test_get_node_state() {
         // Initialize a new RPC.
-        let rpc = new_rpc::<Testnet2, Client<Testnet2>, RocksDB, PathBuf>(None).await;
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(None).await;
 
         // Declare the expected node state.
         let expected = serde_json::json!({
@@ -1185,6 +1290,8 @@ mod tests {
             "number_of_candidate_peers": 0,
             "number_of_connected_peers": 0,
             "number_of_connected_sync_nodes": 0,
+            "number_of_tried_peers": 0,
+            "pruned_block_height": 0,
             "software": format!("snarkOS {}", env!("CARGO_PKG_VERSION")),
             "status": rpc.status.to_string(),
             "type": Client::<Testnet2>::NODE_TYPE,
@@ -1206,14 +1313,237 @@ mod tests {
             .expect("Test RPC failed to process request");
 
         // Process the response into a ledger root.
-        let actual: serde_json::Value = process_response(response).await;
+        let mut actual: serde_json::Value = process_response(response).await;
 
         println!("get_node_state: {:?}", actual);
 
+        // Check the storage metrics, which depend on the storage calls made while opening the ledger.
+        let storage_metrics = actual.as_object_mut().unwrap().remove("storage_metrics").unwrap();
+        let storage_metrics: StorageMetrics = serde_json::from_value(storage_metrics).unwrap();
+        assert_eq!(0, storage_metrics.calls);
+
         // Check the node state.
         assert_eq!(expected, actual);
     }
 
+    #[tokio::test]
+    async fn test_get_node_state_storage_metrics() {
+        // Initialize a new RPC.
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(None).await;
+
+        // Request the genesis block, and a block that does not exist.
+        for block_height in [0, 1] {
+            let request = Request::new(Body::from(format!(
+                r#"{{"jsonrpc": "2.0", "id": "1", "method": "getblock", "params": [{}]}}"#,
+                block_height
+            )));
+            handle_rpc(caller(), rpc.clone(), request)
+                .await
+                .expect("Test RPC failed to process request");
+        }
+
+        // Initialize a new request that calls the `getnodestate` endpoint.
+        let request = Request::new(Body::from(r#"{"jsonrpc": "2.0", "id": "1", "method": "getnodestate"}"#));
+
+        // Send the request to the RPC.
+        let response = handle_rpc(caller(), rpc, request)
+            .await
+            .expect("Test RPC failed to process request");
+
+        // Process the response into the node state.
+        let actual: serde_json::Value = process_response(response).await;
+        let storage_metrics: StorageMetrics = serde_json::from_value(actual["storage_metrics"].clone()).unwrap();
+
+        // Check the storage metrics.
+        assert_eq!(2, storage_metrics.calls);
+        assert_eq!(1, storage_metrics.errors);
+        assert_eq!(0, storage_metrics.timeouts);
+        assert_eq!(0, storage_metrics.pending);
+    }
+
+    #[tokio::test]
+    async fn test_backup() {
+        // Initialize a new RPC.
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(None).await;
+
+        // Initialize a new temporary backup directory.
+        let backup = tempfile::tempdir().expect("Failed to open temporary directory");
+        let body = format!(
+            r#"{{"jsonrpc": "2.0", "id": "1", "method": "backup", "params": ["{}"]}}"#,
+            backup.path().display()
+        );
+
+        // Send the request without credentials to the RPC.
+        let request = Request::new(Body::from(body.clone()));
+        let response = handle_rpc(caller(), rpc.clone(), request)
+            .await
+            .expect("Test RPC failed to process request");
+
+        // Ensure the request is rejected, and no backup is written.
+        let response_bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let response_json: jrt::Response<serde_json::Value, String> = serde_json::from_slice(&response_bytes).unwrap();
+        assert!(response_json.payload.is_err());
+        assert!(backup.path().read_dir().unwrap().next().is_none());
+
+        // Send the request with the credentials of the RPC.
+        let request = Request::builder()
+            .header(hyper::header::AUTHORIZATION, format!("Basic {}", base64::encode("root:pass")))
+            .body(Body::from(body))
+            .unwrap();
+        let response = handle_rpc(caller(), rpc, request)
+            .await
+            .expect("Test RPC failed to process request");
+
+        // Process the response into the backup manifest.
+        let actual: BackupManifest<Testnet2> = process_response(response).await;
+
+        // Check the backup manifest.
+        assert_eq!(0, actual.block_height);
+        assert_eq!(Testnet2::genesis_block().hash(), actual.block_hash);
+        assert_eq!(actual, BackupManifest::<Testnet2>::read(backup.path()).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_ban_peer() {
+        // Initialize a new RPC.
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(None).await;
+
+        // Initializes a new request with the credentials of the RPC.
+        let authorized_request = |body: &str| {
+            Request::builder()
+                .header(hyper::header::AUTHORIZATION, format!("Basic {}", base64::encode("root:pass")))
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+        let list_banned = r#"{"jsonrpc": "2.0", "id": "1", "method": "listbanned"}"#;
+        let ban_peer = r#"{"jsonrpc": "2.0", "id": "1", "method": "banpeer", "params": ["10.1.2.3/8", 3600, "spam"]}"#;
+
+        // Send the request without credentials to the RPC.
+        let response = handle_rpc(caller(), rpc.clone(), Request::new(Body::from(ban_peer)))
+            .await
+            .expect("Test RPC failed to process request");
+
+        // Ensure the request is rejected, and no ban is added.
+        let response_bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let response_json: jrt::Response<serde_json::Value, String> = serde_json::from_slice(&response_bytes).unwrap();
+        assert!(response_json.payload.is_err());
+
+        let response = handle_rpc(caller(), rpc.clone(), authorized_request(list_banned))
+            .await
+            .expect("Test RPC failed to process request");
+        let actual: Vec<Ban> = process_response(response).await;
+        assert!(actual.is_empty());
+
+        // Send the request with the credentials of the RPC.
+        let response = handle_rpc(caller(), rpc.clone(), authorized_request(ban_peer))
+            .await
+            .expect("Test RPC failed to process request");
+
+        // Process the response into the ban, and check the subnet is normalized.
+        let ban: Ban = process_response(response).await;
+        assert_eq!("10.0.0.0/8", ban.subnet.to_string());
+        assert_eq!(Some("spam".to_string()), ban.reason);
+        assert!(ban.expiry.is_some());
+
+        // Ensure the ban is listed.
+        let response = handle_rpc(caller(), rpc.clone(), authorized_request(list_banned))
+            .await
+            .expect("Test RPC failed to process request");
+        let actual: Vec<Ban> = process_response(response).await;
+        assert_eq!(vec![ban], actual);
+
+        // Ensure the ban is lifted.
+        let unban_peer = r#"{"jsonrpc": "2.0", "id": "1", "method": "unbanpeer", "params": ["10.0.0.0/8"]}"#;
+        let response = handle_rpc(caller(), rpc.clone(), authorized_request(unban_peer))
+            .await
+            .expect("Test RPC failed to process request");
+        let actual: bool = process_response(response).await;
+        assert!(actual);
+
+        let response = handle_rpc(caller(), rpc, authorized_request(list_banned))
+            .await
+            .expect("Test RPC failed to process request");
+        let actual: Vec<Ban> = process_response(response).await;
+        assert!(actual.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_reorgs() {
+        let rng = &mut thread_rng();
+        let terminator = AtomicBool::new(false);
+
+        // Initialize a new temporary storage path.
+        let directory = temp_path();
+
+        // Initialize a new ledger state at the temporary storage path.
+        let ledger_state = new_ledger_state::<Testnet2, MemoryDB, PathBuf>(Some(directory.clone()));
+
+        // Initialize a new account.
+        let account = Account::<Testnet2>::new(&mut thread_rng());
+        let address = account.address();
+
+        // Mine the next block, revert it, and record the reorg.
+        let (block_1, _) = ledger_state
+            .mine_next_block(address, true, &[], &terminator, rng)
+            .expect("Failed to mine");
+        ledger_state.add_next_block(&block_1).expect("Failed to add next block to ledger");
+        let reverted_blocks = ledger_state.revert_to_block_height(0).expect("Failed to revert");
+        ledger_state.add_reorg(&reverted_blocks).expect("Failed to add reorg");
+        let expected = ledger_state.get_reorgs().expect("Failed to get reorgs");
+        assert_eq!(1, expected.len());
+
+        // Drop the handle to ledger_state. Note this does not remove the reorgs from the temporary storage.
+        drop(ledger_state);
+
+        // Initialize a new RPC with the ledger state containing the reorg.
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(Some(directory.clone())).await;
+
+        // Initialize a new request that calls the `getreorgs` endpoint.
+        let request = Request::new(Body::from(
+            r#"{
+	"jsonrpc":"2.0",
+	"id": "1",
+	"method": "getreorgs"
+}"#,
+        ));
+
+        // Send the request to the RPC.
+        let response = handle_rpc(caller(), rpc, request)
+            .await
+            .expect("Test RPC failed to process request");
+
+        // Process the response into reorgs.
+        let actual: Vec<Reorg<Testnet2>> = process_response(response).await;
+
+        // Check the reorgs.
+        assert_eq!(expected, actual);
+        assert_eq!(block_1.hash(), actual[0].reverted_blocks[0].block_hash);
+    }
+
+    #[tokio::test]
+    async fn test_get_storage_stats() {
+        // Initialize a new RPC.
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(None).await;
+
+        // Initialize a new request that calls the `getstoragestats` endpoint.
+        let request = Request::new(Body::from(r#"{"jsonrpc": "2.0", "id": "1", "method": "getstoragestats"}"#));
+
+        // Send the request to the RPC.
+        let response = handle_rpc(caller(), rpc, request)
+            .await
+            .expect("Test RPC failed to process request");
+
+        // Process the response into the storage stats.
+        let actual: StorageStats = process_response(response).await;
+
+        // Check the storage stats of the ledger with the genesis block.
+        let block_headers = actual.maps.iter().find(|map| map.name == MapId::BlockHeaders.name()).unwrap();
+        assert_eq!(1, block_headers.num_keys);
+        assert!(block_headers.approximate_size > 0);
+        assert!(actual.compaction.is_none());
+        assert_eq!(Some(0), actual.reader_lag);
+    }
+
     #[tokio::test]
     async fn test_get_transaction() {
         /// Additional metadata included with a transaction response
@@ -1225,10 +1555,10 @@ mod tests {
         }
 
         // Initialize a new ledger.
-        let ledger = new_ledger_state::<Testnet2, RocksDB, PathBuf>(None);
+        let ledger = new_ledger_state::<Testnet2, MemoryDB, PathBuf>(None);
 
         // Initialize a new RPC.
-        let rpc = new_rpc::<Testnet2, Client<Testnet2>, RocksDB, PathBuf>(None).await;
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(None).await;
 
         // Get the genesis coinbase transaction ID.
         let transaction_id = Testnet2::genesis_block().to_coinbase_transaction().unwrap().transaction_id();
@@ -1270,7 +1600,7 @@ mod tests {
     #[tokio::test]
     async fn test_get_transition() {
         // Initialize a new RPC.
-        let rpc = new_rpc::<Testnet2, Client<Testnet2>, RocksDB, PathBuf>(None).await;
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(None).await;
 
         // Get a transition ID from the genesis coinbase transaction.
         let transition_id = Testnet2::genesis_block().to_coinbase_transaction().unwrap().transitions()[0]
@@ -1313,7 +1643,7 @@ mod tests {
     #[tokio::test]
     async fn test_get_connected_peers() {
         // Initialize a new RPC.
-        let rpc = new_rpc::<Testnet2, Client<Testnet2>, RocksDB, PathBuf>(None).await;
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(None).await;
 
         // Initialize a new request that calls the `gettransition` endpoint.
         let request = Request::new(Body::from(
@@ -1337,6 +1667,33 @@ mod tests {
         assert_eq!(actual, Vec::<String>::new());
     }
 
+    #[tokio::test]
+    async fn test_get_peer_info() {
+        // Initialize a new RPC.
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(None).await;
+
+        // Initialize a new request that calls the `getpeerinfo` endpoint.
+        let request = Request::new(Body::from(
+            r#"{
+	"jsonrpc": "2.0",
+	"id": "1",
+	"method": "getpeerinfo",
+	"params": []
+}"#,
+        ));
+
+        // Send the request to the RPC.
+        let response = handle_rpc(caller(), rpc, request)
+            .await
+            .expect("Test RPC failed to process request");
+
+        // Process the response into the peer info.
+        let actual: Vec<serde_json::Value> = process_response(response).await;
+
+        // Check the peer info.
+        assert!(actual.is_empty());
+    }
+
     #[tokio::test]
     async fn test_send_transaction() {
         let mut rng = ChaChaRng::seed_from_u64(123456789);
@@ -1350,7 +1707,7 @@ mod tests {
             .expect("Failed to create a coinbase transaction");
 
         // Initialize a new rpc.
-        let rpc = new_rpc::<Testnet2, Client<Testnet2>, RocksDB, PathBuf>(None).await;
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(None).await;
 
         // Initialize a new request that calls the `sendtransaction` endpoint.
         let request = Request::new(Body::from(format!(
@@ -1384,7 +1741,7 @@ mod tests {
         let mut tasks = Tasks::new();
 
         // Initialize a new RPC.
-        new_rpc_server::<Testnet2, Client<Testnet2>, RocksDB, PathBuf>(None, &mut tasks).await;
+        new_rpc_server::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(None, &mut tasks).await;
 
         ///
         /// Sends a `sendtransaction` RPC request to the given node address.
@@ -1428,7 +1785,7 @@ mod tests {
         let mut rng = ChaChaRng::seed_from_u64(123456789);
 
         // Initialize a new RPC.
-        let rpc = new_rpc::<Testnet2, Client<Testnet2>, RocksDB, PathBuf>(None).await;
+        let rpc = new_rpc::<Testnet2, Client<Testnet2>, MemoryDB, PathBuf>(None).await;
 
         // Send a transaction to the node.
 
@@ -19,15 +19,19 @@
 //! See [RpcFunctions](../trait.RpcFunctions.html) for documentation of public endpoints.
 
 use crate::{
-    helpers::Status,
-    rpc::{rpc::*, rpc_trait::RpcFunctions},
+    helpers::{AsyncLedgerReader, Status},
+    rpc::{
+        rpc::*,
+        rpc_trait::{ProtectedRpcFunctions, RpcFunctions},
+    },
+    Ban,
     Environment,
-    LedgerReader,
     Peers,
     ProverRequest,
     ProverRouter,
+    Subnet,
 };
-use snarkos_storage::Metadata;
+use snarkos_storage::{storage::StorageStats, BackupManifest, Metadata, ProverState, Reorg};
 use snarkvm::{
     dpc::{AleoAmount, Block, BlockHeader, Blocks, MemoryPool, Network, Transaction, Transactions, Transition},
     utilities::FromBytes,
@@ -35,7 +39,7 @@ use snarkvm::{
 
 use jsonrpc_core::Value;
 use snarkvm::{dpc::Record, utilities::ToBytes};
-use std::{cmp::max, net::SocketAddr, ops::Deref, sync::Arc};
+use std::{cmp::max, net::SocketAddr, ops::Deref, str::FromStr, sync::Arc};
 use tokio::sync::RwLock;
 
 #[derive(Debug, Error)]
@@ -66,11 +70,11 @@ impl From<RpcError> for std::io::Error {
 pub struct RpcInner<N: Network, E: Environment> {
     pub(crate) status: Status,
     peers: Arc<Peers<N, E>>,
-    ledger: LedgerReader<N>,
+    ledger: AsyncLedgerReader<N>,
+    prover_state: Arc<ProverState<N>>,
     prover_router: ProverRouter<N>,
     memory_pool: Arc<RwLock<MemoryPool<N>>>,
     /// RPC credentials for accessing guarded endpoints
-    #[allow(unused)]
     pub(crate) credentials: RpcCredentials,
 }
 
@@ -92,7 +96,8 @@ impl<N: Network, E: Environment> RpcImpl<N, E> {
         credentials: RpcCredentials,
         status: Status,
         peers: Arc<Peers<N, E>>,
-        ledger: LedgerReader<N>,
+        ledger: AsyncLedgerReader<N>,
+        prover_state: Arc<ProverState<N>>,
         prover_router: ProverRouter<N>,
         memory_pool: Arc<RwLock<MemoryPool<N>>>,
     ) -> Self {
@@ -100,11 +105,21 @@ impl<N: Network, E: Environment> RpcImpl<N, E> {
             status,
             peers,
             ledger,
+            prover_state,
             prover_router,
             memory_pool,
             credentials,
         }))
     }
+
+    /// Returns `true` if the given request metadata carries the basic authentication credentials of the RPC server.
+    pub(crate) fn is_authorized(&self, meta: &Meta) -> bool {
+        let credentials = format!("{}:{}", self.credentials.username, self.credentials.password);
+        match &meta.auth {
+            Some(auth) => *auth == format!("Basic {}", base64::encode(credentials)),
+            None => false,
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -146,35 +161,41 @@ impl<N: Network, E: Environment> RpcFunctions<N> for RpcImpl<N, E> {
 
     /// Returns the block given the block height.
     async fn get_block(&self, block_height: u32) -> Result<Block<N>, RpcError> {
-        Ok(self.ledger.get_block(block_height)?)
+        Ok(self.ledger.run(move |ledger| ledger.get_block(block_height)).await?)
     }
 
     /// Returns up to `MAXIMUM_BLOCK_REQUEST` blocks from the given `start_block_height` to `end_block_height` (inclusive).
     async fn get_blocks(&self, start_block_height: u32, end_block_height: u32) -> Result<Vec<Block<N>>, RpcError> {
         let safe_start_height = max(start_block_height, end_block_height.saturating_sub(E::MAXIMUM_BLOCK_REQUEST - 1));
-        Ok(self.ledger.get_blocks(safe_start_height, end_block_height)?)
+        Ok(self
+            .ledger
+            .run(move |ledger| ledger.get_blocks(safe_start_height, end_block_height))
+            .await?)
     }
 
     /// Returns the block height for the given the block hash.
     async fn get_block_height(&self, block_hash: serde_json::Value) -> Result<u32, RpcError> {
         let block_hash: N::BlockHash = serde_json::from_value(block_hash)?;
-        Ok(self.ledger.get_block_height(&block_hash)?)
+        Ok(self.ledger.run(move |ledger| ledger.get_block_height(&block_hash)).await?)
     }
 
     /// Returns the block hash for the given block height, if it exists in the canonical chain.
     async fn get_block_hash(&self, block_height: u32) -> Result<N::BlockHash, RpcError> {
-        Ok(self.ledger.get_block_hash(block_height)?)
+        Ok(self.ledger.run(move |ledger| ledger.get_block_hash(block_height)).await?)
     }
 
     /// Returns up to `MAXIMUM_BLOCK_REQUEST` block hashes from the given `start_block_height` to `end_block_height` (inclusive).
     async fn get_block_hashes(&self, start_block_height: u32, end_block_height: u32) -> Result<Vec<N::BlockHash>, RpcError> {
         let safe_start_height = max(start_block_height, end_block_height.saturating_sub(E::MAXIMUM_BLOCK_REQUEST - 1));
-        Ok(self.ledger.get_block_hashes(safe_start_height, end_block_height)?)
+        Ok(self
+            .ledger
+            .run(move |ledger| ledger.get_block_hashes(safe_start_height, end_block_height))
+            .await?)
     }
 
     /// Returns the block header for the given the block height.
     async fn get_block_header(&self, block_height: u32) -> Result<BlockHeader<N>, RpcError> {
-        Ok(self.ledger.get_block_header(block_height)?)
+        Ok(self.ledger.run(move |ledger| ledger.get_block_header(block_height)).await?)
     }
 
     /// Returns the block template for the next mined block
@@ -201,29 +222,37 @@ impl<N: Network, E: Environment> RpcFunctions<N> for RpcImpl<N, E> {
         let mut transaction_fees = AleoAmount::ZERO;
 
         // Get and filter the transactions from the mempool.
-        let transactions: Vec<String> = self
-            .memory_pool
-            .read()
-            .await
-            .transactions()
-            .iter()
-            .filter(|transaction| {
-                for serial_number in transaction.serial_numbers() {
-                    if let Ok(true) = self.ledger.contains_serial_number(serial_number) {
-                        return false;
-                    }
-                }
-
-                for commitment in transaction.commitments() {
-                    if let Ok(true) = self.ledger.contains_commitment(commitment) {
-                        return false;
-                    }
-                }
+        let transactions = self.memory_pool.read().await.transactions();
+        let transactions: Vec<Transaction<N>> = self
+            .ledger
+            .run(move |ledger| {
+                Ok(transactions
+                    .into_iter()
+                    .filter(|transaction| {
+                        for serial_number in transaction.serial_numbers() {
+                            if let Ok(true) = ledger.contains_serial_number(serial_number) {
+                                return false;
+                            }
+                        }
+
+                        for commitment in transaction.commitments() {
+                            if let Ok(true) = ledger.contains_commitment(commitment) {
+                                return false;
+                            }
+                        }
+
+                        true
+                    })
+                    .collect())
+            })
+            .await?;
 
+        let transactions: Vec<String> = transactions
+            .iter()
+            .map(|transaction| {
                 transaction_fees = transaction_fees.add(transaction.value_balance());
-                true
+                transaction.to_string()
             })
-            .map(|tx| tx.to_string())
             .collect();
 
         // Calculate the final coinbase reward (including the transaction fees).
@@ -243,19 +272,22 @@ impl<N: Network, E: Environment> RpcFunctions<N> for RpcImpl<N, E> {
 
     /// Returns the transactions from the block of the given block height.
     async fn get_block_transactions(&self, block_height: u32) -> Result<Transactions<N>, RpcError> {
-        Ok(self.ledger.get_block_transactions(block_height)?)
+        Ok(self.ledger.run(move |ledger| ledger.get_block_transactions(block_height)).await?)
     }
 
     /// Returns the ciphertext given the commitment.
     async fn get_ciphertext(&self, commitment: serde_json::Value) -> Result<N::RecordCiphertext, RpcError> {
         let commitment: N::Commitment = serde_json::from_value(commitment)?;
-        Ok(self.ledger.get_ciphertext(&commitment)?)
+        Ok(self.ledger.run(move |ledger| ledger.get_ciphertext(&commitment)).await?)
     }
 
     /// Returns the ledger proof for a given record commitment.
     async fn get_ledger_proof(&self, record_commitment: serde_json::Value) -> Result<String, RpcError> {
         let record_commitment: N::Commitment = serde_json::from_value(record_commitment)?;
-        let ledger_proof = self.ledger.get_ledger_inclusion_proof(record_commitment)?;
+        let ledger_proof = self
+            .ledger
+            .run(move |ledger| ledger.get_ledger_inclusion_proof(record_commitment))
+            .await?;
         Ok(hex::encode(ledger_proof.to_bytes_le().expect("Failed to serialize ledger proof")))
     }
 
@@ -264,11 +296,30 @@ impl<N: Network, E: Environment> RpcFunctions<N> for RpcImpl<N, E> {
         Ok(self.memory_pool.read().await.transactions())
     }
 
+    /// Returns the reorgs in the node's reorg journal.
+    async fn get_reorgs(&self) -> Result<Vec<Reorg<N>>, RpcError> {
+        Ok(self.ledger.run(|ledger| ledger.get_reorgs()).await?)
+    }
+
+    /// Returns the size and health statistics of the ledger storage.
+    async fn get_storage_stats(&self) -> Result<StorageStats, RpcError> {
+        let mut stats = self.ledger.run(|ledger| ledger.storage_stats()).await?;
+        stats.reader_lag = self.ledger.reader_lag();
+        Ok(stats)
+    }
+
     /// Returns a transaction with metadata and decrypted records given the transaction ID.
     async fn get_transaction(&self, transaction_id: serde_json::Value) -> Result<Value, RpcError> {
         let transaction_id: N::TransactionID = serde_json::from_value(transaction_id)?;
-        let transaction: Transaction<N> = self.ledger.get_transaction(&transaction_id)?;
-        let metadata: Metadata<N> = self.ledger.get_transaction_metadata(&transaction_id)?;
+        let (transaction, metadata): (Transaction<N>, Metadata<N>) = self
+            .ledger
+            .run(move |ledger| {
+                Ok((
+                    ledger.get_transaction(&transaction_id)?,
+                    ledger.get_transaction_metadata(&transaction_id)?,
+                ))
+            })
+            .await?;
         let decrypted_records: Vec<Record<N>> = transaction.to_records().collect();
         Ok(serde_json::json!({ "transaction": transaction, "metadata": metadata, "decrypted_records": decrypted_records }))
     }
@@ -276,7 +327,7 @@ impl<N: Network, E: Environment> RpcFunctions<N> for RpcImpl<N, E> {
     /// Returns a transition given the transition ID.
     async fn get_transition(&self, transition_id: serde_json::Value) -> Result<Transition<N>, RpcError> {
         let transition_id: N::TransitionID = serde_json::from_value(transition_id)?;
-        Ok(self.ledger.get_transition(&transition_id)?)
+        Ok(self.ledger.run(move |ledger| ledger.get_transition(&transition_id)).await?)
     }
 
     /// Returns the peers currently connected to this node.
@@ -284,6 +335,25 @@ impl<N: Network, E: Environment> RpcFunctions<N> for RpcImpl<N, E> {
         Ok(self.peers.connected_peers().await)
     }
 
+    /// Returns the peer ID and reputation of each peer connected to this node.
+    async fn get_peer_info(&self) -> Result<Vec<Value>, RpcError> {
+        Ok(self
+            .peers
+            .connected_peer_reputations()
+            .await
+            .into_iter()
+            .map(|(peer_ip, peer_id, reputation)| {
+                serde_json::json!({
+                    "ip": peer_ip,
+                    "peer_id": peer_id,
+                    "reputation_score": reputation.score::<E>(),
+                    "standing": reputation.standing::<E>(),
+                    "misbehaviors": reputation.misbehaviors(),
+                })
+            })
+            .collect())
+    }
+
     /// Returns the current state of this node.
     async fn get_node_state(&self) -> Result<Value, RpcError> {
         let candidate_peers = self.peers.candidate_peers().await;
@@ -291,10 +361,13 @@ impl<N: Network, E: Environment> RpcFunctions<N> for RpcImpl<N, E> {
         let number_of_candidate_peers = candidate_peers.len();
         let number_of_connected_peers = connected_peers.len();
         let number_of_connected_sync_nodes = self.peers.number_of_connected_sync_nodes().await;
+        let number_of_tried_peers = self.peers.number_of_tried_peers().await;
 
         let latest_block_hash = self.ledger.latest_block_hash();
         let latest_block_height = self.ledger.latest_block_height();
         let latest_cumulative_weight = self.ledger.latest_cumulative_weight();
+        let pruned_block_height = self.ledger.pruned_block_height();
+        let storage_metrics = self.ledger.metrics();
 
         Ok(serde_json::json!({
             "candidate_peers": candidate_peers,
@@ -305,8 +378,11 @@ impl<N: Network, E: Environment> RpcFunctions<N> for RpcImpl<N, E> {
             "number_of_candidate_peers": number_of_candidate_peers,
             "number_of_connected_peers": number_of_connected_peers,
             "number_of_connected_sync_nodes": number_of_connected_sync_nodes,
+            "number_of_tried_peers": number_of_tried_peers,
+            "pruned_block_height": pruned_block_height,
             "software": format!("snarkOS {}", env!("CARGO_PKG_VERSION")),
             "status": self.status.to_string(),
+            "storage_metrics": storage_metrics,
             "type": E::NODE_TYPE,
             "version": E::MESSAGE_VERSION,
         }))
@@ -323,3 +399,45 @@ impl<N: Network, E: Environment> RpcFunctions<N> for RpcImpl<N, E> {
         Ok(transaction.transaction_id())
     }
 }
+
+#[async_trait::async_trait]
+impl<N: Network, E: Environment> ProtectedRpcFunctions<N> for RpcImpl<N, E> {
+    /// Writes a backup of the ledger and the prover to the given directory on the node, and returns its manifest.
+    async fn backup(&self, backup_path: String) -> Result<BackupManifest<N>, RpcError> {
+        if backup_path.is_empty() {
+            return Err(RpcError::Message("The backup path must not be empty".to_string()));
+        }
+
+        let prover_state = self.prover_state.clone();
+        Ok(self.ledger.run(move |ledger| ledger.backup(&prover_state, &backup_path)).await?)
+    }
+
+    /// Bans the given IP address or subnet, for the given duration or permanently, and returns the ban.
+    async fn ban_peer(&self, subnet: String, duration_in_secs: Option<u64>, reason: Option<String>) -> Result<Ban, RpcError> {
+        let expiry = match duration_in_secs {
+            Some(0) => return Err(RpcError::Message("The ban duration must be positive".to_string())),
+            Some(duration_in_secs) => {
+                let duration_in_secs = duration_in_secs.min(i64::MAX as u64) as i64;
+                Some(chrono::Utc::now().timestamp().saturating_add(duration_in_secs))
+            }
+            None => None,
+        };
+        let ban = Ban {
+            subnet: Subnet::from_str(&subnet)?,
+            expiry,
+            reason,
+        };
+        self.peers.ban(ban.clone()).await?;
+        Ok(ban)
+    }
+
+    /// Lifts the ban of the given IP address or subnet, and returns `true` if it was banned.
+    async fn unban_peer(&self, subnet: String) -> Result<bool, RpcError> {
+        Ok(self.peers.unban(&Subnet::from_str(&subnet)?).await?)
+    }
+
+    /// Returns the list of active bans.
+    async fn list_banned(&self) -> Result<Vec<Ban>, RpcError> {
+        Ok(self.peers.banned().await)
+    }
+}
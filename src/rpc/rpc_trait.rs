@@ -16,7 +16,8 @@
 
 //! Definition of the public and private RPC endpoints.
 
-use crate::rpc::rpc_impl::RpcError;
+use crate::{rpc::rpc_impl::RpcError, Ban};
+use snarkos_storage::{storage::StorageStats, BackupManifest, Reorg};
 use snarkvm::dpc::{Block, BlockHeader, Network, Transaction, Transactions, Transition};
 
 use std::net::SocketAddr;
@@ -78,6 +79,12 @@ pub trait RpcFunctions<N: Network> {
     #[doc = include_str!("./documentation/public_endpoints/getmemorypool.md")]
     async fn get_memory_pool(&self) -> Result<Vec<Transaction<N>>, RpcError>;
 
+    #[doc = include_str!("./documentation/public_endpoints/getreorgs.md")]
+    async fn get_reorgs(&self) -> Result<Vec<Reorg<N>>, RpcError>;
+
+    #[doc = include_str!("./documentation/public_endpoints/getstoragestats.md")]
+    async fn get_storage_stats(&self) -> Result<StorageStats, RpcError>;
+
     #[doc = include_str!("./documentation/public_endpoints/gettransaction.md")]
     async fn get_transaction(&self, transaction_id: serde_json::Value) -> Result<serde_json::Value, RpcError>;
 
@@ -87,6 +94,9 @@ pub trait RpcFunctions<N: Network> {
     #[doc = include_str!("./documentation/public_endpoints/getconnectedpeers.md")]
     async fn get_connected_peers(&self) -> Result<Vec<SocketAddr>, RpcError>;
 
+    #[doc = include_str!("./documentation/public_endpoints/getpeerinfo.md")]
+    async fn get_peer_info(&self) -> Result<Vec<serde_json::Value>, RpcError>;
+
     #[doc = include_str!("./documentation/public_endpoints/getnodestate.md")]
     async fn get_node_state(&self) -> Result<serde_json::Value, RpcError>;
 
@@ -94,34 +104,46 @@ pub trait RpcFunctions<N: Network> {
     async fn send_transaction(&self, transaction_bytes: String) -> Result<N::TransactionID, RpcError>;
 }
 
-// /// Definition of private RPC endpoints that require authentication.
-// #[async_trait::async_trait]
-// pub trait ProtectedRpcFunctions {
-//     #[doc = include_str!("../documentation/private_endpoints/createrawtransaction.md")]
-//     async fn create_raw_transaction(
-//         &self,
-//         transaction_input: TransactionInputs,
-//     ) -> Result<CreateRawTransactionOuput, RpcError>;
-//
-//     #[doc = include_str!("../documentation/private_endpoints/createtransaction.md")]
-//     async fn create_transaction(
-//         &self,
-//         private_keys: [String; 2], // TODO (howardwu): Genericize this.
-//         transaction_kernel: String,
-//     ) -> Result<CreateRawTransactionOuput, RpcError>;
-//
-//     #[doc = include_str!("../documentation/private_endpoints/getrecordcommitments.md")]
-//     async fn get_record_commitments(&self) -> Result<Vec<String>, RpcError>;
-//
-//     #[doc = include_str!("../documentation/private_endpoints/getrecordcommitmentcount.md")]
-//     async fn get_record_commitment_count(&self) -> Result<usize, RpcError>;
-//
-//     #[doc = include_str!("../documentation/private_endpoints/getrawrecord.md")]
-//     async fn get_raw_record(&self, record_commitment: String) -> Result<String, RpcError>;
-//
-//     #[doc = include_str!("../documentation/private_endpoints/decryptrecord.md")]
-//     async fn decrypt_record(&self, decryption_input: DecryptRecordInput) -> Result<String, RpcError>;
-//
-//     #[doc = include_str!("../documentation/private_endpoints/connect.md")]
-//     async fn connect(&self, addresses: Vec<SocketAddr>);
-// }
+/// Definition of private RPC endpoints that require authentication.
+#[async_trait::async_trait]
+pub trait ProtectedRpcFunctions<N: Network> {
+    #[doc = include_str!("./documentation/private_endpoints/backup.md")]
+    async fn backup(&self, backup_path: String) -> Result<BackupManifest<N>, RpcError>;
+
+    #[doc = include_str!("./documentation/private_endpoints/banpeer.md")]
+    async fn ban_peer(&self, subnet: String, duration_in_secs: Option<u64>, reason: Option<String>) -> Result<Ban, RpcError>;
+
+    #[doc = include_str!("./documentation/private_endpoints/unbanpeer.md")]
+    async fn unban_peer(&self, subnet: String) -> Result<bool, RpcError>;
+
+    #[doc = include_str!("./documentation/private_endpoints/listbanned.md")]
+    async fn list_banned(&self) -> Result<Vec<Ban>, RpcError>;
+
+    // #[doc = include_str!("../documentation/private_endpoints/createrawtransaction.md")]
+    // async fn create_raw_transaction(
+    //     &self,
+    //     transaction_input: TransactionInputs,
+    // ) -> Result<CreateRawTransactionOuput, RpcError>;
+    //
+    // #[doc = include_str!("../documentation/private_endpoints/createtransaction.md")]
+    // async fn create_transaction(
+    //     &self,
+    //     private_keys: [String; 2], // TODO (howardwu): Genericize this.
+    //     transaction_kernel: String,
+    // ) -> Result<CreateRawTransactionOuput, RpcError>;
+    //
+    // #[doc = include_str!("../documentation/private_endpoints/getrecordcommitments.md")]
+    // async fn get_record_commitments(&self) -> Result<Vec<String>, RpcError>;
+    //
+    // #[doc = include_str!("../documentation/private_endpoints/getrecordcommitmentcount.md")]
+    // async fn get_record_commitment_count(&self) -> Result<usize, RpcError>;
+    //
+    // #[doc = include_str!("../documentation/private_endpoints/getrawrecord.md")]
+    // async fn get_raw_record(&self, record_commitment: String) -> Result<String, RpcError>;
+    //
+    // #[doc = include_str!("../documentation/private_endpoints/decryptrecord.md")]
+    // async fn decrypt_record(&self, decryption_input: DecryptRecordInput) -> Result<String, RpcError>;
+    //
+    // #[doc = include_str!("../documentation/private_endpoints/connect.md")]
+    // async fn connect(&self, addresses: Vec<SocketAddr>);
+}
@@ -0,0 +1,99 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::storage::SCHEMA_VERSION;
+use snarkvm::dpc::Network;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The name of the manifest file in a backup directory.
+const BACKUP_MANIFEST_FILE: &str = "manifest.json";
+/// The name of the ledger checkpoint in a backup directory.
+const BACKUP_LEDGER_DIR: &str = "ledger";
+/// The name of the prover checkpoint in a backup directory.
+const BACKUP_PROVER_DIR: &str = "prover";
+
+///
+/// The manifest of a backup, describing the ledger state it was taken at.
+///
+/// A backup directory holds a checkpoint of the ledger storage, a checkpoint of the prover storage,
+/// and this manifest. The manifest is written last, so a backup without a manifest is incomplete.
+///
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BackupManifest<N: Network> {
+    /// The network ID of the ledger.
+    pub network_id: u16,
+    /// The storage schema version of the checkpoints.
+    pub schema_version: u32,
+    /// The latest block height of the ledger.
+    pub block_height: u32,
+    /// The latest block hash of the ledger.
+    pub block_hash: N::BlockHash,
+    /// The latest ledger root of the ledger.
+    pub ledger_root: N::LedgerRoot,
+    /// The UNIX timestamp of the backup.
+    pub timestamp: i64,
+}
+
+impl<N: Network> BackupManifest<N> {
+    /// Returns the path of the ledger checkpoint in the given backup directory.
+    pub(crate) fn ledger_path<P: AsRef<Path>>(backup: P) -> PathBuf {
+        backup.as_ref().join(BACKUP_LEDGER_DIR)
+    }
+
+    /// Returns the path of the prover checkpoint in the given backup directory.
+    pub(crate) fn prover_path<P: AsRef<Path>>(backup: P) -> PathBuf {
+        backup.as_ref().join(BACKUP_PROVER_DIR)
+    }
+
+    /// Reads the manifest from the given backup directory, and ensures it is compatible with this ledger.
+    pub fn read<P: AsRef<Path>>(backup: P) -> Result<Self> {
+        let path = backup.as_ref().join(BACKUP_MANIFEST_FILE);
+        if !path.is_file() {
+            return Err(anyhow!("The backup at {} is missing its manifest", backup.as_ref().display()));
+        }
+
+        let manifest: Self = serde_json::from_slice(&fs::read(&path)?)?;
+        if manifest.network_id != N::NETWORK_ID {
+            return Err(anyhow!(
+                "The backup is for network {}, expected network {}",
+                manifest.network_id,
+                N::NETWORK_ID
+            ));
+        }
+        if manifest.schema_version != SCHEMA_VERSION {
+            return Err(anyhow!(
+                "The backup has storage schema version {}, expected version {}",
+                manifest.schema_version,
+                SCHEMA_VERSION
+            ));
+        }
+        Ok(manifest)
+    }
+
+    /// Writes the manifest to the given backup directory.
+    pub(crate) fn write<P: AsRef<Path>>(&self, backup: P) -> Result<()> {
+        Ok(fs::write(
+            backup.as_ref().join(BACKUP_MANIFEST_FILE),
+            serde_json::to_vec_pretty(self)?,
+        )?)
+    }
+}
@@ -0,0 +1,321 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::dpc::prelude::*;
+
+use anyhow::Result;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// The maximum number of block headers held in the block cache.
+const MAXIMUM_CACHED_BLOCK_HEADERS: usize = 4096;
+/// The maximum number of blocks held in the block cache.
+const MAXIMUM_CACHED_BLOCKS: usize = 256;
+/// The maximum number of serialized blocks held in the block cache.
+const MAXIMUM_CACHED_SERIALIZED_BLOCKS: usize = 256;
+
+///
+/// The number of hits and misses of each cache in the block cache.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheMetrics {
+    pub block_header_hits: u64,
+    pub block_header_misses: u64,
+    pub block_hits: u64,
+    pub block_misses: u64,
+    pub serialized_block_hits: u64,
+    pub serialized_block_misses: u64,
+}
+
+///
+/// A read-through cache for the block headers, blocks, and serialized blocks in storage.
+///
+/// Entries are keyed by block hash, so a cached entry never goes stale when the canonical chain changes,
+/// as the block height is always resolved from storage first. Reverted blocks are removed from the cache
+/// to free their space, and newly added blocks are inserted, as peers are likely to request them next.
+///
+#[derive(Debug)]
+pub(crate) struct BlockCache<N: Network> {
+    block_headers: Mutex<LruCache<N::BlockHash, BlockHeader<N>>>,
+    blocks: Mutex<LruCache<N::BlockHash, Block<N>>>,
+    serialized_blocks: Mutex<LruCache<N::BlockHash, Vec<u8>>>,
+    block_header_counter: CacheCounter,
+    block_counter: CacheCounter,
+    serialized_block_counter: CacheCounter,
+}
+
+impl<N: Network> Default for BlockCache<N> {
+    fn default() -> Self {
+        Self {
+            block_headers: Mutex::new(LruCache::new(MAXIMUM_CACHED_BLOCK_HEADERS)),
+            blocks: Mutex::new(LruCache::new(MAXIMUM_CACHED_BLOCKS)),
+            serialized_blocks: Mutex::new(LruCache::new(MAXIMUM_CACHED_SERIALIZED_BLOCKS)),
+            block_header_counter: Default::default(),
+            block_counter: Default::default(),
+            serialized_block_counter: Default::default(),
+        }
+    }
+}
+
+impl<N: Network> BlockCache<N> {
+    /// Returns the block header for the given block hash, loading it from storage on a cache miss.
+    pub(crate) fn get_block_header<F: FnOnce() -> Result<BlockHeader<N>>>(
+        &self,
+        block_hash: &N::BlockHash,
+        load: F,
+    ) -> Result<BlockHeader<N>> {
+        if let Some(block_header) = self.block_headers.lock().get(block_hash) {
+            self.block_header_counter.hit(1);
+            return Ok(block_header);
+        }
+        self.block_header_counter.miss(1);
+
+        let block_header = load()?;
+        self.block_headers.lock().insert(*block_hash, block_header.clone());
+        Ok(block_header)
+    }
+
+    /// Returns the block for the given block hash, loading it from storage on a cache miss.
+    pub(crate) fn get_block<F: FnOnce() -> Result<Block<N>>>(&self, block_hash: &N::BlockHash, load: F) -> Result<Block<N>> {
+        if let Some(block) = self.blocks.lock().get(block_hash) {
+            self.block_counter.hit(1);
+            return Ok(block);
+        }
+        self.block_counter.miss(1);
+
+        let block = load()?;
+        self.blocks.lock().insert(block.hash(), block.clone());
+        Ok(block)
+    }
+
+    ///
+    /// Returns the blocks for the given block hashes, in order.
+    ///
+    /// If any of the blocks is missing from the cache, all of the blocks are loaded from storage,
+    /// as loading them in a single pass is cheaper than loading each missing block individually.
+    ///
+    pub(crate) fn get_blocks<F: FnOnce() -> Result<Vec<Block<N>>>>(&self, block_hashes: &[N::BlockHash], load: F) -> Result<Vec<Block<N>>> {
+        let cached_blocks = {
+            let mut cache = self.blocks.lock();
+            block_hashes
+                .iter()
+                .filter_map(|block_hash| cache.get(block_hash))
+                .collect::<Vec<_>>()
+        };
+
+        let number_of_misses = (block_hashes.len() - cached_blocks.len()) as u64;
+        self.block_counter.hit(cached_blocks.len() as u64);
+        self.block_counter.miss(number_of_misses);
+
+        if number_of_misses == 0 {
+            return Ok(cached_blocks);
+        }
+
+        let blocks = load()?;
+        {
+            let mut cache = self.blocks.lock();
+            for block in &blocks {
+                cache.insert(block.hash(), block.clone());
+            }
+        }
+        Ok(blocks)
+    }
+
+    ///
+    /// Returns the serialized blocks for the given block hashes, in order.
+    ///
+    /// If any of the serialized blocks is missing from the cache, the blocks are loaded
+    /// with the given function, and the missing blocks are serialized.
+    ///
+    pub(crate) fn get_serialized_blocks<F: FnOnce() -> Result<Vec<Block<N>>>>(
+        &self,
+        block_hashes: &[N::BlockHash],
+        load: F,
+    ) -> Result<Vec<Vec<u8>>> {
+        let mut cached_blocks = {
+            let mut cache = self.serialized_blocks.lock();
+            block_hashes
+                .iter()
+                .filter_map(|block_hash| Some((*block_hash, cache.get(block_hash)?)))
+                .collect::<HashMap<_, _>>()
+        };
+
+        let number_of_misses = (block_hashes.len() - cached_blocks.len()) as u64;
+        self.serialized_block_counter.hit(cached_blocks.len() as u64);
+        self.serialized_block_counter.miss(number_of_misses);
+
+        if number_of_misses == 0 {
+            return Ok(block_hashes
+                .iter()
+                .filter_map(|block_hash| cached_blocks.remove(block_hash))
+                .collect());
+        }
+
+        let blocks = load()?;
+        let mut serialized_blocks = Vec::with_capacity(blocks.len());
+        for block in &blocks {
+            match cached_blocks.remove(&block.hash()) {
+                Some(serialized_block) => serialized_blocks.push(serialized_block),
+                None => {
+                    let serialized_block = bincode::serialize(block)?;
+                    self.serialized_blocks.lock().insert(block.hash(), serialized_block.clone());
+                    serialized_blocks.push(serialized_block);
+                }
+            }
+        }
+        Ok(serialized_blocks)
+    }
+
+    /// Inserts the given block, which was added to the canonical chain, into the cache.
+    pub(crate) fn insert_block(&self, block: &Block<N>) {
+        self.block_headers.lock().insert(block.hash(), block.header().clone());
+        self.blocks.lock().insert(block.hash(), block.clone());
+    }
+
+    /// Removes the given block, which was reverted from the canonical chain, from the cache.
+    pub(crate) fn remove_block(&self, block_hash: &N::BlockHash) {
+        self.block_headers.lock().remove(block_hash);
+        self.blocks.lock().remove(block_hash);
+        self.serialized_blocks.lock().remove(block_hash);
+    }
+
+    /// Returns the number of hits and misses of each cache.
+    pub(crate) fn metrics(&self) -> CacheMetrics {
+        CacheMetrics {
+            block_header_hits: self.block_header_counter.hits.load(Ordering::SeqCst),
+            block_header_misses: self.block_header_counter.misses.load(Ordering::SeqCst),
+            block_hits: self.block_counter.hits.load(Ordering::SeqCst),
+            block_misses: self.block_counter.misses.load(Ordering::SeqCst),
+            serialized_block_hits: self.serialized_block_counter.hits.load(Ordering::SeqCst),
+            serialized_block_misses: self.serialized_block_counter.misses.load(Ordering::SeqCst),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct CacheCounter {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl CacheCounter {
+    fn hit(&self, count: u64) {
+        self.hits.fetch_add(count, Ordering::SeqCst);
+    }
+
+    fn miss(&self, count: u64) {
+        self.misses.fetch_add(count, Ordering::SeqCst);
+    }
+}
+
+///
+/// A map with a fixed capacity, which evicts its least recently used entry when it is full.
+///
+#[derive(Debug)]
+struct LruCache<K, V> {
+    /// The maximum number of entries.
+    capacity: usize,
+    /// The entries := key => (value, last used).
+    entries: HashMap<K, (V, u64)>,
+    /// The keys of the entries, ordered from the least recently used.
+    recently_used: BTreeMap<u64, K>,
+    /// The counter that orders the uses of the entries.
+    counter: u64,
+}
+
+impl<K: Copy + Eq + Hash, V: Clone> LruCache<K, V> {
+    /// Initializes a new instance of the cache with the given capacity.
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Default::default(),
+            recently_used: Default::default(),
+            counter: 0,
+        }
+    }
+
+    /// Returns the value for the given key, if it exists, and marks it as the most recently used.
+    fn get(&mut self, key: &K) -> Option<V> {
+        self.counter += 1;
+        let (value, last_used) = self.entries.get_mut(key)?;
+        self.recently_used.remove(last_used);
+        self.recently_used.insert(self.counter, *key);
+        *last_used = self.counter;
+        Some(value.clone())
+    }
+
+    /// Inserts the given key and value, evicting the least recently used entry if the cache is full.
+    fn insert(&mut self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        self.remove(&key);
+        while self.entries.len() >= self.capacity {
+            let least_recently_used = match self.recently_used.keys().next() {
+                Some(least_recently_used) => *least_recently_used,
+                None => break,
+            };
+            if let Some(key) = self.recently_used.remove(&least_recently_used) {
+                self.entries.remove(&key);
+            }
+        }
+
+        self.counter += 1;
+        self.recently_used.insert(self.counter, key);
+        self.entries.insert(key, (value, self.counter));
+    }
+
+    /// Removes the entry for the given key, if it exists.
+    fn remove(&mut self, key: &K) {
+        if let Some((_, last_used)) = self.entries.remove(key) {
+            self.recently_used.remove(&last_used);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lru_cache_evicts_least_recently_used() {
+        let mut cache = LruCache::<u32, u32>::new(2);
+        cache.insert(1, 10);
+        cache.insert(2, 20);
+
+        // Use the first entry, so that the second entry is the least recently used.
+        assert_eq!(Some(10), cache.get(&1));
+        cache.insert(3, 30);
+
+        assert_eq!(Some(10), cache.get(&1));
+        assert_eq!(None, cache.get(&2));
+        assert_eq!(Some(30), cache.get(&3));
+
+        // Replace an entry, and remove another.
+        cache.insert(1, 11);
+        cache.remove(&3);
+        assert_eq!(Some(11), cache.get(&1));
+        assert_eq!(None, cache.get(&3));
+        assert_eq!(1, cache.entries.len());
+        assert_eq!(1, cache.recently_used.len());
+    }
+}
@@ -14,5 +14,26 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
+pub mod backup;
+pub use backup::*;
+
+pub mod block_cache;
+pub use block_cache::*;
+
 pub mod block_locators;
 pub use block_locators::*;
+
+pub(crate) mod notifier;
+pub(crate) use notifier::*;
+
+pub mod reorg;
+pub use reorg::*;
+
+pub mod repair;
+pub use repair::*;
+
+pub mod snapshot;
+pub use snapshot::*;
+
+pub mod verification;
+pub use verification::*;
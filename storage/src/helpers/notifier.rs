@@ -0,0 +1,77 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc, Weak},
+};
+
+/// The notifiers of the writable ledgers in this process, keyed by the storage path they were opened with.
+static NOTIFIERS: Lazy<Mutex<HashMap<PathBuf, Weak<LedgerNotifier>>>> = Lazy::new(Default::default);
+
+///
+/// A change to a writable ledger, which is pushed to the read-only ledgers in the same process.
+///
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum LedgerNotification {
+    /// A block was added at the given block height.
+    BlockAdded(u32),
+    /// The ledger was reverted to the given block height.
+    Reverted(u32),
+}
+
+///
+/// Pushes the changes of a writable ledger to the read-only ledgers that subscribed to it.
+///
+/// A read-only ledger may only subscribe to a writable ledger in the same process,
+/// and must otherwise poll its storage for changes.
+///
+#[derive(Debug, Default)]
+pub(crate) struct LedgerNotifier {
+    /// The channels of the read-only ledgers that subscribed to the writable ledger.
+    subscribers: Mutex<Vec<mpsc::Sender<LedgerNotification>>>,
+}
+
+impl LedgerNotifier {
+    /// Registers a new notifier for the writable ledger at the given storage path, replacing any previous notifier.
+    pub(crate) fn register<P: AsRef<Path>>(path: P) -> Arc<Self> {
+        let notifier = Arc::new(Self::default());
+
+        let mut notifiers = NOTIFIERS.lock();
+        // Remove the notifiers of the writable ledgers that have been closed.
+        notifiers.retain(|_, notifier| notifier.strong_count() > 0);
+        notifiers.insert(path.as_ref().to_path_buf(), Arc::downgrade(&notifier));
+
+        notifier
+    }
+
+    /// Subscribes to the writable ledger at the given storage path, if it is open in this process.
+    pub(crate) fn subscribe<P: AsRef<Path>>(path: P) -> Option<mpsc::Receiver<LedgerNotification>> {
+        let notifier = NOTIFIERS.lock().get(path.as_ref())?.upgrade()?;
+
+        let (sender, receiver) = mpsc::channel();
+        notifier.subscribers.lock().push(sender);
+        Some(receiver)
+    }
+
+    /// Sends the given notification to each subscriber, and removes the subscribers that have been closed.
+    pub(crate) fn notify(&self, notification: LedgerNotification) {
+        self.subscribers.lock().retain(|subscriber| subscriber.send(notification).is_ok());
+    }
+}
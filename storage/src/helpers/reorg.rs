@@ -0,0 +1,67 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::dpc::{Block, Network};
+
+use serde::{Deserialize, Serialize};
+
+///
+/// A reorg of the canonical chain, as recorded in the reorg journal.
+///
+/// *Attention*: This data structure is intended for usage in storage.
+/// Modifications to its layout will impact how reorgs are represented in storage.
+///
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Reorg<N: Network> {
+    /// The UNIX timestamp of the reorg.
+    pub timestamp: i64,
+    /// The block height of the latest block before the reorg.
+    pub old_block_height: u32,
+    /// The block hash of the latest block before the reorg.
+    pub old_block_hash: N::BlockHash,
+    /// The block height of the latest block after the reorg.
+    pub new_block_height: u32,
+    /// The block hash of the latest block after the reorg.
+    pub new_block_hash: N::BlockHash,
+    /// The block height of the common ancestor that the ledger was reverted to.
+    pub common_ancestor: u32,
+    /// The reverted blocks, in increasing order of block height.
+    pub reverted_blocks: Vec<RevertedBlock<N>>,
+}
+
+///
+/// A block that was reverted from the canonical chain in a reorg.
+///
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RevertedBlock<N: Network> {
+    /// The block height of the reverted block.
+    pub block_height: u32,
+    /// The block hash of the reverted block.
+    pub block_hash: N::BlockHash,
+    /// The transaction IDs of the reverted block.
+    pub transaction_ids: Vec<N::TransactionID>,
+}
+
+impl<N: Network> RevertedBlock<N> {
+    /// Initializes a new instance of `RevertedBlock` from the given block.
+    pub fn new(block: &Block<N>) -> Self {
+        Self {
+            block_height: block.height(),
+            block_hash: block.hash(),
+            transaction_ids: block.transactions().transaction_ids().collect(),
+        }
+    }
+}
@@ -0,0 +1,94 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::storage::MapId;
+use snarkvm::dpc::Network;
+
+use serde::{Deserialize, Serialize};
+
+///
+/// The block to revert the ledger to when repairing the ledger in storage.
+///
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RepairTarget<N: Network> {
+    /// The block at the given block height.
+    BlockHeight(u32),
+    /// The block with the given block hash, which must be on the canonical chain.
+    BlockHash(N::BlockHash),
+}
+
+///
+/// The action of a change made while repairing the ledger in storage.
+///
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepairAction {
+    /// An entry that is missing from a derived index is inserted.
+    Insert,
+    /// An orphaned or incorrect entry is removed from a derived index.
+    Remove,
+    /// A block is reverted from the canonical chain.
+    Revert,
+}
+
+///
+/// A change made while repairing the ledger in storage.
+///
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RepairChange {
+    /// The action of the change.
+    pub action: RepairAction,
+    /// The name of the map in storage that is changed.
+    pub map: String,
+    /// The block height the change belongs to, if it is known.
+    pub block_height: Option<u32>,
+    /// A description of the changed entry.
+    pub entry: String,
+}
+
+///
+/// The report of repairing the ledger in storage, listing every change that was made,
+/// or that would be made in dry-run mode.
+///
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RepairReport {
+    /// If `true`, the changes were not written to storage.
+    pub dry_run: bool,
+    /// The latest block height in storage, before the repair.
+    pub latest_block_height: u32,
+    /// The block height the ledger is reverted to, if a target was given.
+    pub target_block_height: Option<u32>,
+    /// The list of changes.
+    pub changes: Vec<RepairChange>,
+}
+
+impl RepairReport {
+    /// Returns `true` if the repair made no changes.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Records a change with the given action, in the given map, at the given block height.
+    pub(crate) fn add(&mut self, action: RepairAction, map_id: MapId, block_height: Option<u32>, entry: String) {
+        trace!("Repair will {:?} an entry in the {} map: {}", action, map_id.name(), entry);
+        self.changes.push(RepairChange {
+            action,
+            map: map_id.name().to_string(),
+            block_height,
+            entry,
+        });
+    }
+}
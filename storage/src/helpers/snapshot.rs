@@ -0,0 +1,259 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::storage::{DataMap, Map, MapId};
+use snarkvm::dpc::Network;
+
+use anyhow::{anyhow, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::io::{ErrorKind, Read, Write};
+
+/// The magic bytes at the start of every ledger snapshot.
+const SNAPSHOT_MAGIC: [u8; 8] = *b"aleosnap";
+/// The version of the snapshot file format.
+const SNAPSHOT_VERSION: u16 = 1;
+/// The number of bytes of entries after which a chunk is written out.
+const SNAPSHOT_CHUNK_SIZE_IN_BYTES: usize = 4 * 1024 * 1024;
+/// The maximum number of bytes permitted in a single chunk when reading a snapshot.
+const MAXIMUM_SNAPSHOT_CHUNK_SIZE_IN_BYTES: usize = 256 * 1024 * 1024;
+
+///
+/// The header of a ledger snapshot, describing the ledger state it was taken from.
+///
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotHeader<N: Network> {
+    /// The network ID of the ledger.
+    pub network_id: u16,
+    /// The storage schema version of the snapshot entries.
+    pub schema_version: u32,
+    /// The latest block height of the ledger.
+    pub block_height: u32,
+    /// The latest block hash of the ledger.
+    pub block_hash: N::BlockHash,
+    /// The latest ledger root of the ledger.
+    pub ledger_root: N::LedgerRoot,
+}
+
+///
+/// A set of raw storage entries from one map in a ledger snapshot.
+///
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SnapshotChunk {
+    /// The ID of the map the entries belong to.
+    map_id: Vec<u8>,
+    /// The serialized key-value pairs of the map.
+    entries: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl SnapshotChunk {
+    /// Returns `true` if the chunk contains entries of the given map.
+    pub fn is_for(&self, map_id: MapId) -> bool {
+        self.map_id == map_id.as_bytes()
+    }
+
+    /// Returns the ID of the map the entries belong to, for display purposes.
+    pub fn map_name(&self) -> String {
+        String::from_utf8_lossy(&self.map_id).into_owned()
+    }
+
+    /// Returns the number of entries in the chunk.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the chunk contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Inserts the entries of the chunk into the given map.
+    pub fn insert_into<'a, K: 'a + Serialize + DeserializeOwned, V: 'a + Serialize + DeserializeOwned>(
+        &self,
+        map: &'a DataMap<K, V>,
+    ) -> Result<()> {
+        for (key, value) in &self.entries {
+            map.insert(&bincode::deserialize::<K>(key)?, &bincode::deserialize::<V>(value)?)?;
+        }
+        Ok(())
+    }
+}
+
+///
+/// A streaming writer for ledger snapshots.
+///
+/// A snapshot is laid out as the magic bytes and format version, followed by a sequence of frames.
+/// Each frame is := (length as u32, payload, crc32 checksum of the payload as u32), and the first frame
+/// contains the header. The snapshot is terminated by a frame of length zero, without a payload or checksum.
+///
+pub struct SnapshotWriter<W: Write> {
+    writer: W,
+    chunk: SnapshotChunk,
+    chunk_size_in_bytes: usize,
+}
+
+impl<W: Write> SnapshotWriter<W> {
+    /// Initializes a new snapshot writer, and writes the given header.
+    pub fn new<N: Network>(mut writer: W, header: &SnapshotHeader<N>) -> Result<Self> {
+        writer.write_all(&SNAPSHOT_MAGIC)?;
+        writer.write_all(&SNAPSHOT_VERSION.to_le_bytes())?;
+
+        let mut snapshot = Self {
+            writer,
+            chunk: Default::default(),
+            chunk_size_in_bytes: 0,
+        };
+        snapshot.write_frame(&bincode::serialize(header)?)?;
+        Ok(snapshot)
+    }
+
+    /// Writes all entries of the given map to the snapshot.
+    pub fn write_map<'a, K: 'a + Serialize + DeserializeOwned, V: 'a + Serialize + DeserializeOwned>(
+        &mut self,
+        map_id: MapId,
+        map: &'a DataMap<K, V>,
+    ) -> Result<()> {
+        self.flush_chunk()?;
+        self.chunk.map_id = map_id.as_bytes().to_vec();
+
+        for (key, value) in map.iter() {
+            let (key, value) = (bincode::serialize(&key)?, bincode::serialize(&value)?);
+            self.chunk_size_in_bytes += key.len() + value.len();
+            self.chunk.entries.push((key, value));
+
+            if self.chunk_size_in_bytes >= SNAPSHOT_CHUNK_SIZE_IN_BYTES {
+                self.flush_chunk()?;
+            }
+        }
+
+        self.flush_chunk()
+    }
+
+    /// Writes the terminating frame of the snapshot, and flushes the underlying writer.
+    pub fn finish(mut self) -> Result<W> {
+        self.flush_chunk()?;
+        self.writer.write_all(&0u32.to_le_bytes())?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    /// Writes the pending entries as a chunk, if there are any.
+    fn flush_chunk(&mut self) -> Result<()> {
+        if !self.chunk.is_empty() {
+            let payload = bincode::serialize(&self.chunk)?;
+            self.write_frame(&payload)?;
+            self.chunk.entries.clear();
+            self.chunk_size_in_bytes = 0;
+        }
+        Ok(())
+    }
+
+    /// Writes the given payload as a checksummed frame.
+    fn write_frame(&mut self, payload: &[u8]) -> Result<()> {
+        self.writer.write_all(&(payload.len() as u32).to_le_bytes())?;
+        self.writer.write_all(payload)?;
+        self.writer.write_all(&crc32fast::hash(payload).to_le_bytes())?;
+        Ok(())
+    }
+}
+
+///
+/// A streaming reader for ledger snapshots, which verifies the checksum of each chunk as it is read.
+///
+pub struct SnapshotReader<N: Network, R: Read> {
+    reader: R,
+    header: SnapshotHeader<N>,
+    is_finished: bool,
+}
+
+impl<N: Network, R: Read> SnapshotReader<N, R> {
+    /// Initializes a new snapshot reader, and reads the header.
+    pub fn new(mut reader: R) -> Result<Self> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if magic != SNAPSHOT_MAGIC {
+            return Err(anyhow!("The given file is not a ledger snapshot"));
+        }
+
+        let mut version = [0u8; 2];
+        reader.read_exact(&mut version)?;
+        let version = u16::from_le_bytes(version);
+        if version != SNAPSHOT_VERSION {
+            return Err(anyhow!("Unsupported snapshot version {} (expected {})", version, SNAPSHOT_VERSION));
+        }
+
+        let header = match Self::read_frame(&mut reader)? {
+            Some(payload) => bincode::deserialize(&payload)?,
+            None => return Err(anyhow!("The snapshot is missing its header")),
+        };
+
+        Ok(Self {
+            reader,
+            header,
+            is_finished: false,
+        })
+    }
+
+    /// Returns the header of the snapshot.
+    pub fn header(&self) -> &SnapshotHeader<N> {
+        &self.header
+    }
+
+    /// Returns the next chunk of the snapshot, or `None` once the terminating frame is reached.
+    pub fn next_chunk(&mut self) -> Result<Option<SnapshotChunk>> {
+        if self.is_finished {
+            return Ok(None);
+        }
+
+        match Self::read_frame(&mut self.reader)? {
+            Some(payload) => Ok(Some(bincode::deserialize(&payload)?)),
+            None => {
+                self.is_finished = true;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Reads the next frame and verifies its checksum, returning `None` for the terminating frame.
+    fn read_frame(reader: &mut R) -> Result<Option<Vec<u8>>> {
+        let mut length = [0u8; 4];
+        match reader.read_exact(&mut length) {
+            Ok(()) => (),
+            Err(error) if error.kind() == ErrorKind::UnexpectedEof => return Err(anyhow!("The snapshot is truncated")),
+            Err(error) => return Err(error.into()),
+        }
+
+        let length = u32::from_le_bytes(length) as usize;
+        if length == 0 {
+            return Ok(None);
+        }
+        if length > MAXIMUM_SNAPSHOT_CHUNK_SIZE_IN_BYTES {
+            return Err(anyhow!("The snapshot contains an oversized chunk of {} bytes", length));
+        }
+
+        let mut payload = vec![0u8; length];
+        let mut checksum = [0u8; 4];
+        match reader.read_exact(&mut payload).and_then(|_| reader.read_exact(&mut checksum)) {
+            Ok(()) => (),
+            Err(error) if error.kind() == ErrorKind::UnexpectedEof => return Err(anyhow!("The snapshot is truncated")),
+            Err(error) => return Err(error.into()),
+        }
+
+        if crc32fast::hash(&payload) != u32::from_le_bytes(checksum) {
+            return Err(anyhow!("The snapshot is corrupted (checksum mismatch)"));
+        }
+        Ok(Some(payload))
+    }
+}
@@ -0,0 +1,112 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::storage::MapId;
+
+use serde::{Deserialize, Serialize};
+
+///
+/// The category of a discrepancy found while verifying the ledger in storage.
+///
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscrepancyKind {
+    /// A block height is missing, or does not correspond to its block hash.
+    BlockHeight,
+    /// A block header is missing, or does not correspond to its block hash.
+    BlockHeader,
+    /// A block header does not hash to its block hash from the block hash of its previous block.
+    PreviousBlockHash,
+    /// A block header does not increase the cumulative weight of its previous block.
+    CumulativeWeight,
+    /// The list of transaction IDs of a block is missing, or does not correspond to its transactions.
+    BlockTransactions,
+    /// A transaction entry is missing, or does not point back to an existing block.
+    Transaction,
+    /// A transition entry is missing, or does not point back to an existing transaction.
+    Transition,
+    /// A serial number entry does not point back to an existing transition.
+    SerialNumber,
+    /// A commitment entry does not point back to an existing transition or record proof.
+    Commitment,
+    /// A ledger root is missing, or does not match the ledger root recomputed from the block hashes.
+    LedgerRoot,
+}
+
+///
+/// A discrepancy found while verifying the ledger in storage.
+///
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Discrepancy {
+    /// The category of the discrepancy.
+    pub kind: DiscrepancyKind,
+    /// The name of the map in storage that contains the discrepancy.
+    pub map: String,
+    /// The block height the discrepancy belongs to, if it is known.
+    pub block_height: Option<u32>,
+    /// A description of the discrepancy.
+    pub message: String,
+}
+
+impl Discrepancy {
+    /// Initializes a new instance of `Discrepancy`.
+    pub fn new(kind: DiscrepancyKind, map_id: MapId, block_height: Option<u32>, message: String) -> Self {
+        Self {
+            kind,
+            map: map_id.name().to_string(),
+            block_height,
+            message,
+        }
+    }
+}
+
+///
+/// The report of verifying the ledger in storage, listing every discrepancy that was found.
+///
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct VerificationReport {
+    /// The latest block height in storage.
+    pub latest_block_height: u32,
+    /// The height of the oldest block that is retained in full.
+    pub pruned_block_height: u32,
+    /// The number of blocks that were verified.
+    pub number_of_blocks: u64,
+    /// The number of transactions that were verified.
+    pub number_of_transactions: u64,
+    /// The number of transitions that were verified.
+    pub number_of_transitions: u64,
+    /// The number of serial numbers that were verified.
+    pub number_of_serial_numbers: u64,
+    /// The number of commitments that were verified.
+    pub number_of_commitments: u64,
+    /// The number of ledger roots that were verified.
+    pub number_of_ledger_roots: u64,
+    /// The list of discrepancies that were found.
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+impl VerificationReport {
+    /// Returns `true` if no discrepancies were found.
+    pub fn is_valid(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+
+    /// Records a discrepancy of the given kind, in the given map, at the given block height.
+    pub(crate) fn add(&mut self, kind: DiscrepancyKind, map_id: MapId, block_height: Option<u32>, message: String) {
+        trace!("Found a discrepancy in the {} map: {}", map_id.name(), message);
+        self.discrepancies.push(Discrepancy::new(kind, map_id, block_height, message));
+    }
+}
@@ -18,7 +18,21 @@
 extern crate tracing;
 
 mod helpers;
-pub use helpers::BlockLocators;
+pub use helpers::{
+    BackupManifest,
+    BlockLocators,
+    CacheMetrics,
+    Discrepancy,
+    DiscrepancyKind,
+    Reorg,
+    RepairAction,
+    RepairChange,
+    RepairReport,
+    RepairTarget,
+    RevertedBlock,
+    SnapshotHeader,
+    VerificationReport,
+};
 
 pub(crate) mod state;
 pub use state::{
@@ -15,10 +15,33 @@
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
-    helpers::BlockLocators,
-    storage::{DataMap, Map, MapId, Storage},
+    helpers::{
+        BackupManifest,
+        BlockCache,
+        BlockLocators,
+        CacheMetrics,
+        DiscrepancyKind,
+        LedgerNotification,
+        LedgerNotifier,
+        Reorg,
+        RepairAction,
+        RepairReport,
+        RepairTarget,
+        RevertedBlock,
+        SnapshotChunk,
+        SnapshotHeader,
+        SnapshotReader,
+        SnapshotWriter,
+        VerificationReport,
+    },
+    state::{schema::check_schema_version, ProverState},
+    storage::{DataMap, Map, MapId, Storage, StorageStats, SCHEMA_VERSION},
+};
+use snarkvm::{
+    algorithms::CRH,
+    dpc::prelude::*,
+    utilities::{FromBytes, ToBits, ToBytes},
 };
-use snarkvm::dpc::prelude::*;
 
 use anyhow::{anyhow, Result};
 use circular_queue::CircularQueue;
@@ -28,10 +51,12 @@ use rand::{CryptoRng, Rng};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{BTreeMap, HashSet},
-    path::Path,
+    collections::{BTreeMap, HashMap, HashSet},
+    io::{Read, Write},
+    path::{Path, PathBuf},
     sync::{
-        atomic::{AtomicBool, AtomicU32, Ordering},
+        atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering},
+        mpsc::Receiver,
         Arc,
     },
     thread,
@@ -51,6 +76,9 @@ const MAXIMUM_FORK_DEPTH: u32 = 4096;
 /// The maximum future block time - 2 minutes.
 const MAXIMUM_FUTURE_BLOCK_TIME: i64 = 120;
 
+/// The maximum number of blocks that are retained in side chains.
+const MAXIMUM_SIDE_CHAIN_BLOCKS: usize = 2 * MAXIMUM_FORK_DEPTH as usize;
+
 ///
 /// A helper struct containing transaction metadata.
 ///
@@ -91,10 +119,28 @@ pub struct LedgerState<N: Network> {
     ledger_roots: DataMap<N::LedgerRoot, u32>,
     /// The blocks of the ledger in storage.
     blocks: BlockState<N>,
+    /// The cache of the most recently used block headers and blocks.
+    block_cache: BlockCache<N>,
+    /// The pruning state := (pruning depth, pruned block height), if the ledger is pruned.
+    pruning: DataMap<(), (u32, u32)>,
+    /// The journal of reorgs := reorg index => reorg.
+    reorgs: DataMap<u32, Reorg<N>>,
+    /// The blocks of the side chains that fork from the canonical chain := block hash => block.
+    side_chain_blocks: DataMap<N::BlockHash, Block<N>>,
+    /// The children of each block in the side chains := previous block hash => [(block hash, block height, cumulative weight)].
+    side_chain_children: DataMap<N::BlockHash, Vec<(N::BlockHash, u32, u128)>>,
+    /// The number of side chain blocks in storage.
+    number_of_side_chain_blocks: AtomicUsize,
+    /// The number of latest blocks that are retained in full, if the ledger is pruned.
+    pruning_depth: Option<u32>,
+    /// The height of the oldest block that is retained in full.
+    pruned_block_height: AtomicU32,
     /// The indicator bit and tracker for a ledger in read-only mode.
     read_only: (bool, Arc<AtomicU32>, RwLock<Option<Arc<JoinHandle<()>>>>),
     /// Used to ensure the database operations aren't interrupted by a shutdown.
     map_lock: Arc<RwLock<()>>,
+    /// Pushes the changes of a writable ledger to the read-only ledgers in the same process.
+    notifier: Arc<LedgerNotifier>,
 }
 
 impl<N: Network> LedgerState<N> {
@@ -109,22 +155,16 @@ impl<N: Network> LedgerState<N> {
         // Open storage.
         let context = N::NETWORK_ID;
         let is_read_only = false;
-        let storage = S::open(path, context, is_read_only)?;
+        let storage = S::open(&path, context, is_read_only)?;
+
+        // Ensure the storage is at the current schema version.
+        check_schema_version::<N, S>(&storage, is_read_only)?;
 
         // Initialize the ledger.
-        let ledger = Self {
-            ledger_tree: RwLock::new(LedgerTree::<N>::new()?),
-            latest_block: RwLock::new(N::genesis_block().clone()),
-            latest_block_hashes_and_headers: RwLock::new(CircularQueue::with_capacity(MAXIMUM_LINEAR_BLOCK_LOCATORS as usize)),
-            latest_block_locators: Default::default(),
-            ledger_roots: storage.open_map(MapId::LedgerRoots)?,
-            blocks: BlockState::open(storage)?,
-            read_only: (is_read_only, Arc::new(AtomicU32::new(0)), RwLock::new(None)),
-            map_lock: Default::default(),
-        };
+        let mut ledger = Self::initialize(storage, is_read_only)?;
 
         // Determine the latest block height.
-        let latest_block_height = match (ledger.ledger_roots.values().max(), ledger.blocks.block_heights.keys().max()) {
+        let latest_block_height = match (ledger.ledger_roots.values().max(), ledger.blocks.latest_block_height()?) {
             (Some(latest_block_height_0), Some(latest_block_height_1)) => match latest_block_height_0 == latest_block_height_1 {
                 true => latest_block_height_0,
                 false => match ledger.try_fixing_inconsistent_state() {
@@ -139,12 +179,15 @@ impl<N: Network> LedgerState<N> {
         // If this is new storage, initialize it with the genesis block.
         if latest_block_height == 0u32 && !ledger.blocks.contains_block_height(0u32)? {
             let genesis = N::genesis_block();
-            ledger.ledger_roots.insert(&genesis.previous_ledger_root(), &genesis.height())?;
 
             // Acquire the map lock to ensure the following operations aren't interrupted by a shutdown.
             let _map_lock = ledger.map_lock.read();
 
-            ledger.blocks.add_block(genesis)?;
+            // Write the genesis block to storage as a single atomic batch.
+            ledger.atomic_batch(|| {
+                ledger.ledger_roots.insert(&genesis.previous_ledger_root(), &genesis.height())?;
+                ledger.blocks.add_block(genesis)
+            })?;
 
             // The map lock goes out of scope on its own.
         }
@@ -213,20 +256,54 @@ impl<N: Network> LedgerState<N> {
         *ledger.latest_block.write() = ledger.get_block(latest_block_height)?;
         ledger.regenerate_latest_ledger_state()?;
 
-        // Validate the ledger root one final time.
-        let latest_ledger_root = ledger.ledger_tree.read().root();
-        ledger.regenerate_ledger_tree()?;
-        assert_eq!(ledger.ledger_tree.read().root(), latest_ledger_root);
-
         // let value = storage.export()?;
         // println!("{}", value);
         // let storage_2 = S::open(".ledger_2", context)?;
         // storage_2.import(value)?;
 
+        // Prune the blocks that have fallen out of the pruning depth, if the ledger is pruned.
+        ledger.prune_blocks()?;
+        // Remove the side chain blocks that have fallen out of the maximum fork depth.
+        ledger.prune_side_chain_blocks()?;
+
+        // Register the ledger, so that the read-only ledgers in this process are notified of its changes.
+        ledger.notifier = LedgerNotifier::register(&path);
+
         info!("Ledger successfully loaded at block {}", ledger.latest_block_height());
         Ok(ledger)
     }
 
+    ///
+    /// Opens a new writable instance of `LedgerState` in pruned mode from the given storage path,
+    /// which retains the latest `pruning_depth` blocks in full.
+    ///
+    /// For older blocks, a pruned ledger discards the transitions, and retains the block headers,
+    /// ledger roots, serial numbers, and commitments needed to validate new blocks, along with
+    /// a record proof for each commitment to serve ledger inclusion proofs.
+    ///
+    /// *Attention*: Pruning is irreversible, and `LedgerState::open_writer` continues to prune
+    /// the ledger thereafter. The ledger cannot revert to a block that has been pruned,
+    /// so the pruning depth must be no less than the maximum fork depth.
+    ///
+    pub fn open_pruned_writer<S: Storage, P: AsRef<Path>>(path: P, pruning_depth: u32) -> Result<Self> {
+        // Ensure the blocks within the maximum fork depth are retained in full, to resolve forks.
+        if pruning_depth < MAXIMUM_FORK_DEPTH {
+            return Err(anyhow!("The pruning depth must be at least {} blocks", MAXIMUM_FORK_DEPTH));
+        }
+
+        // Record the pruning depth in storage. The storage is closed at the end of this scope, before the ledger is opened.
+        {
+            let storage = S::open(&path, N::NETWORK_ID, false)?;
+            check_schema_version::<N, S>(&storage, false)?;
+
+            let pruning: DataMap<(), (u32, u32)> = storage.open_map(MapId::Pruning)?;
+            let pruned_block_height = pruning.get(&())?.map(|(_, pruned_block_height)| pruned_block_height).unwrap_or(0);
+            pruning.insert(&(), &(pruning_depth, pruned_block_height))?;
+        }
+
+        Self::open_writer::<S, _>(path)
+    }
+
     ///
     /// Opens a read-only instance of `LedgerState` from the given storage path.
     /// For a writable instance of `LedgerState`, use `LedgerState::open_writer`.
@@ -235,25 +312,23 @@ impl<N: Network> LedgerState<N> {
     /// a read-only instance of `LedgerState` may only call immutable methods.
     ///
     pub fn open_reader<S: Storage, P: AsRef<Path>>(path: P) -> Result<Arc<Self>> {
+        // Subscribe to the writable ledger, if it is open in this process. This precedes opening
+        // storage, so that no change is missed between opening storage and starting the heartbeat.
+        let notifications = LedgerNotifier::subscribe(&path);
+
         // Open storage.
         let context = N::NETWORK_ID;
         let is_read_only = true;
         let storage = S::open(path, context, is_read_only)?;
 
+        // Ensure the storage is at the current schema version.
+        check_schema_version::<N, S>(&storage, is_read_only)?;
+
         // Initialize the ledger.
-        let ledger = Arc::new(Self {
-            ledger_tree: RwLock::new(LedgerTree::<N>::new()?),
-            latest_block: RwLock::new(N::genesis_block().clone()),
-            latest_block_hashes_and_headers: RwLock::new(CircularQueue::with_capacity(MAXIMUM_LINEAR_BLOCK_LOCATORS as usize)),
-            latest_block_locators: Default::default(),
-            ledger_roots: storage.open_map(MapId::LedgerRoots)?,
-            blocks: BlockState::open(storage)?,
-            read_only: (is_read_only, Arc::new(AtomicU32::new(0)), RwLock::new(None)),
-            map_lock: Default::default(),
-        });
+        let ledger = Arc::new(Self::initialize(storage, is_read_only)?);
 
         // Determine the latest block height.
-        let latest_block_height = match (ledger.ledger_roots.values().max(), ledger.blocks.block_heights.keys().max()) {
+        let latest_block_height = match (ledger.ledger_roots.values().max(), ledger.blocks.latest_block_height()?) {
             (Some(latest_block_height_0), Some(latest_block_height_1)) => match latest_block_height_0 == latest_block_height_1 {
                 true => latest_block_height_0,
                 false => {
@@ -285,17 +360,409 @@ impl<N: Network> LedgerState<N> {
         // Update the ledger tree state.
         ledger.regenerate_ledger_tree()?;
         // As the ledger is in read-only mode, proceed to start a process to keep the reader in sync.
-        *ledger.read_only.2.write() = Some(Arc::new(ledger.initialize_reader_heartbeat(latest_block_height)?));
+        *ledger.read_only.2.write() = Some(Arc::new(ledger.initialize_reader_heartbeat(latest_block_height, notifications)?));
 
         trace!("[Read-Only] Ledger successfully loaded at block {}", ledger.latest_block_height());
         Ok(ledger)
     }
 
+    ///
+    /// Imports a ledger snapshot from the given reader into new storage at the given path,
+    /// which must not exist, and returns a writable instance of `LedgerState` for the imported ledger.
+    ///
+    /// The snapshot is imported into a temporary sibling of the given path, where the imported ledger
+    /// is fully validated on load, and its latest block and ledger root must match the snapshot header.
+    /// The imported ledger is only moved to the given path once it has been validated.
+    ///
+    pub fn import_snapshot<S: Storage, P: AsRef<Path>, R: Read>(path: P, reader: R) -> Result<Self> {
+        // Read the snapshot header.
+        let mut snapshot = SnapshotReader::<N, R>::new(reader)?;
+        let header = snapshot.header().clone();
+
+        // Ensure the snapshot is compatible with this ledger.
+        if header.network_id != N::NETWORK_ID {
+            return Err(anyhow!(
+                "The snapshot is for network {}, expected network {}",
+                header.network_id,
+                N::NETWORK_ID
+            ));
+        }
+        if header.schema_version != SCHEMA_VERSION {
+            return Err(anyhow!(
+                "The snapshot has storage schema version {}, expected version {}",
+                header.schema_version,
+                SCHEMA_VERSION
+            ));
+        }
+
+        // Ensure the snapshot is not imported over existing storage.
+        if S::exists(&path) {
+            return Err(anyhow!("A ledger snapshot may only be imported into new storage"));
+        }
+
+        // Import the snapshot into a temporary path, discarding the leftovers of an interrupted import.
+        let import_path = Self::temporary_path(path.as_ref(), "import");
+        S::destroy(&import_path)?;
+        if let Err(error) = Self::import_snapshot_into::<S, R>(&import_path, &mut snapshot) {
+            if let Err(error) = S::destroy(&import_path) {
+                warn!(
+                    "Failed to remove the partially imported ledger at {}: {}",
+                    import_path.display(),
+                    error
+                );
+            }
+            return Err(error);
+        }
+
+        // Move the validated ledger into place, and open it.
+        S::rename(&import_path, &path)?;
+        let ledger = Self::open_writer::<S, _>(path)?;
+
+        info!("Ledger snapshot successfully imported at block {}", header.block_height);
+        Ok(ledger)
+    }
+
+    /// Imports the given ledger snapshot into new storage at the given path, and ensures the imported ledger matches the snapshot header.
+    fn import_snapshot_into<S: Storage, R: Read>(path: &Path, snapshot: &mut SnapshotReader<N, R>) -> Result<()> {
+        let header = snapshot.header().clone();
+
+        // The storage is closed at the end of this scope, before the imported ledger is opened.
+        {
+            let storage = S::open(path, N::NETWORK_ID, false)?;
+            check_schema_version::<N, S>(&storage, false)?;
+            let ledger = Self::initialize(storage, false)?;
+
+            // Write each chunk of the snapshot to storage as a single atomic batch.
+            let mut num_entries = 0usize;
+            while let Some(chunk) = snapshot.next_chunk()? {
+                ledger.atomic_batch(|| ledger.import_snapshot_chunk(&chunk))?;
+                num_entries += chunk.len();
+                debug!("Imported {} entries from the snapshot ({} total)", chunk.len(), num_entries);
+            }
+        }
+
+        // Open the imported ledger, which validates the ledger state.
+        let ledger = Self::open_writer::<S, _>(path)?;
+
+        // Ensure the imported ledger matches the snapshot header.
+        if ledger.latest_block_height() != header.block_height || ledger.latest_block_hash() != header.block_hash {
+            return Err(anyhow!(
+                "The imported ledger is at block {} ({}), but the snapshot header expects block {} ({})",
+                ledger.latest_block_height(),
+                ledger.latest_block_hash(),
+                header.block_height,
+                header.block_hash
+            ));
+        }
+        if ledger.latest_ledger_root() != header.ledger_root {
+            return Err(anyhow!(
+                "The imported ledger has ledger root {}, but the snapshot header expects {}",
+                ledger.latest_ledger_root(),
+                header.ledger_root
+            ));
+        }
+
+        // The imported ledger is closed once it goes out of scope.
+        Ok(())
+    }
+
+    ///
+    /// Restores the backup at the given path to the given ledger and prover storage paths,
+    /// which must not exist, and returns the manifest of the backup.
+    ///
+    /// The manifest is validated before the restored storage is opened. The backup is restored into
+    /// temporary siblings of the given paths, where the restored ledger is opened and ensured to match
+    /// the manifest, as opening it may fix inconsistent state. The restored storage is only moved to
+    /// the given paths once it has been validated.
+    ///
+    pub fn restore_backup<S: Storage, P: AsRef<Path>, Q: AsRef<Path>, R: AsRef<Path>>(
+        backup: P,
+        ledger_path: Q,
+        prover_path: R,
+    ) -> Result<BackupManifest<N>> {
+        // Read the manifest, which ensures the backup is complete and compatible with this ledger.
+        let manifest = BackupManifest::<N>::read(&backup)?;
+
+        // Ensure the backup is not restored over existing storage.
+        if S::exists(&ledger_path) || S::exists(&prover_path) {
+            return Err(anyhow!("A ledger backup may only be restored into new storage"));
+        }
+
+        // Restore the backup into temporary paths, discarding the leftovers of an interrupted restore.
+        let restore_ledger_path = Self::temporary_path(ledger_path.as_ref(), "restore");
+        let restore_prover_path = Self::temporary_path(prover_path.as_ref(), "restore");
+        S::destroy(&restore_ledger_path)?;
+        S::destroy(&restore_prover_path)?;
+        if let Err(error) = Self::restore_backup_into::<S>(backup.as_ref(), &manifest, &restore_ledger_path, &restore_prover_path) {
+            for path in &[&restore_ledger_path, &restore_prover_path] {
+                if let Err(error) = S::destroy(path) {
+                    warn!("Failed to remove the partially restored storage at {}: {}", path.display(), error);
+                }
+            }
+            return Err(error);
+        }
+
+        // Move the validated prover and ledger into place, removing the prover if the ledger cannot be moved.
+        S::rename(&restore_prover_path, &prover_path)?;
+        if let Err(error) = S::rename(&restore_ledger_path, &ledger_path) {
+            S::destroy(&prover_path)?;
+            return Err(error);
+        }
+
+        info!("Ledger backup successfully restored at block {}", manifest.block_height);
+        Ok(manifest)
+    }
+
+    /// Restores the given backup into new storage at the given paths, and ensures the restored ledger matches the manifest.
+    fn restore_backup_into<S: Storage>(backup: &Path, manifest: &BackupManifest<N>, ledger_path: &Path, prover_path: &Path) -> Result<()> {
+        // Restore the checkpoints of the ledger and the prover.
+        S::restore_checkpoint(BackupManifest::<N>::ledger_path(backup), ledger_path)?;
+        S::restore_checkpoint(BackupManifest::<N>::prover_path(backup), prover_path)?;
+
+        // Open the restored prover and ledger, which validates the ledger state.
+        ProverState::<N>::open_writer::<S, _>(prover_path)?;
+        let ledger = Self::open_writer::<S, _>(ledger_path)?;
+
+        // Ensure the restored ledger matches the manifest.
+        if ledger.latest_block_height() != manifest.block_height || ledger.latest_block_hash() != manifest.block_hash {
+            return Err(anyhow!(
+                "The restored ledger is at block {} ({}), but the backup manifest expects block {} ({})",
+                ledger.latest_block_height(),
+                ledger.latest_block_hash(),
+                manifest.block_height,
+                manifest.block_hash
+            ));
+        }
+        if ledger.latest_ledger_root() != manifest.ledger_root {
+            return Err(anyhow!(
+                "The restored ledger has ledger root {}, but the backup manifest expects {}",
+                ledger.latest_ledger_root(),
+                manifest.ledger_root
+            ));
+        }
+
+        // The restored prover and ledger are closed once they go out of scope.
+        Ok(())
+    }
+
+    ///
+    /// Writes a snapshot of the ledger to the given writer, and returns the snapshot header.
+    ///
+    /// The ledger is not updated while the snapshot is being written.
+    ///
+    pub fn export_snapshot<W: Write>(&self, writer: W) -> Result<SnapshotHeader<N>> {
+        // Acquire the map lock to ensure the ledger is not updated during the export.
+        let _map_lock = self.map_lock.write();
+
+        let latest_block = self.latest_block();
+        let header = SnapshotHeader {
+            network_id: N::NETWORK_ID,
+            schema_version: SCHEMA_VERSION,
+            block_height: latest_block.height(),
+            block_hash: latest_block.hash(),
+            ledger_root: self.latest_ledger_root(),
+        };
+
+        let mut snapshot = SnapshotWriter::new(writer, &header)?;
+        snapshot.write_map(MapId::LedgerRoots, &self.ledger_roots)?;
+        snapshot.write_map(MapId::BlockHeights, &self.blocks.block_heights)?;
+        snapshot.write_map(MapId::BlockHeaders, &self.blocks.block_headers)?;
+        snapshot.write_map(MapId::BlockTransactions, &self.blocks.block_transactions)?;
+        snapshot.write_map(MapId::Transactions, &self.blocks.transactions.transactions)?;
+        snapshot.write_map(MapId::Transitions, &self.blocks.transactions.transitions)?;
+        snapshot.write_map(MapId::SerialNumbers, &self.blocks.transactions.serial_numbers)?;
+        snapshot.write_map(MapId::Commitments, &self.blocks.transactions.commitments)?;
+        snapshot.write_map(MapId::RecordProofs, &self.blocks.transactions.record_proofs)?;
+        snapshot.write_map(MapId::Pruning, &self.pruning)?;
+        snapshot.finish()?;
+
+        info!("Ledger snapshot successfully exported at block {}", header.block_height);
+        Ok(header)
+    }
+
+    ///
+    /// Writes a backup of the ledger and the given prover to the given directory, which must be empty
+    /// if it exists, and returns the manifest of the backup.
+    ///
+    /// The backup consists of a checkpoint of each storage, which is taken without stopping the node.
+    /// The ledger is not updated while the checkpoints are being taken.
+    ///
+    pub fn backup<P: AsRef<Path>>(&self, prover: &ProverState<N>, backup: P) -> Result<BackupManifest<N>> {
+        // If the storage is in read-only mode, this method cannot be called.
+        if self.is_read_only() {
+            return Err(anyhow!("Ledger must be writable to write a backup"));
+        }
+
+        // Ensure the backup directory is empty.
+        let backup = backup.as_ref();
+        if backup.exists() && backup.read_dir()?.next().is_some() {
+            return Err(anyhow!("The backup directory {} is not empty", backup.display()));
+        }
+        std::fs::create_dir_all(backup)?;
+
+        // Acquire the map lock to ensure the ledger is not updated during the backup.
+        let _map_lock = self.map_lock.write();
+
+        let latest_block = self.latest_block();
+        let manifest = BackupManifest {
+            network_id: N::NETWORK_ID,
+            schema_version: SCHEMA_VERSION,
+            block_height: latest_block.height(),
+            block_hash: latest_block.hash(),
+            ledger_root: self.latest_ledger_root(),
+            timestamp: chrono::Utc::now().timestamp(),
+        };
+
+        // Take the checkpoints, and write the manifest last, to mark the backup as complete.
+        self.ledger_roots.create_checkpoint(BackupManifest::<N>::ledger_path(backup))?;
+        prover.create_checkpoint(BackupManifest::<N>::prover_path(backup))?;
+        manifest.write(backup)?;
+
+        info!("Ledger backup successfully written at block {}", manifest.block_height);
+        Ok(manifest)
+    }
+
+    ///
+    /// Verifies the integrity of the ledger in storage at the given path, and returns a report
+    /// of every discrepancy that was found.
+    ///
+    /// The storage is opened in read-only mode, and the ledger state is not loaded,
+    /// so that a ledger may be verified even if it is inconsistent.
+    ///
+    pub fn verify<S: Storage, P: AsRef<Path>>(path: P) -> Result<VerificationReport> {
+        // Open storage.
+        let context = N::NETWORK_ID;
+        let is_read_only = true;
+        let storage = S::open(path, context, is_read_only)?;
+
+        // Ensure the storage is at the current schema version.
+        check_schema_version::<N, S>(&storage, is_read_only)?;
+
+        // Initialize the ledger, without loading any ledger state.
+        let ledger = Self::initialize(storage, is_read_only)?;
+
+        let mut report = VerificationReport {
+            latest_block_height: ledger.blocks.latest_block_height()?.unwrap_or(0),
+            pruned_block_height: ledger.pruned_block_height(),
+            ..Default::default()
+        };
+
+        let latest_ledger_root = ledger.verify_blocks(&mut report)?;
+        ledger.verify_ledger_roots(&mut report)?;
+        ledger.verify_transactions(&mut report, latest_ledger_root)?;
+        ledger.verify_transitions(&mut report)?;
+        ledger.verify_serial_numbers(&mut report)?;
+        ledger.verify_commitments(&mut report)?;
+
+        info!(
+            "Ledger verification found {} discrepancies up to block {}",
+            report.discrepancies.len(),
+            report.latest_block_height
+        );
+        Ok(report)
+    }
+
+    ///
+    /// Repairs the ledger in storage at the given path, and returns a report of the changes.
+    ///
+    /// The derived indexes of the ledger (ledger roots, serial numbers, and commitments) are rebuilt
+    /// from the canonical blocks, and orphaned index entries are removed. Then, if a target is given,
+    /// the ledger is reverted to the target block with `LedgerState::revert_to_block_height`.
+    ///
+    /// In dry-run mode, the storage is opened in read-only mode, and the changes are reported without being written.
+    ///
+    pub fn repair<S: Storage, P: AsRef<Path>>(path: P, target: Option<RepairTarget<N>>, dry_run: bool) -> Result<RepairReport> {
+        let mut report = RepairReport {
+            dry_run,
+            ..Default::default()
+        };
+
+        // The storage is closed at the end of this scope, before the repaired ledger is opened.
+        {
+            let storage = S::open(&path, N::NETWORK_ID, dry_run)?;
+            check_schema_version::<N, S>(&storage, dry_run)?;
+
+            // Initialize the ledger, without loading any ledger state.
+            let ledger = Self::initialize(storage, dry_run)?;
+            report.latest_block_height = match ledger.blocks.latest_block_height()? {
+                Some(latest_block_height) => latest_block_height,
+                None => return Err(anyhow!("The ledger has no blocks to repair")),
+            };
+
+            // Rebuild the derived indexes from the canonical blocks.
+            ledger.repair_ledger_roots(&mut report)?;
+            ledger.repair_serial_numbers(&mut report)?;
+            ledger.repair_commitments(&mut report)?;
+
+            // Determine the block height to revert to, if a target is given.
+            if let Some(target) = target {
+                let target_block_height = match target {
+                    RepairTarget::BlockHeight(block_height) => block_height,
+                    RepairTarget::BlockHash(block_hash) => {
+                        let block_height = ledger.blocks.get_block_height(&block_hash)?;
+                        if ledger.blocks.get_block_hash(block_height)? != block_hash {
+                            return Err(anyhow!("Block {} is not on the canonical chain", block_hash));
+                        }
+                        block_height
+                    }
+                };
+                if target_block_height > report.latest_block_height {
+                    return Err(anyhow!(
+                        "Cannot revert to block {}, as the latest block is {}",
+                        target_block_height,
+                        report.latest_block_height
+                    ));
+                }
+                if target_block_height < ledger.pruned_block_height() {
+                    return Err(anyhow!("Cannot revert to block {}, as it has been pruned", target_block_height));
+                }
+
+                for block_height in (target_block_height + 1)..=report.latest_block_height {
+                    let entry = format!("Block {} ({})", block_height, ledger.blocks.get_block_hash(block_height)?);
+                    report.add(RepairAction::Revert, MapId::BlockHeights, Some(block_height), entry);
+                }
+                report.target_block_height = Some(target_block_height);
+            }
+        }
+
+        if !dry_run {
+            // Open the repaired ledger, which validates the ledger state.
+            let ledger = Self::open_writer::<S, _>(&path)?;
+
+            // Revert the ledger to the target block height, in steps of the maximum fork depth.
+            if let Some(target_block_height) = report.target_block_height {
+                while ledger.latest_block_height() > target_block_height {
+                    let block_height = std::cmp::max(target_block_height, ledger.latest_block_height().saturating_sub(MAXIMUM_FORK_DEPTH));
+                    ledger.revert_to_block_height(block_height)?;
+                    debug!("Reverted the ledger to block {}", block_height);
+                }
+            }
+
+            info!(
+                "Ledger successfully repaired at block {} ({} changes)",
+                ledger.latest_block_height(),
+                report.changes.len()
+            );
+        }
+
+        Ok(report)
+    }
+
     /// Returns `true` if the ledger is in read-only mode.
     pub fn is_read_only(&self) -> bool {
         self.read_only.0
     }
 
+    /// Returns the number of latest blocks that are retained in full, if the ledger is pruned.
+    pub fn pruning_depth(&self) -> Option<u32> {
+        self.pruning_depth
+    }
+
+    /// Returns the height of the oldest block that is retained in full. All blocks below this height have been pruned.
+    pub fn pruned_block_height(&self) -> u32 {
+        self.pruned_block_height.load(Ordering::SeqCst)
+    }
+
     /// Returns the latest block.
     pub fn latest_block(&self) -> Block<N> {
         self.latest_block.read().clone()
@@ -378,16 +845,38 @@ impl<N: Network> LedgerState<N> {
 
     /// Returns the record ciphertext for a given commitment.
     pub fn get_ciphertext(&self, commitment: &N::Commitment) -> Result<N::RecordCiphertext> {
+        // Ensure the transition of the commitment has not been pruned.
+        if self.pruned_block_height() > 0 && self.blocks.transactions.record_proofs.contains_key(commitment)? {
+            return Err(anyhow!("The ciphertext of commitment {} has been pruned from storage", commitment));
+        }
         self.blocks.get_ciphertext(commitment)
     }
 
     /// Returns the transition for a given transition ID.
     pub fn get_transition(&self, transition_id: &N::TransitionID) -> Result<Transition<N>> {
-        self.blocks.get_transition(transition_id)
+        match self.blocks.get_transition(transition_id) {
+            // A pruned ledger does not retain the transitions of pruned blocks.
+            Err(_) if self.pruned_block_height() > 0 => Err(anyhow!(
+                "Transition {} does not exist in storage, or has been pruned from storage",
+                transition_id
+            )),
+            result => result,
+        }
     }
 
     /// Returns the transaction for a given transaction ID.
     pub fn get_transaction(&self, transaction_id: &N::TransactionID) -> Result<Transaction<N>> {
+        // Ensure the transaction has not been pruned.
+        if self.pruned_block_height() > 0 {
+            let metadata = self.get_transaction_metadata(transaction_id)?;
+            if metadata.block_height < self.pruned_block_height() {
+                return Err(anyhow!(
+                    "Transaction {} in block {} has been pruned from storage",
+                    transaction_id,
+                    metadata.block_height
+                ));
+            }
+        }
         self.blocks.get_transaction(transaction_id)
     }
 
@@ -423,12 +912,21 @@ impl<N: Network> LedgerState<N> {
 
     /// Returns the block header for the given block height.
     pub fn get_block_header(&self, block_height: u32) -> Result<BlockHeader<N>> {
-        self.blocks.get_block_header(block_height)
+        let block_hash = self.blocks.get_block_hash(block_height)?;
+        self.block_cache
+            .get_block_header(&block_hash, || self.blocks.get_block_header_by_hash(&block_hash))
     }
 
     /// Returns the block headers from the given `start_block_height` to `end_block_height` (inclusive).
     pub fn get_block_headers(&self, start_block_height: u32, end_block_height: u32) -> Result<Vec<BlockHeader<N>>> {
-        self.blocks.get_block_headers(start_block_height, end_block_height)
+        self.blocks
+            .get_block_hashes(start_block_height, end_block_height)?
+            .par_iter()
+            .map(|block_hash| {
+                self.block_cache
+                    .get_block_header(block_hash, || self.blocks.get_block_header_by_hash(block_hash))
+            })
+            .collect()
     }
 
     /// Returns the number of all block headers belonging to canonical blocks.
@@ -438,17 +936,42 @@ impl<N: Network> LedgerState<N> {
 
     /// Returns the transactions from the block of the given block height.
     pub fn get_block_transactions(&self, block_height: u32) -> Result<Transactions<N>> {
+        self.ensure_block_is_not_pruned(block_height)?;
         self.blocks.get_block_transactions(block_height)
     }
 
     /// Returns the block for a given block height.
     pub fn get_block(&self, block_height: u32) -> Result<Block<N>> {
-        self.blocks.get_block(block_height)
+        self.ensure_block_is_not_pruned(block_height)?;
+        let block_hash = self.blocks.get_block_hash(block_height)?;
+        self.block_cache.get_block(&block_hash, || self.blocks.get_block(block_height))
     }
 
     /// Returns the blocks from the given `start_block_height` to `end_block_height` (inclusive).
     pub fn get_blocks(&self, start_block_height: u32, end_block_height: u32) -> Result<Vec<Block<N>>> {
-        self.blocks.get_blocks(start_block_height, end_block_height)
+        self.ensure_block_is_not_pruned(start_block_height)?;
+        let block_hashes = self.blocks.get_block_hashes(start_block_height, end_block_height)?;
+        self.block_cache
+            .get_blocks(&block_hashes, || self.blocks.get_blocks(start_block_height, end_block_height))
+    }
+
+    /// Returns the serialized blocks from the given `start_block_height` to `end_block_height` (inclusive).
+    pub fn get_serialized_blocks(&self, start_block_height: u32, end_block_height: u32) -> Result<Vec<Vec<u8>>> {
+        self.ensure_block_is_not_pruned(start_block_height)?;
+        let block_hashes = self.blocks.get_block_hashes(start_block_height, end_block_height)?;
+        self.block_cache
+            .get_serialized_blocks(&block_hashes, || self.get_blocks(start_block_height, end_block_height))
+    }
+
+    /// Returns the number of hits and misses of the block cache.
+    pub fn cache_metrics(&self) -> CacheMetrics {
+        self.block_cache.metrics()
+    }
+
+    /// Returns the size and health statistics of the ledger storage.
+    pub fn storage_stats(&self) -> Result<StorageStats> {
+        // The maps of the ledger share the same storage, so the statistics may be retrieved from any map.
+        self.ledger_roots.storage_stats()
     }
 
     /// Returns the ledger root in the block header of the given block height.
@@ -685,128 +1208,111 @@ impl<N: Network> LedgerState<N> {
             return Err(anyhow!("Block {} is invalid", block.height()));
         }
 
-        // Retrieve the current block.
-        let current_block = self.latest_block();
-
-        // Ensure the block height increments by one.
-        let block_height = block.height();
-        if block_height != current_block.height() + 1 {
-            return Err(anyhow!(
-                "Block {} should have block height {}",
-                block_height,
-                current_block.height() + 1
-            ));
-        }
+        // Ensure the block extends the canonical chain.
+        self.check_next_block(
+            block,
+            &self.latest_block(),
+            self.latest_ledger_root(),
+            &StagedBlocks::default(),
+            &StagedBlocks::default(),
+        )?;
 
-        // Ensure the previous block hash matches.
-        if block.previous_block_hash() != current_block.hash() {
-            return Err(anyhow!(
-                "Block {} has an incorrect previous block hash in the canon chain",
-                block_height
-            ));
-        }
+        // Acquire the map lock to ensure the following operations aren't interrupted by a shutdown.
+        let _map_lock = self.map_lock.read();
 
-        // Ensure the next block timestamp is within the declared time limit.
-        let now = chrono::Utc::now().timestamp();
-        if block.timestamp() > (now + MAXIMUM_FUTURE_BLOCK_TIME) {
-            return Err(anyhow!("The given block timestamp exceeds the time limit"));
-        }
+        // Write the block to storage as a single atomic batch, along with the pruning of the block
+        // that falls out of the pruning depth, if the ledger is pruned.
+        let pruned_block_height = self.pruning_target(block.height());
+        self.atomic_batch(|| {
+            self.blocks.add_block(block)?;
+            self.ledger_roots.insert(&block.previous_ledger_root(), &block.height())?;
+            self.prune_blocks_below(pruned_block_height)
+        })?;
+        self.pruned_block_height.store(pruned_block_height, Ordering::SeqCst);
 
-        // Ensure the next block timestamp is after the current block timestamp.
-        if block.timestamp() <= current_block.timestamp() {
-            return Err(anyhow!("The given block timestamp is before the current timestamp"));
-        }
+        self.ledger_tree.write().add(&block.hash())?;
+        self.latest_block_hashes_and_headers
+            .write()
+            .push((block.hash(), block.header().clone()));
+        *self.latest_block_locators.write() = self.get_block_locators(block.height())?;
+        *self.latest_block.write() = block.clone();
+        self.block_cache.insert_block(block);
 
-        // Compute the expected difficulty target.
-        let expected_difficulty_target =
-            Blocks::<N>::compute_difficulty_target(current_block.timestamp(), current_block.difficulty_target(), block.timestamp());
+        // The map lock goes out of scope on its own.
 
-        // Ensure the expected difficulty target is met.
-        if block.difficulty_target() != expected_difficulty_target {
-            return Err(anyhow!(
-                "Block {} has an incorrect difficulty target. Found {}, but expected {}",
-                block_height,
-                block.difficulty_target(),
-                expected_difficulty_target
-            ));
-        }
+        // Notify the read-only ledgers in this process of the new block.
+        self.notifier.notify(LedgerNotification::BlockAdded(block.height()));
 
-        // Ensure the expected cumulative weight is computed correctly.
-        let expected_cumulative_weight = current_block
-            .cumulative_weight()
-            .saturating_add((u64::MAX / expected_difficulty_target) as u128);
-        if block.cumulative_weight() != expected_cumulative_weight {
-            return Err(anyhow!(
-                "The given cumulative weight is incorrect. Found {}, but expected {}",
-                block.cumulative_weight(),
-                expected_cumulative_weight
-            ));
-        }
+        Ok(())
+    }
 
-        // Ensure the block height does not already exist.
-        if self.contains_block_height(block_height)? {
-            return Err(anyhow!("Block {} already exists in the canon chain", block_height));
+    ///
+    /// Adds the given contiguous blocks as the next blocks in the ledger, in a single atomic batch.
+    ///
+    /// The block proofs are verified in parallel, and the ledger tree is updated once for all blocks.
+    /// If any block is invalid or does not extend the block before it, no blocks are added.
+    ///
+    pub fn add_next_blocks(&self, blocks: &[Block<N>]) -> Result<()> {
+        // If the storage is in read-only mode, this method cannot be called.
+        if self.is_read_only() {
+            return Err(anyhow!("Ledger is in read-only mode"));
         }
 
-        // Ensure the block hash does not already exist.
-        if self.contains_block_hash(&block.hash())? {
-            return Err(anyhow!("Block {} has a repeat block hash in the canon chain", block_height));
-        }
+        let last_block = match blocks.last() {
+            Some(block) => block,
+            None => return Ok(()),
+        };
 
-        // Ensure the ledger root in the block matches the current ledger root.
-        if block.previous_ledger_root() != self.latest_ledger_root() {
-            return Err(anyhow!("Block {} declares an incorrect ledger root", block_height));
+        // Ensure the blocks themselves are valid.
+        blocks.par_iter().try_for_each(|block| match block.is_valid() {
+            true => Ok(()),
+            false => Err(anyhow!("Block {} is invalid", block.height())),
+        })?;
+
+        // Ensure each block extends the block before it, starting from the canonical chain.
+        let mut ledger_tree = self.ledger_tree.read().clone();
+        let mut staged = StagedBlocks::default();
+        let mut previous_block = self.latest_block();
+        for block in blocks {
+            self.check_next_block(block, &previous_block, ledger_tree.root(), &staged, &StagedBlocks::default())?;
+            ledger_tree.add(&block.hash())?;
+            staged.add(block);
+            previous_block = block.clone();
         }
 
-        // Ensure the canon chain does not already contain the given serial numbers.
-        for serial_number in block.serial_numbers() {
-            if self.contains_serial_number(serial_number)? {
-                return Err(anyhow!("Serial number {} already exists in the ledger", serial_number));
-            }
-        }
+        {
+            // Acquire the map lock to ensure the following operations aren't interrupted by a shutdown.
+            let _map_lock = self.map_lock.read();
+
+            // Write the blocks to storage as a single atomic batch, along with the pruning of the blocks
+            // that fall out of the pruning depth, if the ledger is pruned. Only blocks that are already
+            // in storage are pruned, as the pruning reads the blocks it prunes.
+            let pruned_block_height = std::cmp::min(self.pruning_target(last_block.height()), self.latest_block_height() + 1);
+            self.atomic_batch(|| {
+                for block in blocks {
+                    self.blocks.add_block(block)?;
+                    self.ledger_roots.insert(&block.previous_ledger_root(), &block.height())?;
+                }
+                self.prune_blocks_below(pruned_block_height)
+            })?;
+            self.pruned_block_height.store(pruned_block_height, Ordering::SeqCst);
 
-        // Ensure the canon chain does not already contain the given commitments.
-        for commitment in block.commitments() {
-            if self.contains_commitment(commitment)? {
-                return Err(anyhow!("Commitment {} already exists in the ledger", commitment));
+            *self.ledger_tree.write() = ledger_tree;
+            {
+                let mut latest_block_hashes_and_headers = self.latest_block_hashes_and_headers.write();
+                for block in blocks {
+                    latest_block_hashes_and_headers.push((block.hash(), block.header().clone()));
+                }
             }
-        }
-
-        // Ensure each transaction in the given block is new to the canon chain.
-        for transaction in block.transactions().iter() {
-            // Ensure the transactions in the given block do not already exist.
-            if self.contains_transaction(&transaction.transaction_id())? {
-                return Err(anyhow!(
-                    "Transaction {} in block {} has a duplicate transaction in the ledger",
-                    transaction.transaction_id(),
-                    block_height
-                ));
-            }
-
-            // Ensure the transaction in the block references a valid past or current ledger root.
-            if !self.contains_ledger_root(&transaction.ledger_root())? {
-                return Err(anyhow!(
-                    "Transaction {} in block {} references non-existent ledger root {}",
-                    transaction.transaction_id(),
-                    block_height,
-                    &transaction.ledger_root()
-                ));
+            *self.latest_block_locators.write() = self.get_block_locators(last_block.height())?;
+            *self.latest_block.write() = last_block.clone();
+            for block in blocks {
+                self.block_cache.insert_block(block);
             }
         }
 
-        // Acquire the map lock to ensure the following operations aren't interrupted by a shutdown.
-        let _map_lock = self.map_lock.read();
-
-        self.blocks.add_block(block)?;
-        self.ledger_tree.write().add(&block.hash())?;
-        self.ledger_roots.insert(&block.previous_ledger_root(), &block.height())?;
-        self.latest_block_hashes_and_headers
-            .write()
-            .push((block.hash(), block.header().clone()));
-        *self.latest_block_locators.write() = self.get_block_locators(block.height())?;
-        *self.latest_block.write() = block.clone();
-
-        // The map lock goes out of scope on its own.
+        // Notify the read-only ledgers in this process of the new blocks.
+        self.notifier.notify(LedgerNotification::BlockAdded(last_block.height()));
 
         Ok(())
     }
@@ -830,40 +1336,44 @@ impl<N: Network> LedgerState<N> {
         // Fetch the blocks to be removed. This ensures the blocks to be removed exist in the ledger,
         // and is used during the removal process to expedite the procedure.
         let start_block_height = latest_block_height.saturating_sub(number_of_blocks);
-        let blocks: BTreeMap<u32, Block<N>> = self
-            .get_blocks(start_block_height, latest_block_height)?
-            .iter()
-            .map(|block| (block.height(), block.clone()))
-            .collect();
+        let blocks: BTreeMap<u32, Block<N>> = match self.get_blocks(start_block_height, latest_block_height) {
+            Ok(blocks) => blocks.iter().map(|block| (block.height(), block.clone())).collect(),
+            Err(error) => {
+                // A block is missing from storage, so attempt to resolve the inconsistent state before failing the revert.
+                let _map_lock = self.map_lock.read();
+                let current_block_height = self.try_fixing_inconsistent_state()?;
+                if current_block_height != latest_block_height {
+                    *self.latest_block.write() = self.get_block(current_block_height)?;
+                    self.regenerate_latest_ledger_state()?;
+                    self.regenerate_ledger_tree()?;
+                }
+                return Err(anyhow!(
+                    "Failed to fetch the blocks to revert to block height {}: {}",
+                    block_height,
+                    error
+                ));
+            }
+        };
 
         // Acquire the map lock to ensure the following operations aren't interrupted by a shutdown.
         let _map_lock = self.map_lock.read();
 
-        // Process the block removals.
-        let mut current_block_height = latest_block_height;
-        let mut current_block = blocks.get(&current_block_height);
-        while current_block_height > block_height {
-            match current_block {
-                Some(block) => {
-                    // Update the internal storage state of the ledger.
-                    self.blocks.remove_block(current_block_height)?;
-                    self.ledger_roots.remove(&block.previous_ledger_root())?;
-                    // Decrement the current block height, and update the current block.
-                    current_block_height = current_block_height.saturating_sub(1);
-                    current_block = blocks.get(&current_block_height);
-                }
-                None => match self.try_fixing_inconsistent_state() {
-                    Ok(block_height) => {
-                        current_block_height = block_height;
-                        break;
-                    }
-                    Err(error) => return Err(error),
-                },
+        // Remove the blocks from storage as a single atomic batch.
+        self.atomic_batch(|| {
+            for (current_block_height, block) in blocks.range(block_height + 1..).rev() {
+                self.blocks.remove_block(*current_block_height)?;
+                self.ledger_roots.remove(&block.previous_ledger_root())?;
             }
+            Ok(())
+        })?;
+
+        // Remove the reverted blocks from the block cache.
+        for block in blocks.values().skip(1) {
+            self.block_cache.remove_block(&block.hash());
         }
 
         // Update the latest block.
-        *self.latest_block.write() = self.get_block(current_block_height)?;
+        *self.latest_block.write() = self.get_block(block_height)?;
         // Regenerate the latest ledger state.
         self.regenerate_latest_ledger_state()?;
         // Regenerate the ledger tree.
@@ -871,42 +1381,332 @@ impl<N: Network> LedgerState<N> {
 
         // The map lock goes out of scope on its own.
 
+        // Notify the read-only ledgers in this process of the reverted blocks.
+        self.notifier.notify(LedgerNotification::Reverted(block_height));
+
         // Return the removed blocks, in increasing order (i.e. 1, 2, 3...).
         Ok(blocks.values().skip(1).cloned().collect())
     }
 
+    /// Returns `true` if the given block hash exists in a side chain.
+    pub fn contains_side_chain_block(&self, block_hash: &N::BlockHash) -> Result<bool> {
+        self.side_chain_blocks.contains_key(block_hash)
+    }
+
     ///
-    /// Returns a ledger proof for the given commitment.
+    /// Adds the given block to a side chain, if its previous block is in the canonical chain
+    /// or in a side chain, and is within the maximum fork depth of the latest block.
     ///
-    pub fn get_ledger_inclusion_proof(&self, commitment: N::Commitment) -> Result<LedgerProof<N>> {
-        // TODO (raychu86): Add getter functions.
-        let commitment_transition_id = match self.blocks.transactions.commitments.get(&commitment)? {
-            Some(transition_id) => transition_id,
-            None => return Err(anyhow!("commitment {} missing from commitments map", commitment)),
+    /// The block itself and its cumulative weight are validated when it is added. The remaining
+    /// checks are deferred until the canonical chain switches to its side chain.
+    ///
+    pub fn add_side_chain_block(&self, block: &Block<N>) -> Result<()> {
+        // If the storage is in read-only mode, this method cannot be called.
+        if self.is_read_only() {
+            return Err(anyhow!("Ledger is in read-only mode"));
+        }
+
+        // Ensure the block is within the maximum fork depth.
+        let block_height = block.height();
+        if block_height == 0 || block_height.saturating_add(MAXIMUM_FORK_DEPTH) <= self.latest_block_height() {
+            return Err(anyhow!("Side chain block {} is beyond the maximum fork depth", block_height));
+        }
+        // Ensure the block is new.
+        if self.contains_block_hash(&block.hash())? || self.contains_side_chain_block(&block.hash())? {
+            return Err(anyhow!(
+                "Side chain block {} ({}) already exists in the ledger",
+                block_height,
+                block.hash()
+            ));
+        }
+        // Ensure the previous block is in the canonical chain or in a side chain, at the previous block height.
+        let previous_block = match self.side_chain_blocks.get(&block.previous_block_hash())? {
+            Some(previous_block) => Some((previous_block.height(), previous_block.cumulative_weight())),
+            None => match self.get_block_height(&block.previous_block_hash()) {
+                Ok(previous_block_height) => Some((previous_block_height, self.get_cumulative_weight(previous_block_height)?)),
+                Err(_) => None,
+            },
+        };
+        let previous_cumulative_weight = match previous_block {
+            Some((previous_block_height, cumulative_weight)) if previous_block_height == block_height - 1 => cumulative_weight,
+            _ => {
+                return Err(anyhow!(
+                    "Side chain block {} ({}) has an unknown previous block",
+                    block_height,
+                    block.hash()
+                ))
+            }
+        };
+        // Ensure the cumulative weight extends the cumulative weight of the previous block.
+        let expected_cumulative_weight = match u64::MAX.checked_div(block.difficulty_target()) {
+            Some(weight) => previous_cumulative_weight.saturating_add(weight as u128),
+            None => return Err(anyhow!("Side chain block {} has an invalid difficulty target", block_height)),
+        };
+        if block.cumulative_weight() != expected_cumulative_weight {
+            return Err(anyhow!(
+                "Side chain block {} has an incorrect cumulative weight. Found {}, but expected {}",
+                block_height,
+                block.cumulative_weight(),
+                expected_cumulative_weight
+            ));
+        }
+        // Ensure the block itself is valid.
+        if !block.is_valid() {
+            return Err(anyhow!("Side chain block {} is invalid", block_height));
+        }
+
+        // Ensure the number of side chain blocks is bounded, once the side chain blocks that have
+        // fallen out of the maximum fork depth are removed.
+        if self.number_of_side_chain_blocks.load(Ordering::SeqCst) >= MAXIMUM_SIDE_CHAIN_BLOCKS {
+            self.prune_side_chain_blocks()?;
+        }
+        if self.number_of_side_chain_blocks.load(Ordering::SeqCst) >= MAXIMUM_SIDE_CHAIN_BLOCKS {
+            return Err(anyhow!(
+                "Side chain block {} exceeds the maximum of {} side chain blocks",
+                block_height,
+                MAXIMUM_SIDE_CHAIN_BLOCKS
+            ));
+        }
+
+        // Acquire the map lock to ensure the following operations aren't interrupted by a shutdown.
+        let _map_lock = self.map_lock.read();
+
+        self.atomic_batch(|| {
+            self.side_chain_blocks.insert(&block.hash(), block)?;
+            self.update_side_chain_children(&[block.clone()], &[])
+        })?;
+        self.number_of_side_chain_blocks.fetch_add(1, Ordering::SeqCst);
+
+        Ok(())
+    }
+
+    ///
+    /// Returns the tips of the side chains within the maximum fork depth of the latest block
+    /// := (block hash, block height, cumulative weight).
+    ///
+    pub fn get_side_chain_tips(&self) -> Result<Vec<(N::BlockHash, u32, u128)>> {
+        let children = self.side_chain_children.iter().collect::<HashMap<_, _>>();
+        let latest_block_height = self.latest_block_height();
+
+        // A side chain block is a tip if it has no children.
+        Ok(children
+            .values()
+            .flatten()
+            .filter(|(block_hash, block_height, _)| {
+                !children.contains_key(block_hash) && block_height.saturating_add(MAXIMUM_FORK_DEPTH) > latest_block_height
+            })
+            .copied()
+            .collect())
+    }
+
+    ///
+    /// Switches the canonical chain to the side chain that ends with the given block hash,
+    /// returning the reverted blocks and the added blocks on success. The reverted blocks
+    /// are retained as a side chain, so that the ledger may switch back to them.
+    ///
+    /// The side chain is validated against the ledger state at the fork before anything is written.
+    /// If a block of the side chain is invalid, it is removed along with its descendants, and the ledger
+    /// only switches to the valid blocks before it if they outweigh the canonical chain. The switch is
+    /// written as a single atomic batch, along with its entry in the reorg journal.
+    ///
+    pub fn switch_to_side_chain(&self, block_hash: &N::BlockHash) -> Result<(Vec<Block<N>>, Vec<Block<N>>)> {
+        // If the storage is in read-only mode, this method cannot be called.
+        if self.is_read_only() {
+            return Err(anyhow!("Ledger is in read-only mode"));
+        }
+
+        // Collect the blocks of the side chain, in increasing order of block height.
+        let mut side_chain = Vec::new();
+        let mut previous_block_hash = *block_hash;
+        while let Some(block) = self.side_chain_blocks.get(&previous_block_hash)? {
+            previous_block_hash = block.previous_block_hash();
+            side_chain.push(block);
+        }
+        side_chain.reverse();
+
+        // Ensure the side chain forks from the canonical chain, within the maximum fork depth.
+        let fork_block_height = match side_chain.first() {
+            Some(block) => block.height() - 1,
+            None => return Err(anyhow!("Block {} is not in a side chain", block_hash)),
         };
+        if self.get_block_hash(fork_block_height)? != previous_block_hash {
+            return Err(anyhow!("Side chain of block {} does not fork from the canonical chain", block_hash));
+        }
+        let latest_block_height = self.latest_block_height();
+        if latest_block_height.saturating_sub(fork_block_height) > MAXIMUM_FORK_DEPTH {
+            return Err(anyhow!("Side chain of block {} is beyond the maximum fork depth", block_hash));
+        }
 
-        let transaction_id = match self.blocks.transactions.transitions.get(&commitment_transition_id)? {
-            Some((transaction_id, _, _)) => transaction_id,
-            None => return Err(anyhow!("transition id {} missing from transactions map", commitment_transition_id)),
+        // Retrieve the canonical blocks after the fork, which are reverted by the switch.
+        let reverted_blocks = match fork_block_height < latest_block_height {
+            true => self.get_blocks(fork_block_height + 1, latest_block_height)?,
+            false => Vec::new(),
         };
+        let mut reverted = StagedBlocks::default();
+        for block in &reverted_blocks {
+            reverted.add(block);
+        }
 
-        let transaction = self.get_transaction(&transaction_id)?;
+        // Ensure each block of the side chain extends the block before it, starting from the ledger state at the fork.
+        let mut ledger_tree = LedgerTree::<N>::new()?;
+        ledger_tree.add_all(&self.get_block_hashes(0, fork_block_height)?)?;
+        let mut staged = StagedBlocks::default();
+        let mut previous_block = self.get_block(fork_block_height)?;
+        let mut invalid_block_error = None;
+        for (index, block) in side_chain.iter().enumerate() {
+            let result = match block.is_valid() {
+                true => self.check_next_block(block, &previous_block, ledger_tree.root(), &staged, &reverted),
+                false => Err(anyhow!("Block {} is invalid", block.height())),
+            };
+            if let Err(error) = result {
+                warn!("Side chain block {} ({}) is invalid - {}", block.height(), block.hash(), error);
+                self.remove_side_chain_block(&block.hash())?;
+                invalid_block_error = Some((index, error));
+                break;
+            }
+            ledger_tree.add(&block.hash())?;
+            staged.add(block);
+            previous_block = block.clone();
+        }
 
-        let block_hash = match self.blocks.transactions.transactions.get(&transaction_id)? {
-            Some((_, _, metadata)) => metadata.block_hash,
-            None => return Err(anyhow!("transaction id {} missing from transactions map", transaction_id)),
+        // Ensure the valid blocks of the side chain outweigh the canonical chain.
+        if let Some((index, _)) = invalid_block_error {
+            side_chain.truncate(index);
+        }
+        let last_block = match side_chain.last() {
+            Some(block) if block.cumulative_weight() > self.latest_cumulative_weight() => block.clone(),
+            _ => {
+                return Err(match invalid_block_error {
+                    Some((_, error)) => error,
+                    None => anyhow!("Side chain of block {} does not outweigh the canonical chain", block_hash),
+                })
+            }
         };
 
-        let block_header = match self.blocks.block_headers.get(&block_hash)? {
-            Some(block_header) => block_header,
-            None => return Err(anyhow!("Block {} missing from block headers map", block_hash)),
+        // Acquire the map lock to ensure the following operations aren't interrupted by a shutdown.
+        let _map_lock = self.map_lock.read();
+
+        // Revert the canonical chain to the fork, retaining the reverted blocks as a side chain, add the blocks of
+        // the side chain, and record the reorg in the journal, as a single atomic batch. This includes the pruning
+        // of the blocks that fall out of the pruning depth, if the ledger is pruned.
+        let pruned_block_height = self.pruning_target(last_block.height());
+        self.atomic_batch(|| {
+            for block in reverted_blocks.iter().rev() {
+                self.blocks.remove_block(block.height())?;
+                self.ledger_roots.remove(&block.previous_ledger_root())?;
+                self.side_chain_blocks.insert(&block.hash(), block)?;
+            }
+            for block in &side_chain {
+                // The block is inserted without the existence checks, as they would see the reverted blocks,
+                // which are only removed once the batch is finished.
+                self.blocks.insert_block(block)?;
+                self.ledger_roots.insert(&block.previous_ledger_root(), &block.height())?;
+                self.side_chain_blocks.remove(&block.hash())?;
+            }
+            let removed_blocks = side_chain
+                .iter()
+                .map(|block| (block.previous_block_hash(), block.hash()))
+                .collect::<Vec<_>>();
+            self.update_side_chain_children(&reverted_blocks, &removed_blocks)?;
+            if !reverted_blocks.is_empty() {
+                self.insert_reorg(&reverted_blocks, &last_block)?;
+            }
+            self.prune_blocks_below(pruned_block_height)
+        })?;
+        self.pruned_block_height.store(pruned_block_height, Ordering::SeqCst);
+        self.number_of_side_chain_blocks.fetch_add(reverted_blocks.len(), Ordering::SeqCst);
+        self.number_of_side_chain_blocks.fetch_sub(side_chain.len(), Ordering::SeqCst);
+
+        // Update the block cache with the reverted blocks and the added blocks.
+        for block in &reverted_blocks {
+            self.block_cache.remove_block(&block.hash());
+        }
+        for block in &side_chain {
+            self.block_cache.insert_block(block);
+        }
+
+        *self.ledger_tree.write() = ledger_tree;
+        *self.latest_block.write() = last_block.clone();
+        // Regenerate the latest ledger state.
+        self.regenerate_latest_ledger_state()?;
+
+        // The map lock goes out of scope on its own.
+
+        // Notify the read-only ledgers in this process of the reverted blocks and the added blocks.
+        if !reverted_blocks.is_empty() {
+            self.notifier.notify(LedgerNotification::Reverted(fork_block_height));
+        }
+        self.notifier.notify(LedgerNotification::BlockAdded(last_block.height()));
+
+        Ok((reverted_blocks, side_chain))
+    }
+
+    ///
+    /// Records a reorg in the journal, from the last of the given reverted blocks to the latest block.
+    /// This is called once the chain that replaces the reverted blocks is applied.
+    ///
+    pub fn add_reorg(&self, reverted_blocks: &[Block<N>]) -> Result<()> {
+        // If the storage is in read-only mode, this method cannot be called.
+        if self.is_read_only() {
+            return Err(anyhow!("Ledger is in read-only mode"));
+        }
+
+        // Acquire the map lock to ensure the following operations aren't interrupted by a shutdown.
+        let _map_lock = self.map_lock.read();
+
+        self.insert_reorg(reverted_blocks, &self.latest_block())
+    }
+
+    /// Returns the reorgs in the journal, in the order they occurred.
+    pub fn get_reorgs(&self) -> Result<Vec<Reorg<N>>> {
+        Ok(self.reorgs.values().collect())
+    }
+
+    ///
+    /// Returns a ledger proof for the given commitment.
+    ///
+    pub fn get_ledger_inclusion_proof(&self, commitment: N::Commitment) -> Result<LedgerProof<N>> {
+        // Retrieve the record proof, which is retained in storage for the commitments of pruned blocks.
+        let (block_hash, record_proof) = match self.blocks.transactions.record_proofs.get(&commitment)? {
+            Some((block_hash, record_proof)) => (block_hash, RecordProof::<N>::read_le(&record_proof[..])?),
+            None => {
+                // TODO (raychu86): Add getter functions.
+                let commitment_transition_id = match self.blocks.transactions.commitments.get(&commitment)? {
+                    Some(transition_id) => transition_id,
+                    None => return Err(anyhow!("commitment {} missing from commitments map", commitment)),
+                };
+
+                let transaction_id = match self.blocks.transactions.transitions.get(&commitment_transition_id)? {
+                    Some((transaction_id, _, _)) => transaction_id,
+                    None => return Err(anyhow!("transition id {} missing from transactions map", commitment_transition_id)),
+                };
+
+                let transaction = self.get_transaction(&transaction_id)?;
+
+                let block_height = match self.blocks.transactions.transactions.get(&transaction_id)? {
+                    Some((_, _, metadata)) => metadata.block_height,
+                    None => return Err(anyhow!("transaction id {} missing from transactions map", transaction_id)),
+                };
+
+                let block = self.get_block(block_height)?;
+                (block.hash(), Self::to_record_proof(&block, &transaction, commitment)?)
+            }
         };
 
+        // Generate the ledger root inclusion proof.
+        let ledger_root = self.ledger_tree.read().root();
+        let ledger_root_inclusion_proof = self.ledger_tree.read().to_ledger_inclusion_proof(&block_hash)?;
+
+        LedgerProof::new(ledger_root, ledger_root_inclusion_proof, record_proof)
+    }
+
+    /// Returns the record proof for the given commitment, in the given transaction of the given block.
+    fn to_record_proof(block: &Block<N>, transaction: &Transaction<N>, commitment: N::Commitment) -> Result<RecordProof<N>> {
         // Generate the local proof for the commitment.
         let local_proof = transaction.to_local_proof(commitment)?;
 
         let transaction_id = local_proof.transaction_id();
-        let transactions = self.get_block_transactions(block_header.height())?;
+        let transactions = block.transactions();
 
         // Compute the transactions inclusion proof.
         let transactions_inclusion_proof = {
@@ -923,30 +1723,775 @@ impl<N: Network> LedgerState<N> {
             transactions.to_transactions_inclusion_proof(index[0], transaction_id)?
         };
 
-        // Compute the block header inclusion proof.
-        let transactions_root = transactions.transactions_root();
-        let block_header_inclusion_proof = block_header.to_header_inclusion_proof(1, transactions_root)?;
-        let block_header_root = block_header.to_header_root()?;
-
-        // Determine the previous block hash.
-        let previous_block_hash = self.get_previous_block_hash(self.get_block_height(&block_hash)?)?;
+        // Compute the block header inclusion proof.
+        let transactions_root = transactions.transactions_root();
+        let block_header_inclusion_proof = block.header().to_header_inclusion_proof(1, transactions_root)?;
+        let block_header_root = block.header().to_header_root()?;
+
+        // Generate the record proof.
+        RecordProof::new(
+            block.hash(),
+            block.previous_block_hash(),
+            block_header_root,
+            block_header_inclusion_proof,
+            transactions_root,
+            transactions_inclusion_proof,
+            local_proof,
+        )
+    }
+
+    /// Initializes a new instance of `LedgerState` from the given storage, without loading any ledger state.
+    fn initialize<S: Storage>(storage: S, is_read_only: bool) -> Result<Self> {
+        // Retrieve the pruning state, if the ledger is pruned.
+        let pruning: DataMap<(), (u32, u32)> = storage.open_map(MapId::Pruning)?;
+        let (pruning_depth, pruned_block_height) = match pruning.get(&())? {
+            Some((pruning_depth, pruned_block_height)) => (Some(pruning_depth), pruned_block_height),
+            None => (None, 0),
+        };
+        // Count the side chain blocks, each of which is a child of its previous block.
+        let side_chain_children: DataMap<N::BlockHash, Vec<(N::BlockHash, u32, u128)>> = storage.open_map(MapId::SideChainChildren)?;
+        let number_of_side_chain_blocks = side_chain_children.values().map(|children| children.len()).sum();
+
+        Ok(Self {
+            ledger_tree: RwLock::new(LedgerTree::<N>::new()?),
+            latest_block: RwLock::new(N::genesis_block().clone()),
+            latest_block_hashes_and_headers: RwLock::new(CircularQueue::with_capacity(MAXIMUM_LINEAR_BLOCK_LOCATORS as usize)),
+            latest_block_locators: Default::default(),
+            ledger_roots: storage.open_map(MapId::LedgerRoots)?,
+            reorgs: storage.open_map(MapId::Reorgs)?,
+            side_chain_blocks: storage.open_map(MapId::SideChainBlocks)?,
+            side_chain_children,
+            number_of_side_chain_blocks: AtomicUsize::new(number_of_side_chain_blocks),
+            blocks: BlockState::open(storage)?,
+            block_cache: Default::default(),
+            pruning,
+            pruning_depth,
+            pruned_block_height: AtomicU32::new(pruned_block_height),
+            read_only: (is_read_only, Arc::new(AtomicU32::new(0)), RwLock::new(None)),
+            map_lock: Default::default(),
+            notifier: Default::default(),
+        })
+    }
+
+    /// Inserts the entries of the given snapshot chunk into their corresponding map.
+    fn import_snapshot_chunk(&self, chunk: &SnapshotChunk) -> Result<()> {
+        let transactions = &self.blocks.transactions;
+        match chunk {
+            chunk if chunk.is_for(MapId::LedgerRoots) => chunk.insert_into(&self.ledger_roots),
+            chunk if chunk.is_for(MapId::BlockHeights) => chunk.insert_into(&self.blocks.block_heights),
+            chunk if chunk.is_for(MapId::BlockHeaders) => chunk.insert_into(&self.blocks.block_headers),
+            chunk if chunk.is_for(MapId::BlockTransactions) => chunk.insert_into(&self.blocks.block_transactions),
+            chunk if chunk.is_for(MapId::Transactions) => chunk.insert_into(&transactions.transactions),
+            chunk if chunk.is_for(MapId::Transitions) => chunk.insert_into(&transactions.transitions),
+            chunk if chunk.is_for(MapId::SerialNumbers) => chunk.insert_into(&transactions.serial_numbers),
+            chunk if chunk.is_for(MapId::Commitments) => chunk.insert_into(&transactions.commitments),
+            chunk if chunk.is_for(MapId::RecordProofs) => chunk.insert_into(&transactions.record_proofs),
+            chunk if chunk.is_for(MapId::Pruning) => chunk.insert_into(&self.pruning),
+            chunk => Err(anyhow!("The snapshot contains entries for an unknown map '{}'", chunk.map_name())),
+        }
+    }
+
+    /// Returns the temporary sibling of the given storage path with the given suffix, which is used to stage new storage.
+    fn temporary_path(path: &Path, suffix: &str) -> PathBuf {
+        let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+        file_name.push(format!(".{}", suffix));
+        path.with_file_name(file_name)
+    }
+
+    /// Returns the block hash of the given block header, which extends the block with the given previous block hash.
+    fn compute_block_hash(previous_block_hash: N::BlockHash, block_header: &BlockHeader<N>) -> Result<N::BlockHash> {
+        let header_root = block_header.to_header_root()?;
+        Ok(N::block_hash_crh()
+            .hash_bits(&[previous_block_hash.to_bits_le(), header_root.to_bits_le()].concat())?
+            .into())
+    }
+
+    /// Performs the given storage operations as a single atomic batch, discarding all of them on failure.
+    fn atomic_batch<F: FnOnce() -> Result<()>>(&self, operations: F) -> Result<()> {
+        // The atomic batch is shared by all maps in storage, so it may be started from any map.
+        self.ledger_roots.start_atomic()?;
+        match operations() {
+            Ok(()) => self.ledger_roots.finish_atomic(),
+            Err(error) => {
+                self.ledger_roots.abort_atomic();
+                Err(error)
+            }
+        }
+    }
+
+    ///
+    /// Verifies the block heights, block headers, and block transactions of each block in order,
+    /// along with the ledger roots recomputed from the block hashes, and returns the latest ledger root.
+    ///
+    /// The ledger tree is rebuilt once, block by block, so the ledger root of every block is checked
+    /// until the first missing block height.
+    ///
+    fn verify_blocks(&self, report: &mut VerificationReport) -> Result<Option<N::LedgerRoot>> {
+        let blocks = &self.blocks;
+
+        // The ledger tree is recomputed until the first missing block height.
+        let mut ledger_tree = Some(LedgerTree::<N>::new()?);
+        // The block hash and cumulative weight of the previous block, if it was verified.
+        let mut previous_block = Some((N::genesis_block().previous_block_hash(), 0u128));
+        let mut expected_block_height = 0u32;
+
+        for (block_height, block_hash) in blocks.block_heights.iter() {
+            report.number_of_blocks += 1;
+
+            // Check 1 - Ensure the block heights are contiguous.
+            if block_height != expected_block_height {
+                let message = format!("Blocks {} to {} are missing", expected_block_height, block_height.saturating_sub(1));
+                report.add(DiscrepancyKind::BlockHeight, MapId::BlockHeights, Some(block_height), message);
+                ledger_tree = None;
+                previous_block = None;
+            }
+            expected_block_height = block_height.saturating_add(1);
+
+            // Check 2 - Ensure the block header exists, and corresponds to the block height.
+            let block_header = match blocks.block_headers.get(&block_hash)? {
+                Some(block_header) => block_header,
+                None => {
+                    let message = format!("Block {} is missing from the block headers map", block_hash);
+                    report.add(DiscrepancyKind::BlockHeader, MapId::BlockHeaders, Some(block_height), message);
+                    if let Some(ledger_tree) = &mut ledger_tree {
+                        ledger_tree.add(&block_hash)?;
+                    }
+                    previous_block = None;
+                    continue;
+                }
+            };
+            if block_header.height() != block_height {
+                let message = format!("Block {} has height {} in its block header", block_hash, block_header.height());
+                report.add(DiscrepancyKind::BlockHeader, MapId::BlockHeaders, Some(block_height), message);
+            }
+
+            // Check 3 - Ensure the block hash is recomputed from the previous block hash and the block header,
+            // and the block increases the cumulative weight of the previous block.
+            if let Some((previous_block_hash, previous_cumulative_weight)) = previous_block {
+                let candidate_block_hash = Self::compute_block_hash(previous_block_hash, &block_header)?;
+                if candidate_block_hash != block_hash {
+                    let message = format!(
+                        "Block {} has a block header that hashes to {} from previous block {}",
+                        block_hash, candidate_block_hash, previous_block_hash
+                    );
+                    report.add(DiscrepancyKind::PreviousBlockHash, MapId::BlockHeaders, Some(block_height), message);
+                }
+                if block_height > 0 && block_header.cumulative_weight() <= previous_cumulative_weight {
+                    let message = format!(
+                        "Block {} has cumulative weight {}, which does not exceed the previous cumulative weight {}",
+                        block_hash,
+                        block_header.cumulative_weight(),
+                        previous_cumulative_weight
+                    );
+                    report.add(DiscrepancyKind::CumulativeWeight, MapId::BlockHeaders, Some(block_height), message);
+                }
+            }
+            previous_block = Some((block_hash, block_header.cumulative_weight()));
+
+            // Check 4 - Ensure the ledger root in the block header is saved for this block height.
+            match self.ledger_roots.get(&block_header.previous_ledger_root())? {
+                Some(candidate_height) if candidate_height == block_height => (),
+                candidate_height => {
+                    let message = format!(
+                        "Ledger root {} of block {} is saved for block {:?}",
+                        block_header.previous_ledger_root(),
+                        block_hash,
+                        candidate_height
+                    );
+                    report.add(DiscrepancyKind::LedgerRoot, MapId::LedgerRoots, Some(block_height), message);
+                }
+            }
+
+            // Check 5 - Ensure the ledger root in the block header matches the ledger root recomputed from the previous block hashes.
+            if let Some(ledger_tree) = &mut ledger_tree {
+                if block_height > 0 && block_header.previous_ledger_root() != ledger_tree.root() {
+                    let message = format!(
+                        "Block {} has ledger root {}, but the recomputed ledger root is {}",
+                        block_hash,
+                        block_header.previous_ledger_root(),
+                        ledger_tree.root()
+                    );
+                    report.add(DiscrepancyKind::LedgerRoot, MapId::BlockHeaders, Some(block_height), message);
+                }
+                ledger_tree.add(&block_hash)?;
+            }
+
+            // Check 6 - Ensure the transactions of the block point back to this block.
+            let transaction_ids = match blocks.block_transactions.get(&block_hash)? {
+                Some(transaction_ids) => transaction_ids,
+                None => {
+                    let message = format!("Block {} is missing from the block transactions map", block_hash);
+                    report.add(
+                        DiscrepancyKind::BlockTransactions,
+                        MapId::BlockTransactions,
+                        Some(block_height),
+                        message,
+                    );
+                    continue;
+                }
+            };
+            for (transaction_index, transaction_id) in transaction_ids.iter().enumerate() {
+                match blocks.transactions.transactions.get(transaction_id)? {
+                    Some((_, _, metadata)) => {
+                        if metadata.block_height != block_height
+                            || metadata.block_hash != block_hash
+                            || metadata.transaction_index as usize != transaction_index
+                        {
+                            let message = format!(
+                                "Transaction {} at index {} of block {} has metadata for index {} of block {} ({})",
+                                transaction_id,
+                                transaction_index,
+                                block_hash,
+                                metadata.transaction_index,
+                                metadata.block_height,
+                                metadata.block_hash
+                            );
+                            report.add(DiscrepancyKind::Transaction, MapId::Transactions, Some(block_height), message);
+                        }
+                    }
+                    None => {
+                        let message = format!(
+                            "Transaction {} of block {} is missing from the transactions map",
+                            transaction_id, block_hash
+                        );
+                        report.add(DiscrepancyKind::Transaction, MapId::Transactions, Some(block_height), message);
+                    }
+                }
+            }
+
+            // Check 7 - Ensure the transactions of the block match the transactions root, if the block has not been pruned.
+            if block_height >= report.pruned_block_height {
+                match blocks.get_block_transactions_by_hash(&block_hash) {
+                    Ok(transactions) => {
+                        if transactions.transactions_root() != block_header.transactions_root() {
+                            let message = format!("Block {} has transactions that do not match its transactions root", block_hash);
+                            report.add(
+                                DiscrepancyKind::BlockTransactions,
+                                MapId::BlockTransactions,
+                                Some(block_height),
+                                message,
+                            );
+                        }
+                    }
+                    Err(error) => {
+                        let message = format!("Block {} has invalid transactions: {}", block_hash, error);
+                        report.add(
+                            DiscrepancyKind::BlockTransactions,
+                            MapId::BlockTransactions,
+                            Some(block_height),
+                            message,
+                        );
+                    }
+                }
+            }
+        }
+
+        // Ensure the ledger contains at least the genesis block.
+        if report.number_of_blocks == 0 {
+            report.add(
+                DiscrepancyKind::BlockHeight,
+                MapId::BlockHeights,
+                None,
+                "The ledger has no blocks".to_string(),
+            );
+        }
+
+        // Return the latest ledger root.
+        Ok(ledger_tree.map(|ledger_tree| ledger_tree.root()))
+    }
+
+    /// Verifies that each saved ledger root corresponds to the ledger root in the block header at its block height.
+    fn verify_ledger_roots(&self, report: &mut VerificationReport) -> Result<()> {
+        for (ledger_root, block_height) in self.ledger_roots.iter() {
+            report.number_of_ledger_roots += 1;
+
+            let message = match self.blocks.block_heights.get(&block_height)? {
+                Some(block_hash) => match self.blocks.block_headers.get(&block_hash)? {
+                    Some(block_header) if block_header.previous_ledger_root() == ledger_root => continue,
+                    Some(block_header) => format!(
+                        "Ledger root {} is saved for block {}, which has ledger root {}",
+                        ledger_root,
+                        block_height,
+                        block_header.previous_ledger_root()
+                    ),
+                    None => continue,
+                },
+                None => format!(
+                    "Ledger root {} is saved for block {}, which does not exist",
+                    ledger_root, block_height
+                ),
+            };
+            report.add(DiscrepancyKind::LedgerRoot, MapId::LedgerRoots, Some(block_height), message);
+        }
+        Ok(())
+    }
+
+    /// Verifies that each transaction points back to a canonical block, along with its transitions.
+    fn verify_transactions(&self, report: &mut VerificationReport, latest_ledger_root: Option<N::LedgerRoot>) -> Result<()> {
+        let blocks = &self.blocks;
+        let transactions = &blocks.transactions;
+
+        for (transaction_id, (ledger_root, transition_ids, metadata)) in transactions.transactions.iter() {
+            report.number_of_transactions += 1;
+            let block_height = Some(metadata.block_height);
+
+            // Ensure the transaction points back to a block on the canonical chain.
+            if blocks.block_heights.get(&metadata.block_height)? != Some(metadata.block_hash) {
+                let message = format!(
+                    "Transaction {} points to block {} ({}), which is not on the canonical chain",
+                    transaction_id, metadata.block_height, metadata.block_hash
+                );
+                report.add(DiscrepancyKind::Transaction, MapId::Transactions, block_height, message);
+            }
+
+            // Ensure the block lists the transaction at its transaction index.
+            let block_transaction_ids = blocks.block_transactions.get(&metadata.block_hash)?.unwrap_or_default();
+            if block_transaction_ids.get(metadata.transaction_index as usize) != Some(&transaction_id) {
+                let message = format!(
+                    "Transaction {} is not listed at index {} of block {}",
+                    transaction_id, metadata.transaction_index, metadata.block_hash
+                );
+                report.add(DiscrepancyKind::Transaction, MapId::Transactions, block_height, message);
+            }
+
+            // Ensure the transaction references a saved ledger root.
+            if Some(ledger_root) != latest_ledger_root && !self.ledger_roots.contains_key(&ledger_root)? {
+                let message = format!("Transaction {} references non-existent ledger root {}", transaction_id, ledger_root);
+                report.add(DiscrepancyKind::Transaction, MapId::Transactions, block_height, message);
+            }
+
+            // Ensure the transitions of the transaction point back to it, if the block has not been pruned.
+            if metadata.block_height >= report.pruned_block_height {
+                for (index, transition_id) in transition_ids.iter().enumerate() {
+                    match transactions.transitions.get(transition_id)? {
+                        Some((candidate_id, candidate_index, _)) if candidate_id == transaction_id && candidate_index as usize == index => {
+                        }
+                        Some((candidate_id, candidate_index, _)) => {
+                            let message = format!(
+                                "Transition {} of transaction {} points to index {} of transaction {}",
+                                transition_id, transaction_id, candidate_index, candidate_id
+                            );
+                            report.add(DiscrepancyKind::Transition, MapId::Transitions, block_height, message);
+                        }
+                        None => {
+                            let message = format!(
+                                "Transition {} of transaction {} is missing from the transitions map",
+                                transition_id, transaction_id
+                            );
+                            report.add(DiscrepancyKind::Transition, MapId::Transitions, block_height, message);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Verifies that each transition points back to an existing transaction, and that its serial numbers and commitments are indexed.
+    fn verify_transitions(&self, report: &mut VerificationReport) -> Result<()> {
+        let transactions = &self.blocks.transactions;
+
+        for (transition_id, (transaction_id, index, transition)) in transactions.transitions.iter() {
+            report.number_of_transitions += 1;
+
+            // Ensure the transition ID matches the transition.
+            if transition.transition_id() != transition_id {
+                let message = format!("Transition {} is saved as transition {}", transition.transition_id(), transition_id);
+                report.add(DiscrepancyKind::Transition, MapId::Transitions, None, message);
+            }
+
+            // Ensure the transition points back to an existing transaction, which lists it at its index.
+            let block_height = match transactions.transactions.get(&transaction_id)? {
+                Some((_, transition_ids, metadata)) => {
+                    if transition_ids.get(index as usize) != Some(&transition_id) {
+                        let message = format!(
+                            "Transition {} is not listed at index {} of transaction {}",
+                            transition_id, index, transaction_id
+                        );
+                        report.add(
+                            DiscrepancyKind::Transition,
+                            MapId::Transitions,
+                            Some(metadata.block_height),
+                            message,
+                        );
+                    }
+                    Some(metadata.block_height)
+                }
+                None => {
+                    let message = format!(
+                        "Transition {} points to transaction {}, which does not exist",
+                        transition_id, transaction_id
+                    );
+                    report.add(DiscrepancyKind::Transition, MapId::Transitions, None, message);
+                    None
+                }
+            };
+
+            // Ensure the serial numbers and commitments of the transition are indexed.
+            for serial_number in transition.serial_numbers() {
+                if transactions.serial_numbers.get(serial_number)? != Some(transition_id) {
+                    let message = format!(
+                        "Serial number {} of transition {} is not indexed to it",
+                        serial_number, transition_id
+                    );
+                    report.add(DiscrepancyKind::SerialNumber, MapId::SerialNumbers, block_height, message);
+                }
+            }
+            for commitment in transition.commitments() {
+                if transactions.commitments.get(commitment)? != Some(transition_id) {
+                    let message = format!("Commitment {} of transition {} is not indexed to it", commitment, transition_id);
+                    report.add(DiscrepancyKind::Commitment, MapId::Commitments, block_height, message);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Verifies that each serial number points back to an existing transition that contains it.
+    ///
+    /// The transitions of a pruned block are discarded, so serial numbers that point
+    /// to a missing transition are only reported if the ledger has not been pruned.
+    ///
+    fn verify_serial_numbers(&self, report: &mut VerificationReport) -> Result<()> {
+        let transactions = &self.blocks.transactions;
+
+        for (serial_number, transition_id) in transactions.serial_numbers.iter() {
+            report.number_of_serial_numbers += 1;
+
+            let message = match transactions.transitions.get(&transition_id)? {
+                Some((_, _, transition)) if transition.serial_numbers().any(|candidate| *candidate == serial_number) => continue,
+                Some(_) => format!(
+                    "Serial number {} points to transition {}, which does not contain it",
+                    serial_number, transition_id
+                ),
+                None if report.pruned_block_height > 0 => continue,
+                None => format!(
+                    "Serial number {} points to transition {}, which does not exist",
+                    serial_number, transition_id
+                ),
+            };
+            report.add(DiscrepancyKind::SerialNumber, MapId::SerialNumbers, None, message);
+        }
+        Ok(())
+    }
+
+    ///
+    /// Verifies that each commitment points back to an existing transition that contains it.
+    ///
+    /// The transitions of a pruned block are discarded, so a commitment that points
+    /// to a missing transition must have a record proof for an existing block instead.
+    ///
+    fn verify_commitments(&self, report: &mut VerificationReport) -> Result<()> {
+        let transactions = &self.blocks.transactions;
+
+        for (commitment, transition_id) in transactions.commitments.iter() {
+            report.number_of_commitments += 1;
+
+            let message = match transactions.transitions.get(&transition_id)? {
+                Some((_, _, transition)) if transition.commitments().any(|candidate| *candidate == commitment) => continue,
+                Some(_) => format!(
+                    "Commitment {} points to transition {}, which does not contain it",
+                    commitment, transition_id
+                ),
+                None => match transactions.record_proofs.get(&commitment)? {
+                    Some((block_hash, _)) if self.blocks.block_headers.contains_key(&block_hash)? => continue,
+                    Some((block_hash, _)) => format!(
+                        "Commitment {} has a record proof for block {}, which does not exist",
+                        commitment, block_hash
+                    ),
+                    None => format!(
+                        "Commitment {} points to transition {}, which does not exist",
+                        commitment, transition_id
+                    ),
+                },
+            };
+            report.add(DiscrepancyKind::Commitment, MapId::Commitments, None, message);
+        }
+        Ok(())
+    }
+
+    /// Rebuilds the ledger roots map from the block headers of the canonical blocks, removing any orphaned ledger roots.
+    fn repair_ledger_roots(&self, report: &mut RepairReport) -> Result<()> {
+        let mut insertions = Vec::new();
+        let mut removals = Vec::new();
+
+        // Ensure the ledger root of each canonical block is saved for its block height.
+        for (block_height, block_hash) in self.blocks.block_heights.iter() {
+            let ledger_root = match self.blocks.block_headers.get(&block_hash)? {
+                Some(block_header) => block_header.previous_ledger_root(),
+                None => {
+                    return Err(anyhow!(
+                        "Block {} missing from block headers map, and cannot be repaired",
+                        block_hash
+                    ))
+                }
+            };
+            if self.ledger_roots.get(&ledger_root)? != Some(block_height) {
+                insertions.push((ledger_root, block_height));
+            }
+        }
+
+        // Ensure each saved ledger root belongs to the canonical block at its block height.
+        for (ledger_root, block_height) in self.ledger_roots.iter() {
+            let is_canonical = match self.blocks.block_heights.get(&block_height)? {
+                Some(block_hash) => match self.blocks.block_headers.get(&block_hash)? {
+                    Some(block_header) => block_header.previous_ledger_root() == ledger_root,
+                    None => false,
+                },
+                None => false,
+            };
+            if !is_canonical && !insertions.iter().any(|(candidate, _)| *candidate == ledger_root) {
+                removals.push((ledger_root, block_height));
+            }
+        }
+
+        for (ledger_root, block_height) in insertions.iter() {
+            let entry = format!("{} => {}", ledger_root, block_height);
+            report.add(RepairAction::Insert, MapId::LedgerRoots, Some(*block_height), entry);
+        }
+        for (ledger_root, block_height) in removals.iter() {
+            let entry = format!("{} => {}", ledger_root, block_height);
+            report.add(RepairAction::Remove, MapId::LedgerRoots, Some(*block_height), entry);
+        }
+
+        match report.dry_run {
+            true => Ok(()),
+            false => self.atomic_batch(|| {
+                for (ledger_root, _) in removals.iter() {
+                    self.ledger_roots.remove(ledger_root)?;
+                }
+                for (ledger_root, block_height) in insertions.iter() {
+                    self.ledger_roots.insert(ledger_root, block_height)?;
+                }
+                Ok(())
+            }),
+        }
+    }
+
+    ///
+    /// Rebuilds the serial numbers map from the transitions of the canonical blocks, removing any orphaned serial numbers.
+    ///
+    /// The transitions of a pruned block are discarded, so the serial numbers of a pruned ledger
+    /// that point to a missing transition are retained.
+    ///
+    fn repair_serial_numbers(&self, report: &mut RepairReport) -> Result<()> {
+        let transactions = &self.blocks.transactions;
+        let mut insertions = Vec::new();
+        let mut removals = Vec::new();
+
+        // Ensure each serial number of a canonical transition points to the transition.
+        self.for_each_canonical_transition(|block_height, transition| {
+            for serial_number in transition.serial_numbers() {
+                if transactions.serial_numbers.get(serial_number)? != Some(transition.transition_id()) {
+                    insertions.push((*serial_number, transition.transition_id(), block_height));
+                }
+            }
+            Ok(())
+        })?;
+
+        // Ensure each saved serial number points to a canonical transition that contains it.
+        for (serial_number, transition_id) in transactions.serial_numbers.iter() {
+            let is_canonical = match self.get_canonical_transition(&transition_id)? {
+                Some(transition) => transition.serial_numbers().any(|candidate| *candidate == serial_number),
+                None => self.pruned_block_height() > 0 && !transactions.transitions.contains_key(&transition_id)?,
+            };
+            if !is_canonical && !insertions.iter().any(|(candidate, _, _)| *candidate == serial_number) {
+                removals.push((serial_number, transition_id));
+            }
+        }
+
+        for (serial_number, transition_id, block_height) in insertions.iter() {
+            let entry = format!("{} => {}", serial_number, transition_id);
+            report.add(RepairAction::Insert, MapId::SerialNumbers, Some(*block_height), entry);
+        }
+        for (serial_number, transition_id) in removals.iter() {
+            let entry = format!("{} => {}", serial_number, transition_id);
+            report.add(RepairAction::Remove, MapId::SerialNumbers, None, entry);
+        }
+
+        match report.dry_run {
+            true => Ok(()),
+            false => self.atomic_batch(|| {
+                for (serial_number, _) in removals.iter() {
+                    transactions.serial_numbers.remove(serial_number)?;
+                }
+                for (serial_number, transition_id, _) in insertions.iter() {
+                    transactions.serial_numbers.insert(serial_number, transition_id)?;
+                }
+                Ok(())
+            }),
+        }
+    }
+
+    ///
+    /// Rebuilds the commitments map from the transitions of the canonical blocks, removing any orphaned commitments.
+    ///
+    /// The transitions of a pruned block are discarded, so the commitments of a pruned ledger
+    /// that point to a missing transition are retained if they have a record proof.
+    ///
+    fn repair_commitments(&self, report: &mut RepairReport) -> Result<()> {
+        let transactions = &self.blocks.transactions;
+        let mut insertions = Vec::new();
+        let mut removals = Vec::new();
+
+        // Ensure each commitment of a canonical transition points to the transition.
+        self.for_each_canonical_transition(|block_height, transition| {
+            for commitment in transition.commitments() {
+                if transactions.commitments.get(commitment)? != Some(transition.transition_id()) {
+                    insertions.push((*commitment, transition.transition_id(), block_height));
+                }
+            }
+            Ok(())
+        })?;
+
+        // Ensure each saved commitment points to a canonical transition that contains it.
+        for (commitment, transition_id) in transactions.commitments.iter() {
+            let is_canonical = match self.get_canonical_transition(&transition_id)? {
+                Some(transition) => transition.commitments().any(|candidate| *candidate == commitment),
+                None => !transactions.transitions.contains_key(&transition_id)? && transactions.record_proofs.contains_key(&commitment)?,
+            };
+            if !is_canonical && !insertions.iter().any(|(candidate, _, _)| *candidate == commitment) {
+                removals.push((commitment, transition_id));
+            }
+        }
+
+        for (commitment, transition_id, block_height) in insertions.iter() {
+            let entry = format!("{} => {}", commitment, transition_id);
+            report.add(RepairAction::Insert, MapId::Commitments, Some(*block_height), entry);
+        }
+        for (commitment, transition_id) in removals.iter() {
+            let entry = format!("{} => {}", commitment, transition_id);
+            report.add(RepairAction::Remove, MapId::Commitments, None, entry);
+        }
+
+        match report.dry_run {
+            true => Ok(()),
+            false => self.atomic_batch(|| {
+                for (commitment, _) in removals.iter() {
+                    transactions.commitments.remove(commitment)?;
+                }
+                for (commitment, transition_id, _) in insertions.iter() {
+                    transactions.commitments.insert(commitment, transition_id)?;
+                }
+                Ok(())
+            }),
+        }
+    }
+
+    /// Calls the given function with the block height and transition of each transition in the canonical blocks that has not been pruned.
+    fn for_each_canonical_transition<F: FnMut(u32, &Transition<N>) -> Result<()>>(&self, mut function: F) -> Result<()> {
+        let blocks = &self.blocks;
+        for (block_height, block_hash) in blocks.block_heights.range(self.pruned_block_height()..)? {
+            let transaction_ids = blocks.block_transactions.get(&block_hash)?.unwrap_or_default();
+            for transaction_id in transaction_ids.iter() {
+                if let Some((_, transition_ids, _)) = blocks.transactions.transactions.get(transaction_id)? {
+                    for transition_id in transition_ids.iter() {
+                        if let Some((_, _, transition)) = blocks.transactions.transitions.get(transition_id)? {
+                            function(block_height, &transition)?;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the transition for the given transition ID, if it exists and belongs to a transaction in a canonical block.
+    fn get_canonical_transition(&self, transition_id: &N::TransitionID) -> Result<Option<Transition<N>>> {
+        let transactions = &self.blocks.transactions;
+        let (transaction_id, transition) = match transactions.transitions.get(transition_id)? {
+            Some((transaction_id, _, transition)) => (transaction_id, transition),
+            None => return Ok(None),
+        };
+        let metadata = match transactions.transactions.get(&transaction_id)? {
+            Some((_, _, metadata)) => metadata,
+            None => return Ok(None),
+        };
+        match self.blocks.block_heights.get(&metadata.block_height)? == Some(metadata.block_hash) {
+            true => Ok(Some(transition)),
+            false => Ok(None),
+        }
+    }
+
+    /// Ensures the block at the given block height has not been pruned.
+    fn ensure_block_is_not_pruned(&self, block_height: u32) -> Result<()> {
+        match block_height < self.pruned_block_height() {
+            true => Err(anyhow!("Block {} has been pruned from storage", block_height)),
+            false => Ok(()),
+        }
+    }
+
+    /// Returns the height of the oldest block to retain in full, given the latest block height.
+    fn pruning_target(&self, latest_block_height: u32) -> u32 {
+        match self.pruning_depth {
+            Some(pruning_depth) => std::cmp::max(
+                self.pruned_block_height(),
+                latest_block_height.saturating_add(1).saturating_sub(pruning_depth),
+            ),
+            None => self.pruned_block_height(),
+        }
+    }
+
+    /// Prunes the blocks that have fallen out of the pruning depth in batches, if the ledger is pruned.
+    fn prune_blocks(&self) -> Result<()> {
+        const INCREMENT: u32 = 100;
+
+        let target_block_height = self.pruning_target(self.latest_block_height());
+        while self.pruned_block_height() < target_block_height {
+            // Compute the end block height (exclusive) for this iteration.
+            let end_block_height = std::cmp::min(self.pruned_block_height().saturating_add(INCREMENT), target_block_height);
+
+            // Acquire the map lock to ensure the following operations aren't interrupted by a shutdown.
+            let _map_lock = self.map_lock.read();
+
+            // Prune the blocks as a single atomic batch.
+            self.atomic_batch(|| self.prune_blocks_below(end_block_height))?;
+            self.pruned_block_height.store(end_block_height, Ordering::SeqCst);
+
+            // Log the progress of the pruning procedure.
+            let progress = (end_block_height as f64 / target_block_height as f64 * 100f64) as u8;
+            debug!("Pruning the ledger up to block {} ({}%)", end_block_height, progress);
+        }
+        Ok(())
+    }
+
+    ///
+    /// Prunes the blocks from the pruned block height up to the given block height (exclusive),
+    /// discarding their transitions, and retaining a record proof for each of their commitments.
+    ///
+    /// All changes are staged in the atomic batch in progress, along with the new pruned block height.
+    ///
+    fn prune_blocks_below(&self, block_height: u32) -> Result<()> {
+        let pruning_depth = match self.pruning_depth {
+            Some(pruning_depth) => pruning_depth,
+            None => return Ok(()),
+        };
+
+        let pruned_block_height = self.pruned_block_height();
+        if block_height <= pruned_block_height {
+            return Ok(());
+        }
 
-        // Generate the record proof.
-        let record_proof = RecordProof::new(
-            block_hash,
-            previous_block_hash,
-            block_header_root,
-            block_header_inclusion_proof,
-            transactions_root,
-            transactions_inclusion_proof,
-            local_proof,
-        )?;
+        let transactions = &self.blocks.transactions;
+        for block in self.blocks.get_blocks(pruned_block_height, block_height - 1)? {
+            for transaction in block.transactions().iter() {
+                // Retain a record proof for each commitment, to serve ledger inclusion proofs.
+                for commitment in transaction.commitments() {
+                    let record_proof = Self::to_record_proof(&block, transaction, *commitment)?;
+                    transactions
+                        .record_proofs
+                        .insert(commitment, &(block.hash(), record_proof.to_bytes_le()?))?;
+                }
 
-        // Generate the ledger root inclusion proof.
-        let ledger_root = self.ledger_tree.read().root();
-        let ledger_root_inclusion_proof = self.ledger_tree.read().to_ledger_inclusion_proof(&block_hash)?;
+                // Discard the transitions.
+                for transition_id in transaction.transition_ids() {
+                    transactions.transitions.remove(&transition_id)?;
+                }
+            }
+        }
 
-        LedgerProof::new(ledger_root, ledger_root_inclusion_proof, record_proof)
+        self.pruning.insert(&(), &(pruning_depth, block_height))
     }
 
     /// Updates the latest block hashes and block headers.
@@ -978,21 +2523,14 @@ impl<N: Network> LedgerState<N> {
         Ok(())
     }
 
-    // TODO (raychu86): Make this more efficient.
     /// Updates the ledger tree.
     fn regenerate_ledger_tree(&self) -> Result<()> {
         // Acquire the ledger tree write lock.
         let mut ledger_tree = self.ledger_tree.write();
 
-        // Retrieve all of the block hashes.
-        let mut block_hashes = Vec::with_capacity(self.latest_block_height() as usize);
-        for height in 0..=self.latest_block_height() {
-            block_hashes.push(self.get_block_hash(height)?);
-        }
-
         // Add the block hashes to create the new ledger tree.
         let mut new_ledger_tree = LedgerTree::<N>::new()?;
-        new_ledger_tree.add_all(&block_hashes)?;
+        new_ledger_tree.add_all(&self.get_block_hashes(0, self.latest_block_height())?)?;
 
         // Update the current ledger tree with the current state.
         *ledger_tree = new_ledger_tree;
@@ -1000,8 +2538,163 @@ impl<N: Network> LedgerState<N> {
         Ok(())
     }
 
+    ///
+    /// Updates the ledger tree from the given block height to the latest block height.
+    ///
+    /// If the ledger tree is on the canonical chain at the given block height, only the block hashes
+    /// since the given block height are added. Otherwise, the ledger tree is regenerated.
+    ///
+    fn update_ledger_tree(&self, block_height: u32) -> Result<()> {
+        let latest_block_height = self.latest_block_height();
+        let block_hash = self.get_block_hash(std::cmp::min(block_height, latest_block_height))?;
+
+        match block_height <= latest_block_height && self.ledger_tree.read().contains_block_hash(&block_hash) {
+            true => {
+                if block_height < latest_block_height {
+                    let block_hashes = self.get_block_hashes(block_height + 1, latest_block_height)?;
+                    self.ledger_tree.write().add_all(&block_hashes)?;
+                }
+                Ok(())
+            }
+            false => self.regenerate_ledger_tree(),
+        }
+    }
+
+    /// Removes the given side chain block from storage, along with its descendants.
+    fn remove_side_chain_block(&self, block_hash: &N::BlockHash) -> Result<()> {
+        let previous_block_hash = match self.side_chain_blocks.get(block_hash)? {
+            Some(block) => block.previous_block_hash(),
+            None => return Ok(()),
+        };
+
+        // Acquire the map lock to ensure the following operations aren't interrupted by a shutdown.
+        let _map_lock = self.map_lock.read();
+
+        let mut number_of_removed_blocks = 0;
+        self.atomic_batch(|| {
+            let mut block_hashes = vec![*block_hash];
+            while let Some(block_hash) = block_hashes.pop() {
+                if let Some(children) = self.side_chain_children.get(&block_hash)? {
+                    block_hashes.extend(children.into_iter().map(|(block_hash, _, _)| block_hash));
+                    self.side_chain_children.remove(&block_hash)?;
+                }
+                self.side_chain_blocks.remove(&block_hash)?;
+                number_of_removed_blocks += 1;
+            }
+            self.update_side_chain_children(&[], &[(previous_block_hash, *block_hash)])
+        })?;
+        self.number_of_side_chain_blocks
+            .fetch_sub(number_of_removed_blocks, Ordering::SeqCst);
+
+        Ok(())
+    }
+
+    /// Removes the side chain blocks that have fallen out of the maximum fork depth of the latest block.
+    fn prune_side_chain_blocks(&self) -> Result<()> {
+        let latest_block_height = self.latest_block_height();
+        let removed_blocks = self
+            .side_chain_children
+            .iter()
+            .flat_map(|(previous_block_hash, children)| {
+                children
+                    .into_iter()
+                    .filter(|(_, block_height, _)| block_height.saturating_add(MAXIMUM_FORK_DEPTH) <= latest_block_height)
+                    .map(move |(block_hash, _, _)| (previous_block_hash, block_hash))
+            })
+            .collect::<Vec<_>>();
+        if removed_blocks.is_empty() {
+            return Ok(());
+        }
+
+        // Acquire the map lock to ensure the following operations aren't interrupted by a shutdown.
+        let _map_lock = self.map_lock.read();
+
+        self.atomic_batch(|| {
+            for (_, block_hash) in &removed_blocks {
+                self.side_chain_blocks.remove(block_hash)?;
+            }
+            self.update_side_chain_children(&[], &removed_blocks)
+        })?;
+        self.number_of_side_chain_blocks.fetch_sub(removed_blocks.len(), Ordering::SeqCst);
+
+        Ok(())
+    }
+
+    ///
+    /// Updates the children of the side chain blocks with the given added blocks, and the given removed blocks
+    /// := (previous block hash, block hash). This is called within an atomic batch, which only sees the committed
+    /// state, so the changes to each previous block are merged before they are written.
+    ///
+    fn update_side_chain_children(&self, added_blocks: &[Block<N>], removed_blocks: &[(N::BlockHash, N::BlockHash)]) -> Result<()> {
+        let mut children = HashMap::new();
+        let previous_block_hashes = added_blocks
+            .iter()
+            .map(|block| block.previous_block_hash())
+            .chain(removed_blocks.iter().map(|(previous_block_hash, _)| *previous_block_hash));
+        for previous_block_hash in previous_block_hashes {
+            if !children.contains_key(&previous_block_hash) {
+                let candidates = self.side_chain_children.get(&previous_block_hash)?.unwrap_or_default();
+                children.insert(previous_block_hash, candidates);
+            }
+        }
+
+        for (previous_block_hash, block_hash) in removed_blocks {
+            if let Some(candidates) = children.get_mut(previous_block_hash) {
+                candidates.retain(|(candidate_hash, _, _)| candidate_hash != block_hash);
+            }
+        }
+        for block in added_blocks {
+            if let Some(candidates) = children.get_mut(&block.previous_block_hash()) {
+                candidates.push((block.hash(), block.height(), block.cumulative_weight()));
+            }
+        }
+
+        for (previous_block_hash, candidates) in children {
+            match candidates.is_empty() {
+                true => self.side_chain_children.remove(&previous_block_hash)?,
+                false => self.side_chain_children.insert(&previous_block_hash, &candidates)?,
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Adds a reorg to the journal, from the last of the given reverted blocks to the given latest block.
+    ///
+    fn insert_reorg(&self, reverted_blocks: &[Block<N>], latest_block: &Block<N>) -> Result<()> {
+        let (first_block, last_block) = match (reverted_blocks.first(), reverted_blocks.last()) {
+            (Some(first_block), Some(last_block)) => (first_block, last_block),
+            _ => return Err(anyhow!("A reorg must revert at least one block")),
+        };
+
+        let reorg = Reorg {
+            timestamp: chrono::Utc::now().timestamp(),
+            old_block_height: last_block.height(),
+            old_block_hash: last_block.hash(),
+            new_block_height: latest_block.height(),
+            new_block_hash: latest_block.hash(),
+            common_ancestor: first_block.height().saturating_sub(1),
+            reverted_blocks: reverted_blocks.iter().map(RevertedBlock::new).collect(),
+        };
+
+        let index = match self.reorgs.last()? {
+            Some((index, _)) => index + 1,
+            None => 0,
+        };
+        self.reorgs.insert(&index, &reorg)
+    }
+
+    ///
     /// Initializes a heartbeat to keep the ledger reader in sync, with the given starting block height.
-    fn initialize_reader_heartbeat(self: &Arc<Self>, starting_block_height: u32) -> Result<JoinHandle<()>> {
+    ///
+    /// If the reader is subscribed to a writable ledger in the same process, it is updated as soon as
+    /// it is notified of a change. Otherwise, or once the writable ledger is closed, it polls storage.
+    ///
+    fn initialize_reader_heartbeat(
+        self: &Arc<Self>,
+        starting_block_height: u32,
+        mut notifications: Option<Receiver<LedgerNotification>>,
+    ) -> Result<JoinHandle<()>> {
         // If the storage is *not* in read-only mode, this method cannot be called.
         if !self.is_read_only() {
             return Err(anyhow!("Ledger must be read-only to initialize a reader heartbeat"));
@@ -1013,10 +2706,18 @@ impl<N: Network> LedgerState<N> {
             ledger.read_only.1.store(starting_block_height, Ordering::SeqCst);
 
             loop {
+                // Acquire the map lock to ensure the reader is not updated during a snapshot export.
+                let map_lock = ledger.map_lock.read();
+
                 // Refresh the ledger storage state.
                 if ledger.ledger_roots.refresh() {
+                    // Update the pruned block height, as the writer may have pruned more blocks.
+                    if let Ok(Some((_, pruned_block_height))) = ledger.pruning.get(&()) {
+                        ledger.pruned_block_height.store(pruned_block_height, Ordering::SeqCst);
+                    }
+
                     // After catching up the reader, determine the latest block height.
-                    if let Some(latest_block_height) = ledger.blocks.block_heights.keys().max() {
+                    if let Ok(Some(latest_block_height)) = ledger.blocks.latest_block_height() {
                         let current_block_height = last_seen_block_height.load(Ordering::SeqCst);
                         trace!(
                             "[Read-Only] Updating ledger state from block {} to {}",
@@ -1029,8 +2730,8 @@ impl<N: Network> LedgerState<N> {
                             Ok(block) => *ledger.latest_block.write() = block,
                             Err(error) => warn!("[Read-Only] {}", error),
                         };
-                        // Regenerate the ledger tree.
-                        if let Err(error) = ledger.regenerate_ledger_tree() {
+                        // Update the ledger tree with the blocks since the last seen block height.
+                        if let Err(error) = ledger.update_ledger_tree(current_block_height) {
                             warn!("[Read-Only] {}", error);
                         };
                         // Regenerate the latest ledger state.
@@ -1041,11 +2742,163 @@ impl<N: Network> LedgerState<N> {
                         last_seen_block_height.store(latest_block_height, Ordering::SeqCst);
                     }
                 }
+
+                drop(map_lock);
+
+                // Wait for the next change from the writable ledger, if subscribed to one.
+                if let Some(receiver) = &notifications {
+                    match receiver.recv() {
+                        // Coalesce the pending notifications, as a single refresh catches up to all of them.
+                        Ok(_) => {
+                            receiver.try_iter().for_each(drop);
+                            continue;
+                        }
+                        // The writable ledger has been closed, so fall back to polling storage.
+                        Err(_) => notifications = None,
+                    }
+                }
                 thread::sleep(std::time::Duration::from_secs(6));
             }
         }))
     }
 
+    ///
+    /// Checks that the given block extends the given previous block, with the given ledger root
+    /// as the latest ledger root, and that it does not conflict with the ledger or the staged blocks.
+    ///
+    /// The given reverted blocks are canonical blocks that are reverted along with the addition
+    /// of the block, and are not considered to be in the ledger.
+    ///
+    fn check_next_block(
+        &self,
+        block: &Block<N>,
+        previous_block: &Block<N>,
+        latest_ledger_root: N::LedgerRoot,
+        staged: &StagedBlocks<N>,
+        reverted: &StagedBlocks<N>,
+    ) -> Result<()> {
+        // Ensure the block height increments by one.
+        let block_height = block.height();
+        if block_height != previous_block.height() + 1 {
+            return Err(anyhow!(
+                "Block {} should have block height {}",
+                block_height,
+                previous_block.height() + 1
+            ));
+        }
+
+        // Ensure the previous block hash matches.
+        if block.previous_block_hash() != previous_block.hash() {
+            return Err(anyhow!(
+                "Block {} has an incorrect previous block hash in the canon chain",
+                block_height
+            ));
+        }
+
+        // Ensure the next block timestamp is within the declared time limit.
+        let now = chrono::Utc::now().timestamp();
+        if block.timestamp() > (now + MAXIMUM_FUTURE_BLOCK_TIME) {
+            return Err(anyhow!("The given block timestamp exceeds the time limit"));
+        }
+
+        // Ensure the next block timestamp is after the current block timestamp.
+        if block.timestamp() <= previous_block.timestamp() {
+            return Err(anyhow!("The given block timestamp is before the current timestamp"));
+        }
+
+        // Compute the expected difficulty target.
+        let expected_difficulty_target =
+            Blocks::<N>::compute_difficulty_target(previous_block.timestamp(), previous_block.difficulty_target(), block.timestamp());
+
+        // Ensure the expected difficulty target is met.
+        if block.difficulty_target() != expected_difficulty_target {
+            return Err(anyhow!(
+                "Block {} has an incorrect difficulty target. Found {}, but expected {}",
+                block_height,
+                block.difficulty_target(),
+                expected_difficulty_target
+            ));
+        }
+
+        // Ensure the expected cumulative weight is computed correctly.
+        let expected_cumulative_weight = previous_block
+            .cumulative_weight()
+            .saturating_add((u64::MAX / expected_difficulty_target) as u128);
+        if block.cumulative_weight() != expected_cumulative_weight {
+            return Err(anyhow!(
+                "The given cumulative weight is incorrect. Found {}, but expected {}",
+                block.cumulative_weight(),
+                expected_cumulative_weight
+            ));
+        }
+
+        // Ensure the block height does not already exist.
+        if !reverted.block_heights.contains(&block_height) && self.contains_block_height(block_height)? {
+            return Err(anyhow!("Block {} already exists in the canon chain", block_height));
+        }
+
+        // Ensure the block hash does not already exist.
+        if staged.block_hashes.contains(&block.hash())
+            || (!reverted.block_hashes.contains(&block.hash()) && self.contains_block_hash(&block.hash())?)
+        {
+            return Err(anyhow!("Block {} has a repeat block hash in the canon chain", block_height));
+        }
+
+        // Ensure the ledger root in the block matches the current ledger root.
+        if block.previous_ledger_root() != latest_ledger_root {
+            return Err(anyhow!("Block {} declares an incorrect ledger root", block_height));
+        }
+
+        // Ensure the canon chain does not already contain the given serial numbers.
+        for serial_number in block.serial_numbers() {
+            if staged.serial_numbers.contains(serial_number)
+                || (!reverted.serial_numbers.contains(serial_number) && self.contains_serial_number(serial_number)?)
+            {
+                return Err(anyhow!("Serial number {} already exists in the ledger", serial_number));
+            }
+        }
+
+        // Ensure the canon chain does not already contain the given commitments.
+        for commitment in block.commitments() {
+            if staged.commitments.contains(commitment)
+                || (!reverted.commitments.contains(commitment) && self.contains_commitment(commitment)?)
+            {
+                return Err(anyhow!("Commitment {} already exists in the ledger", commitment));
+            }
+        }
+
+        // Ensure each transaction in the given block is new to the canon chain.
+        for transaction in block.transactions().iter() {
+            // Ensure the transactions in the given block do not already exist.
+            let transaction_id = transaction.transaction_id();
+            if staged.transaction_ids.contains(&transaction_id)
+                || (!reverted.transaction_ids.contains(&transaction_id) && self.contains_transaction(&transaction_id)?)
+            {
+                return Err(anyhow!(
+                    "Transaction {} in block {} has a duplicate transaction in the ledger",
+                    transaction.transaction_id(),
+                    block_height
+                ));
+            }
+
+            // Ensure the transaction in the block references a valid past or current ledger root.
+            let ledger_root = transaction.ledger_root();
+            if ledger_root != latest_ledger_root
+                && !staged.ledger_roots.contains(&ledger_root)
+                && (reverted.ledger_roots.contains(&ledger_root) || !self.contains_ledger_root(&ledger_root)?)
+            {
+                return Err(anyhow!(
+                    "Transaction {} in block {} references non-existent ledger root {}",
+                    transaction.transaction_id(),
+                    block_height,
+                    &transaction.ledger_root()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Attempts to automatically resolve inconsistent ledger state.
     fn try_fixing_inconsistent_state(&self) -> Result<u32> {
         // If the storage is in read-only mode, this method cannot be called.
@@ -1054,7 +2907,7 @@ impl<N: Network> LedgerState<N> {
         }
 
         // Determine the latest block height.
-        match (self.ledger_roots.values().max(), self.blocks.block_heights.keys().max()) {
+        match (self.ledger_roots.values().max(), self.blocks.latest_block_height()?) {
             (Some(latest_block_height_0), Some(latest_block_height_1)) => match latest_block_height_0 == latest_block_height_1 {
                 true => Ok(latest_block_height_0),
                 false => {
@@ -1109,14 +2962,51 @@ impl<N: Network> LedgerState<N> {
     }
 
     /// Gracefully shuts down the ledger state.
-    // FIXME: currently only obtains the lock that is used to ensure that map operations
-    // can't be interrupted by a shutdown; the real solution is to use batch writes in
-    // rocksdb.
+    /// Storage writes for a block are atomic; this lock ensures the in-memory ledger state
+    /// is not left partially updated by a shutdown.
     pub fn shut_down(&self) -> Arc<RwLock<()>> {
         self.map_lock.clone()
     }
 }
 
+///
+/// The blocks that are checked, but not yet committed, in a bulk import or a side chain switch.
+///
+struct StagedBlocks<N: Network> {
+    block_heights: HashSet<u32>,
+    block_hashes: HashSet<N::BlockHash>,
+    serial_numbers: HashSet<N::SerialNumber>,
+    commitments: HashSet<N::Commitment>,
+    transaction_ids: HashSet<N::TransactionID>,
+    ledger_roots: HashSet<N::LedgerRoot>,
+}
+
+impl<N: Network> Default for StagedBlocks<N> {
+    fn default() -> Self {
+        Self {
+            block_heights: Default::default(),
+            block_hashes: Default::default(),
+            serial_numbers: Default::default(),
+            commitments: Default::default(),
+            transaction_ids: Default::default(),
+            ledger_roots: Default::default(),
+        }
+    }
+}
+
+impl<N: Network> StagedBlocks<N> {
+    /// Adds the given block to the staged blocks.
+    fn add(&mut self, block: &Block<N>) {
+        self.block_heights.insert(block.height());
+        self.block_hashes.insert(block.hash());
+        self.serial_numbers.extend(block.serial_numbers().copied());
+        self.commitments.extend(block.commitments().copied());
+        self.transaction_ids
+            .extend(block.transactions().iter().map(|transaction| transaction.transaction_id()));
+        self.ledger_roots.insert(block.previous_ledger_root());
+    }
+}
+
 #[derive(Clone, Debug)]
 struct BlockState<N: Network> {
     block_heights: DataMap<u32, N::BlockHash>,
@@ -1136,6 +3026,11 @@ impl<N: Network> BlockState<N> {
         })
     }
 
+    /// Returns the latest block height in storage, if storage is not empty.
+    fn latest_block_height(&self) -> Result<Option<u32>> {
+        Ok(self.block_heights.last()?.map(|(block_height, _)| block_height))
+    }
+
     /// Returns `true` if the given block height exists in storage.
     fn contains_block_height(&self, block_height: u32) -> Result<bool> {
         self.block_heights.contains_key(&block_height)
@@ -1206,10 +3101,23 @@ impl<N: Network> BlockState<N> {
             return Err(anyhow!("Invalid starting and ending block heights"));
         }
 
-        (start_block_height..=end_block_height)
-            .into_iter()
-            .map(|height| self.get_block_hash(height))
-            .collect()
+        // Retrieve the block hashes with a single range scan of the block heights map.
+        let block_hashes = self
+            .block_heights
+            .range(start_block_height..=end_block_height)?
+            .map(|(_, block_hash)| block_hash)
+            .collect::<Vec<_>>();
+
+        // Ensure every block in the range exists.
+        let num_blocks = (end_block_height - start_block_height) as usize + 1;
+        match block_hashes.len() == num_blocks {
+            true => Ok(block_hashes),
+            false => Err(anyhow!(
+                "Blocks {} to {} are missing from block heights map",
+                start_block_height,
+                end_block_height
+            )),
+        }
     }
 
     /// Returns the previous block hash for the given block height.
@@ -1228,23 +3136,15 @@ impl<N: Network> BlockState<N> {
         // Retrieve the block hash.
         let block_hash = self.get_block_hash(block_height)?;
 
-        match self.block_headers.get(&block_hash)? {
-            Some(block_header) => Ok(block_header),
-            None => return Err(anyhow!("Block {} missing from block headers map", block_hash)),
-        }
+        self.get_block_header_by_hash(&block_hash)
     }
 
-    /// Returns the block headers from the given `start_block_height` to `end_block_height` (inclusive).
-    fn get_block_headers(&self, start_block_height: u32, end_block_height: u32) -> Result<Vec<BlockHeader<N>>> {
-        // Ensure the starting block height is less than the ending block height.
-        if start_block_height > end_block_height {
-            return Err(anyhow!("Invalid starting and ending block heights"));
+    /// Returns the block header for the given block hash.
+    fn get_block_header_by_hash(&self, block_hash: &N::BlockHash) -> Result<BlockHeader<N>> {
+        match self.block_headers.get(block_hash)? {
+            Some(block_header) => Ok(block_header),
+            None => return Err(anyhow!("Block {} missing from block headers map", block_hash)),
         }
-
-        (start_block_height..=end_block_height)
-            .into_par_iter()
-            .map(|height| self.get_block_header(height))
-            .collect()
     }
 
     /// Returns the number of all block headers belonging to canonical blocks.
@@ -1261,8 +3161,13 @@ impl<N: Network> BlockState<N> {
         // Retrieve the block hash.
         let block_hash = self.get_block_hash(block_height)?;
 
+        self.get_block_transactions_by_hash(&block_hash)
+    }
+
+    /// Returns the transactions from the block of the given block hash.
+    fn get_block_transactions_by_hash(&self, block_hash: &N::BlockHash) -> Result<Transactions<N>> {
         // Retrieve the block transaction IDs.
-        let transaction_ids = match self.block_transactions.get(&block_hash)? {
+        let transaction_ids = match self.block_transactions.get(block_hash)? {
             Some(transaction_ids) => transaction_ids,
             None => return Err(anyhow!("Block {} missing from block transactions map", block_hash)),
         };
@@ -1298,9 +3203,23 @@ impl<N: Network> BlockState<N> {
             return Err(anyhow!("Invalid starting and ending block heights"));
         }
 
-        (start_block_height..=end_block_height)
-            .into_par_iter()
-            .map(|height| self.get_block(height))
+        // Retrieve the block hashes, and pair each block hash with its previous block hash.
+        let block_hashes = self.get_block_hashes(start_block_height, end_block_height)?;
+        let previous_block_hashes = std::iter::once(self.get_previous_block_hash(start_block_height)?)
+            .chain(block_hashes.iter().copied())
+            .collect::<Vec<_>>();
+
+        block_hashes
+            .par_iter()
+            .zip(previous_block_hashes.par_iter())
+            .map(|(block_hash, previous_block_hash)| {
+                // Retrieve the block header.
+                let block_header = self.get_block_header_by_hash(block_hash)?;
+                // Retrieve the block transactions.
+                let transactions = self.get_block_transactions_by_hash(block_hash)?;
+
+                Ok(Block::from(*previous_block_hash, block_header, transactions)?)
+            })
             .collect()
     }
 
@@ -1319,25 +3238,37 @@ impl<N: Network> BlockState<N> {
         if self.block_heights.contains_key(&block_height)? {
             Err(anyhow!("Block {} already exists in storage", block_height))
         } else {
-            let block_hash = block.hash();
-            let block_header = block.header();
-            let transactions = block.transactions();
-            let transaction_ids = transactions.transaction_ids().collect::<Vec<_>>();
-
-            // Insert the block height.
-            self.block_heights.insert(&block_height, &block_hash)?;
-            // Insert the block header.
-            self.block_headers.insert(&block_hash, block_header)?;
-            // Insert the block transactions.
-            self.block_transactions.insert(&block_hash, &transaction_ids)?;
-            // Insert the transactions.
-            for (index, transaction) in transactions.iter().enumerate() {
-                let metadata = Metadata::<N>::new(block_height, block_hash, block.timestamp(), index as u16);
-                self.transactions.add_transaction(transaction, metadata)?;
+            // Ensure the transactions do not exist.
+            for transaction_id in block.transactions().transaction_ids() {
+                if self.transactions.contains_transaction(&transaction_id)? {
+                    return Err(anyhow!("Transaction {} already exists in storage", transaction_id));
+                }
             }
+            self.insert_block(block)
+        }
+    }
 
-            Ok(())
+    /// Inserts the given block into storage, without checking that it does not exist.
+    fn insert_block(&self, block: &Block<N>) -> Result<()> {
+        let block_height = block.height();
+        let block_hash = block.hash();
+        let block_header = block.header();
+        let transactions = block.transactions();
+        let transaction_ids = transactions.transaction_ids().collect::<Vec<_>>();
+
+        // Insert the block height.
+        self.block_heights.insert(&block_height, &block_hash)?;
+        // Insert the block header.
+        self.block_headers.insert(&block_hash, block_header)?;
+        // Insert the block transactions.
+        self.block_transactions.insert(&block_hash, &transaction_ids)?;
+        // Insert the transactions.
+        for (index, transaction) in transactions.iter().enumerate() {
+            let metadata = Metadata::<N>::new(block_height, block_hash, block.timestamp(), index as u16);
+            self.transactions.insert_transaction(transaction, metadata)?;
         }
+
+        Ok(())
     }
 
     /// Removes the given block height from storage.
@@ -1391,6 +3322,8 @@ struct TransactionState<N: Network> {
     transitions: DataMap<N::TransitionID, (N::TransactionID, u8, Transition<N>)>,
     serial_numbers: DataMap<N::SerialNumber, N::TransitionID>,
     commitments: DataMap<N::Commitment, N::TransitionID>,
+    /// The record proof of each commitment in a pruned block := (block hash, serialized record proof).
+    record_proofs: DataMap<N::Commitment, (N::BlockHash, Vec<u8>)>,
 }
 
 impl<N: Network> TransactionState<N> {
@@ -1401,6 +3334,7 @@ impl<N: Network> TransactionState<N> {
             transitions: storage.open_map(MapId::Transitions)?,
             serial_numbers: storage.open_map(MapId::SerialNumbers)?,
             commitments: storage.open_map(MapId::Commitments)?,
+            record_proofs: storage.open_map(MapId::RecordProofs)?,
         })
     }
 
@@ -1480,39 +3414,34 @@ impl<N: Network> TransactionState<N> {
         }
     }
 
-    /// Adds the given transaction to storage.
-    fn add_transaction(&self, transaction: &Transaction<N>, metadata: Metadata<N>) -> Result<()> {
-        // Ensure the transaction does not exist.
+    /// Inserts the given transaction into storage, without checking that it does not exist.
+    fn insert_transaction(&self, transaction: &Transaction<N>, metadata: Metadata<N>) -> Result<()> {
         let transaction_id = transaction.transaction_id();
-        if self.transactions.contains_key(&transaction_id)? {
-            Err(anyhow!("Transaction {} already exists in storage", transaction_id))
-        } else {
-            let transition_ids = transaction.transition_ids().collect();
-            let transitions = transaction.transitions();
-            let ledger_root = transaction.ledger_root();
+        let transition_ids = transaction.transition_ids().collect();
+        let transitions = transaction.transitions();
+        let ledger_root = transaction.ledger_root();
 
-            // Insert the transaction ID.
-            self.transactions
-                .insert(&transaction_id, &(ledger_root, transition_ids, metadata))?;
+        // Insert the transaction ID.
+        self.transactions
+            .insert(&transaction_id, &(ledger_root, transition_ids, metadata))?;
 
-            for (i, transition) in transitions.iter().enumerate() {
-                let transition_id = transition.transition_id();
+        for (i, transition) in transitions.iter().enumerate() {
+            let transition_id = transition.transition_id();
 
-                // Insert the transition.
-                self.transitions
-                    .insert(&transition_id, &(transaction_id, i as u8, transition.clone()))?;
+            // Insert the transition.
+            self.transitions
+                .insert(&transition_id, &(transaction_id, i as u8, transition.clone()))?;
 
-                // Insert the serial numbers.
-                for serial_number in transition.serial_numbers() {
-                    self.serial_numbers.insert(serial_number, &transition_id)?;
-                }
-                // Insert the commitments.
-                for commitment in transition.commitments() {
-                    self.commitments.insert(commitment, &transition_id)?;
-                }
+            // Insert the serial numbers.
+            for serial_number in transition.serial_numbers() {
+                self.serial_numbers.insert(serial_number, &transition_id)?;
+            }
+            // Insert the commitments.
+            for commitment in transition.commitments() {
+                self.commitments.insert(commitment, &transition_id)?;
             }
-            Ok(())
         }
+        Ok(())
     }
 
     /// Removes the given transaction ID from storage.
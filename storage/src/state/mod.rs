@@ -20,5 +20,7 @@ pub use ledger::{LedgerState, Metadata, MAXIMUM_BLOCK_LOCATORS, MAXIMUM_LINEAR_B
 pub(crate) mod prover;
 pub use prover::ProverState;
 
+pub(crate) mod schema;
+
 #[cfg(test)]
 mod tests;
@@ -14,7 +14,10 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::storage::{DataMap, Map, MapId, Storage};
+use crate::{
+    state::schema::check_schema_version,
+    storage::{DataMap, Map, MapId, Storage},
+};
 use snarkvm::dpc::prelude::*;
 
 use anyhow::{anyhow, Result};
@@ -24,6 +27,8 @@ use std::path::Path;
 pub struct ProverState<N: Network> {
     /// The coinbase records of the prover in storage.
     coinbase: CoinbaseState<N>,
+    /// The unconfirmed transactions of the prover in storage.
+    memory_pool: MemoryPoolState<N>,
 }
 
 impl<N: Network> ProverState<N> {
@@ -36,9 +41,13 @@ impl<N: Network> ProverState<N> {
         let is_read_only = false;
         let storage = S::open(path, context, is_read_only)?;
 
+        // Ensure the storage is at the current schema version.
+        check_schema_version::<N, S>(&storage, is_read_only)?;
+
         // Initialize the prover.
         let prover = Self {
-            coinbase: CoinbaseState::open(storage)?,
+            coinbase: CoinbaseState::open(&storage)?,
+            memory_pool: MemoryPoolState::open(&storage)?,
         };
 
         // let value = storage.export()?;
@@ -74,6 +83,26 @@ impl<N: Network> ProverState<N> {
     pub fn remove_coinbase_record(&self, commitment: &N::Commitment) -> Result<()> {
         self.coinbase.remove_record(commitment)
     }
+
+    /// Returns all unconfirmed transactions in storage.
+    pub fn to_unconfirmed_transactions(&self) -> Vec<Transaction<N>> {
+        self.memory_pool.to_transactions()
+    }
+
+    /// Adds the given unconfirmed transaction to storage.
+    pub fn add_unconfirmed_transaction(&self, transaction: &Transaction<N>) -> Result<()> {
+        self.memory_pool.add_transaction(transaction)
+    }
+
+    /// Removes the given unconfirmed transaction from storage.
+    pub fn remove_unconfirmed_transaction(&self, transaction_id: &N::TransactionID) -> Result<()> {
+        self.memory_pool.remove_transaction(transaction_id)
+    }
+
+    /// Creates a checkpoint of the prover storage at the given path.
+    pub(crate) fn create_checkpoint<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.coinbase.records.create_checkpoint(path)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -84,7 +113,7 @@ struct CoinbaseState<N: Network> {
 
 impl<N: Network> CoinbaseState<N> {
     /// Initializes a new instance of `CoinbaseState`.
-    fn open<S: Storage>(storage: S) -> Result<Self> {
+    fn open<S: Storage>(storage: &S) -> Result<Self> {
         Ok(Self {
             records: storage.open_map(MapId::Records)?,
         })
@@ -128,3 +157,32 @@ impl<N: Network> CoinbaseState<N> {
         Ok(())
     }
 }
+
+#[derive(Clone, Debug)]
+struct MemoryPoolState<N: Network> {
+    transactions: DataMap<N::TransactionID, Transaction<N>>,
+}
+
+impl<N: Network> MemoryPoolState<N> {
+    /// Initializes a new instance of `MemoryPoolState`.
+    fn open<S: Storage>(storage: &S) -> Result<Self> {
+        Ok(Self {
+            transactions: storage.open_map(MapId::MemoryPool)?,
+        })
+    }
+
+    /// Returns all transactions in storage.
+    fn to_transactions(&self) -> Vec<Transaction<N>> {
+        self.transactions.values().collect()
+    }
+
+    /// Adds the given transaction to storage.
+    fn add_transaction(&self, transaction: &Transaction<N>) -> Result<()> {
+        self.transactions.insert(&transaction.transaction_id(), transaction)
+    }
+
+    /// Removes the given transaction from storage.
+    fn remove_transaction(&self, transaction_id: &N::TransactionID) -> Result<()> {
+        self.transactions.remove(transaction_id)
+    }
+}
@@ -0,0 +1,87 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::storage::{DataMap, KeyEncoding, Map, MapId, Storage, SCHEMA_VERSION};
+use snarkvm::dpc::Network;
+
+use anyhow::{anyhow, Result};
+use std::cmp::Ordering;
+
+///
+/// Ensures the given storage is at the current schema version.
+///
+/// A writer migrates older storage in place, one version at a time, with each migration
+/// written as a single atomic batch together with its new schema version. Storage without a
+/// schema version record is either new, or predates schema versioning, and is at version 0.
+/// Storage with a newer schema version than this build supports is never opened.
+///
+pub(crate) fn check_schema_version<N: Network, S: Storage>(storage: &S, is_read_only: bool) -> Result<()> {
+    let schema_version: DataMap<(), u32> = storage.open_map(MapId::SchemaVersion)?;
+    let version = schema_version.get(&())?.unwrap_or(0);
+
+    match version.cmp(&SCHEMA_VERSION) {
+        Ordering::Equal => Ok(()),
+        Ordering::Greater => Err(anyhow!(
+            "Storage is at schema version {}, which is newer than the supported version {}. Please update snarkOS",
+            version,
+            SCHEMA_VERSION
+        )),
+        Ordering::Less if is_read_only => Err(anyhow!(
+            "Storage is at schema version {}, and must be migrated to version {} by a writer",
+            version,
+            SCHEMA_VERSION
+        )),
+        Ordering::Less => {
+            for from_version in version..SCHEMA_VERSION {
+                info!("Migrating storage from schema version {} to {}", from_version, from_version + 1);
+
+                schema_version.start_atomic()?;
+                let result = migrate::<N, S>(storage, from_version).and_then(|_| schema_version.insert(&(), &(from_version + 1)));
+                match result {
+                    Ok(()) => schema_version.finish_atomic()?,
+                    Err(error) => {
+                        schema_version.abort_atomic();
+                        return Err(anyhow!("Failed to migrate storage from schema version {}: {}", from_version, error));
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+///
+/// Migrates the given storage from the given schema version to the next one.
+/// All changes are staged in the atomic batch that is in progress.
+///
+/// To change the layout of a map, increment `SCHEMA_VERSION` and add a migration from the
+/// previous version here, using `DataMap::migrate_into` to rewrite the entries of the map.
+///
+fn migrate<N: Network, S: Storage>(storage: &S, from_version: u32) -> Result<()> {
+    match from_version {
+        // Storage that predates schema versioning has the same layout as version 1.
+        0 => Ok(()),
+        // Version 2 stores the keys of the block heights map in big-endian order.
+        1 => {
+            let block_heights: DataMap<u32, N::BlockHash> = storage.open_map(MapId::BlockHeights)?;
+            block_heights
+                .with_key_encoding(KeyEncoding::LittleEndian)
+                .migrate_into(&block_heights, |block_height, block_hash| Ok((block_height, block_hash)))?;
+            Ok(())
+        }
+        _ => Err(anyhow!("There is no migration from schema version {}", from_version)),
+    }
+}
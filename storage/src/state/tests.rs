@@ -15,27 +15,34 @@
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
-    storage::{rocksdb::RocksDB, Storage},
+    helpers::{DiscrepancyKind, RepairAction, RepairTarget, RevertedBlock},
+    state::schema::check_schema_version,
+    storage::{memory::MemoryDB, DataMap, KeyEncoding, Map, MapId, Storage, SCHEMA_VERSION},
     LedgerState,
+    ProverState,
 };
 use snarkvm::dpc::{prelude::*, testnet2::Testnet2};
 
 use rand::thread_rng;
-use std::sync::atomic::AtomicBool;
+use std::{
+    sync::atomic::AtomicBool,
+    time::{Duration, Instant},
+};
 
-fn temp_dir() -> std::path::PathBuf {
-    tempfile::tempdir().expect("Failed to open temporary directory").into_path()
+/// Returns a unique storage path, which is never created on the filesystem.
+fn temp_path() -> std::path::PathBuf {
+    format!("snarkos-test-ledger-{}", rand::random::<u64>()).into()
 }
 
 /// Initializes a new instance of the ledger.
 fn create_new_ledger<N: Network, S: Storage>() -> LedgerState<N> {
-    LedgerState::open_writer::<S, _>(temp_dir()).expect("Failed to initialize ledger")
+    LedgerState::open_writer::<S, _>(temp_path()).expect("Failed to initialize ledger")
 }
 
 #[test]
 fn test_genesis() {
     // Initialize a new ledger.
-    let ledger = create_new_ledger::<Testnet2, RocksDB>();
+    let ledger = create_new_ledger::<Testnet2, MemoryDB>();
 
     // Retrieve the genesis block.
     let genesis = Testnet2::genesis_block();
@@ -61,7 +68,7 @@ fn test_add_next_block() {
     let terminator = AtomicBool::new(false);
 
     // Initialize a new ledger.
-    let ledger = create_new_ledger::<Testnet2, RocksDB>();
+    let ledger = create_new_ledger::<Testnet2, MemoryDB>();
     assert_eq!(0, ledger.latest_block_height());
 
     // Initialize a new ledger tree.
@@ -103,13 +110,63 @@ fn test_add_next_block() {
     assert_eq!(Some(&(genesis.hash(), None)), block_locators.get(&genesis.height()));
 }
 
+#[test]
+fn test_add_next_blocks() {
+    let rng = &mut thread_rng();
+    let terminator = AtomicBool::new(false);
+
+    // Initialize a new account.
+    let account = Account::<Testnet2>::new(&mut thread_rng());
+    let address = account.address();
+
+    // Mine three blocks on a ledger.
+    let ledger = create_new_ledger::<Testnet2, MemoryDB>();
+    let mut blocks = Vec::new();
+    for _ in 0..3 {
+        let (block, _) = ledger
+            .mine_next_block(address, true, &[], &terminator, rng)
+            .expect("Failed to mine");
+        ledger.add_next_block(&block).expect("Failed to add next block to ledger");
+        blocks.push(block);
+    }
+
+    // Initialize a second ledger.
+    let bulk_ledger = create_new_ledger::<Testnet2, MemoryDB>();
+
+    // Ensure a non-contiguous run of blocks is rejected, and no blocks are added.
+    assert!(bulk_ledger.add_next_blocks(&[blocks[0].clone(), blocks[2].clone()]).is_err());
+    assert!(bulk_ledger.add_next_blocks(&blocks[1..]).is_err());
+    assert_eq!(0, bulk_ledger.latest_block_height());
+
+    // Add the blocks in bulk.
+    bulk_ledger.add_next_blocks(&[]).expect("Failed to add no blocks to ledger");
+    bulk_ledger.add_next_blocks(&blocks).expect("Failed to add next blocks to ledger");
+
+    // Ensure the ledger matches the ledger that added the blocks one at a time.
+    assert_eq!(3, bulk_ledger.latest_block_height());
+    assert_eq!(ledger.latest_block_hash(), bulk_ledger.latest_block_hash());
+    assert_eq!(ledger.latest_ledger_root(), bulk_ledger.latest_ledger_root());
+    assert_eq!(ledger.latest_block_locators(), bulk_ledger.latest_block_locators());
+    assert_eq!(blocks, bulk_ledger.get_blocks(1, 3).expect("Failed to get blocks"));
+
+    // Ensure the same blocks cannot be added again.
+    assert!(bulk_ledger.add_next_blocks(&blocks).is_err());
+
+    // Ensure the next block is added on top of the bulk import.
+    let (block, _) = bulk_ledger
+        .mine_next_block(address, true, &[], &terminator, rng)
+        .expect("Failed to mine");
+    bulk_ledger.add_next_block(&block).expect("Failed to add next block to ledger");
+    assert_eq!(4, bulk_ledger.latest_block_height());
+}
+
 #[test]
 fn test_remove_last_block() {
     let rng = &mut thread_rng();
     let terminator = AtomicBool::new(false);
 
     // Initialize a new ledger.
-    let ledger = create_new_ledger::<Testnet2, RocksDB>();
+    let ledger = create_new_ledger::<Testnet2, MemoryDB>();
     assert_eq!(0, ledger.latest_block_height());
 
     // Initialize a new ledger tree.
@@ -155,7 +212,7 @@ fn test_remove_last_2_blocks() {
     let terminator = AtomicBool::new(false);
 
     // Initialize a new ledger.
-    let ledger = create_new_ledger::<Testnet2, RocksDB>();
+    let ledger = create_new_ledger::<Testnet2, MemoryDB>();
     assert_eq!(0, ledger.latest_block_height());
 
     // Initialize a new ledger tree.
@@ -202,13 +259,213 @@ fn test_remove_last_2_blocks() {
     assert_eq!(ledger_tree.root(), ledger.latest_ledger_root());
 }
 
+#[test]
+fn test_reorg_journal() {
+    let rng = &mut thread_rng();
+    let terminator = AtomicBool::new(false);
+
+    // Initialize a new ledger.
+    let path = temp_path();
+    let ledger = LedgerState::<Testnet2>::open_writer::<MemoryDB, _>(&path).expect("Failed to initialize ledger");
+    assert!(ledger.get_reorgs().expect("Failed to get reorgs").is_empty());
+
+    // Initialize a new account.
+    let account = Account::<Testnet2>::new(&mut thread_rng());
+    let address = account.address();
+
+    // Mine the next two blocks.
+    let (block_1, _) = ledger
+        .mine_next_block(address, true, &[], &terminator, rng)
+        .expect("Failed to mine");
+    ledger.add_next_block(&block_1).expect("Failed to add next block to ledger");
+    let (block_2, _) = ledger
+        .mine_next_block(address, true, &[], &terminator, rng)
+        .expect("Failed to mine");
+    ledger.add_next_block(&block_2).expect("Failed to add next block to ledger");
+
+    // Revert the ledger by one block, and ensure the reorg is not recorded until it is applied.
+    let reverted_blocks = ledger.revert_to_block_height(1).expect("Failed to revert");
+    assert!(ledger.get_reorgs().expect("Failed to get reorgs").is_empty());
+
+    // Mine a different block at the same height, and record the reorg.
+    let (block_2b, _) = ledger
+        .mine_next_block(address, true, &[], &terminator, rng)
+        .expect("Failed to mine");
+    ledger.add_next_block(&block_2b).expect("Failed to add next block to ledger");
+    ledger.add_reorg(&reverted_blocks).expect("Failed to add reorg");
+
+    // Revert the ledger by two blocks, and record the reorg.
+    let reverted_blocks = ledger.revert_to_block_height(0).expect("Failed to revert");
+    ledger.add_reorg(&reverted_blocks).expect("Failed to add reorg");
+    assert!(ledger.add_reorg(&[]).is_err());
+    drop(ledger);
+
+    // Ensure the reorgs are recorded in the order they occurred, and are retained across restarts.
+    let ledger = LedgerState::<Testnet2>::open_writer::<MemoryDB, _>(&path).expect("Failed to open ledger");
+    let reorgs = ledger.get_reorgs().expect("Failed to get reorgs");
+    assert_eq!(2, reorgs.len());
+
+    assert_eq!((2, block_2.hash()), (reorgs[0].old_block_height, reorgs[0].old_block_hash));
+    assert_eq!((2, block_2b.hash()), (reorgs[0].new_block_height, reorgs[0].new_block_hash));
+    assert_eq!(1, reorgs[0].common_ancestor);
+    assert_eq!(vec![RevertedBlock::new(&block_2)], reorgs[0].reverted_blocks);
+    assert_eq!(
+        block_2.transactions().transaction_ids().collect::<Vec<_>>(),
+        reorgs[0].reverted_blocks[0].transaction_ids
+    );
+
+    let genesis = Testnet2::genesis_block();
+    assert_eq!((2, block_2b.hash()), (reorgs[1].old_block_height, reorgs[1].old_block_hash));
+    assert_eq!((0, genesis.hash()), (reorgs[1].new_block_height, reorgs[1].new_block_hash));
+    assert_eq!(0, reorgs[1].common_ancestor);
+    assert_eq!(vec![RevertedBlock::new(&block_1), RevertedBlock::new(&block_2b)], reorgs[1].reverted_blocks);
+    assert!(reorgs[0].timestamp <= reorgs[1].timestamp);
+}
+
+#[test]
+fn test_side_chains() {
+    let rng = &mut thread_rng();
+    let terminator = AtomicBool::new(false);
+
+    // Initialize two new ledgers, to mine competing chains.
+    let path = temp_path();
+    let ledger = LedgerState::<Testnet2>::open_writer::<MemoryDB, _>(&path).expect("Failed to initialize ledger");
+    let other_ledger = create_new_ledger::<Testnet2, MemoryDB>();
+
+    // Initialize a new account.
+    let account = Account::<Testnet2>::new(&mut thread_rng());
+    let address = account.address();
+
+    // Mine one block on the first ledger, and two blocks on the second ledger.
+    let (block_1, _) = ledger
+        .mine_next_block(address, true, &[], &terminator, rng)
+        .expect("Failed to mine");
+    ledger.add_next_block(&block_1).expect("Failed to add next block to ledger");
+    let (fork_block_1, _) = other_ledger
+        .mine_next_block(address, true, &[], &terminator, rng)
+        .expect("Failed to mine");
+    other_ledger.add_next_block(&fork_block_1).expect("Failed to add next block to ledger");
+    let (fork_block_2, _) = other_ledger
+        .mine_next_block(address, true, &[], &terminator, rng)
+        .expect("Failed to mine");
+    other_ledger.add_next_block(&fork_block_2).expect("Failed to add next block to ledger");
+
+    // Ensure a side chain block must be new, and must have a known previous block.
+    assert!(ledger.add_side_chain_block(&block_1).is_err());
+    assert!(ledger.add_side_chain_block(&fork_block_2).is_err());
+
+    // Add the competing chain as a side chain.
+    ledger.add_side_chain_block(&fork_block_1).expect("Failed to add side chain block");
+    ledger.add_side_chain_block(&fork_block_2).expect("Failed to add side chain block");
+    assert!(ledger.add_side_chain_block(&fork_block_2).is_err());
+    assert_eq!(
+        vec![(fork_block_2.hash(), 2, fork_block_2.cumulative_weight())],
+        ledger.get_side_chain_tips().expect("Failed to get side chain tips")
+    );
+    assert_eq!(block_1.hash(), ledger.latest_block_hash());
+
+    // Switch to the side chain, and ensure the reverted block is retained as a side chain.
+    let (reverted_blocks, added_blocks) = ledger
+        .switch_to_side_chain(&fork_block_2.hash())
+        .expect("Failed to switch to side chain");
+    assert_eq!(vec![block_1.clone()], reverted_blocks);
+    assert_eq!(vec![fork_block_1.clone(), fork_block_2.clone()], added_blocks);
+    assert_eq!(fork_block_2.hash(), ledger.latest_block_hash());
+    assert_eq!(other_ledger.latest_ledger_root(), ledger.latest_ledger_root());
+    assert!(!ledger.contains_side_chain_block(&fork_block_1.hash()).expect("Failed to check side chain"));
+
+    // Ensure the reorg is recorded with the tip of the side chain as the new latest block.
+    let reorgs = ledger.get_reorgs().expect("Failed to get reorgs");
+    assert_eq!(1, reorgs.len());
+    assert_eq!((1, block_1.hash()), (reorgs[0].old_block_height, reorgs[0].old_block_hash));
+    assert_eq!((2, fork_block_2.hash()), (reorgs[0].new_block_height, reorgs[0].new_block_hash));
+    assert_eq!(0, reorgs[0].common_ancestor);
+
+    // Ensure a side chain that does not outweigh the canonical chain is rejected, without modifying the ledger.
+    assert!(ledger.switch_to_side_chain(&block_1.hash()).is_err());
+    assert_eq!(fork_block_2.hash(), ledger.latest_block_hash());
+    assert_eq!(other_ledger.latest_ledger_root(), ledger.latest_ledger_root());
+    assert_eq!(1, ledger.get_reorgs().expect("Failed to get reorgs").len());
+    drop(ledger);
+
+    // Ensure the side chains are retained across restarts.
+    let ledger = LedgerState::<Testnet2>::open_writer::<MemoryDB, _>(&path).expect("Failed to open ledger");
+    assert!(ledger.contains_side_chain_block(&block_1.hash()).expect("Failed to check side chain"));
+    assert_eq!(
+        vec![(block_1.hash(), 1, block_1.cumulative_weight())],
+        ledger.get_side_chain_tips().expect("Failed to get side chain tips")
+    );
+}
+
+#[test]
+fn test_block_cache() {
+    let rng = &mut thread_rng();
+    let terminator = AtomicBool::new(false);
+
+    // Initialize a new ledger.
+    let ledger = create_new_ledger::<Testnet2, MemoryDB>();
+
+    // Initialize a new account.
+    let account = Account::<Testnet2>::new(&mut thread_rng());
+    let address = account.address();
+
+    // Mine the next block.
+    let (block_1, _) = ledger
+        .mine_next_block(address, true, &[], &terminator, rng)
+        .expect("Failed to mine");
+    ledger.add_next_block(&block_1).expect("Failed to add next block to ledger");
+
+    // Ensure the added block is served from the cache.
+    let metrics = ledger.cache_metrics();
+    assert_eq!(block_1, ledger.get_block(1).expect("Failed to get block"));
+    assert_eq!(metrics.block_hits + 1, ledger.cache_metrics().block_hits);
+    assert_eq!(metrics.block_misses, ledger.cache_metrics().block_misses);
+
+    // Ensure the genesis block is loaded from storage once, and served from the cache thereafter.
+    let metrics = ledger.cache_metrics();
+    let genesis = Testnet2::genesis_block();
+    assert_eq!(*genesis, ledger.get_block(0).expect("Failed to get block"));
+    assert_eq!(*genesis, ledger.get_block(0).expect("Failed to get block"));
+    assert_eq!(metrics.block_hits + 1, ledger.cache_metrics().block_hits);
+    assert_eq!(metrics.block_misses + 1, ledger.cache_metrics().block_misses);
+
+    // Ensure the serialized blocks match the blocks, and are served from the cache thereafter.
+    let expected = vec![
+        bincode::serialize(genesis).expect("Failed to serialize"),
+        bincode::serialize(&block_1).expect("Failed to serialize"),
+    ];
+    assert_eq!(expected, ledger.get_serialized_blocks(0, 1).expect("Failed to get serialized blocks"));
+    assert_eq!(expected, ledger.get_serialized_blocks(0, 1).expect("Failed to get serialized blocks"));
+    assert_eq!(2, ledger.cache_metrics().serialized_block_hits);
+    assert_eq!(2, ledger.cache_metrics().serialized_block_misses);
+
+    // Revert the block, and mine a different block at the same height.
+    ledger.revert_to_block_height(0).expect("Failed to revert");
+    assert!(ledger.get_block(1).is_err());
+    assert!(ledger.get_block_header(1).is_err());
+    let (block_1b, _) = ledger
+        .mine_next_block(address, true, &[], &terminator, rng)
+        .expect("Failed to mine");
+    ledger.add_next_block(&block_1b).expect("Failed to add next block to ledger");
+
+    // Ensure the cache serves the new block, and not the reverted block.
+    assert_ne!(block_1.hash(), block_1b.hash());
+    assert_eq!(block_1b, ledger.get_block(1).expect("Failed to get block"));
+    assert_eq!(block_1b.header(), &ledger.get_block_header(1).expect("Failed to get block header"));
+    assert_eq!(vec![block_1b.clone()], ledger.get_blocks(1, 1).expect("Failed to get blocks"));
+    assert_eq!(
+        vec![bincode::serialize(&block_1b).expect("Failed to serialize")],
+        ledger.get_serialized_blocks(1, 1).expect("Failed to get serialized blocks")
+    );
+}
+
 #[test]
 fn test_get_block_locators() {
     let rng = &mut thread_rng();
     let terminator = AtomicBool::new(false);
 
     // Initialize a new ledger.
-    let ledger = create_new_ledger::<Testnet2, RocksDB>();
+    let ledger = create_new_ledger::<Testnet2, MemoryDB>();
     assert_eq!(0, ledger.latest_block_height());
 
     // Initialize a new ledger tree.
@@ -232,11 +489,9 @@ fn test_get_block_locators() {
     let block_locators = ledger
         .get_block_locators(ledger.latest_block_height())
         .expect("Failed to get block locators");
-    assert!(
-        ledger
-            .check_block_locators(&block_locators)
-            .expect("Failed to check block locators")
-    );
+    assert!(ledger
+        .check_block_locators(&block_locators)
+        .expect("Failed to check block locators"));
 
     // Mine the next block.
     let (block_2, _) = ledger
@@ -249,11 +504,9 @@ fn test_get_block_locators() {
     let block_locators = ledger
         .get_block_locators(ledger.latest_block_height())
         .expect("Failed to get block locators");
-    assert!(
-        ledger
-            .check_block_locators(&block_locators)
-            .expect("Failed to check block locators")
-    );
+    assert!(ledger
+        .check_block_locators(&block_locators)
+        .expect("Failed to check block locators"));
 
     // Mine the next block.
     let (block_3, _) = ledger
@@ -266,9 +519,458 @@ fn test_get_block_locators() {
     let block_locators = ledger
         .get_block_locators(ledger.latest_block_height())
         .expect("Failed to get block locators");
-    assert!(
-        ledger
-            .check_block_locators(&block_locators)
-            .expect("Failed to check block locators")
+    assert!(ledger
+        .check_block_locators(&block_locators)
+        .expect("Failed to check block locators"));
+}
+
+#[test]
+fn test_pruned_ledger() {
+    let rng = &mut thread_rng();
+    let terminator = AtomicBool::new(false);
+
+    // Initialize a new ledger.
+    let path = temp_path();
+    let ledger = LedgerState::<Testnet2>::open_writer::<MemoryDB, _>(&path).expect("Failed to initialize ledger");
+    assert_eq!(None, ledger.pruning_depth());
+
+    // Initialize a new account.
+    let account = Account::<Testnet2>::new(&mut thread_rng());
+    let address = account.address();
+
+    // Mine the next two blocks.
+    let (block_1, record_1) = ledger
+        .mine_next_block(address, true, &[], &terminator, rng)
+        .expect("Failed to mine");
+    ledger.add_next_block(&block_1).expect("Failed to add next block to ledger");
+    let (block_2, _) = ledger
+        .mine_next_block(address, true, &[], &terminator, rng)
+        .expect("Failed to mine");
+    ledger.add_next_block(&block_2).expect("Failed to add next block to ledger");
+
+    // Record a pruning depth of 2 blocks in storage, which is below the maximum fork depth
+    // accepted by `open_pruned_writer`, and reopen the ledger, which prunes the genesis block.
+    drop(ledger);
+    {
+        let storage = MemoryDB::open(&path, Testnet2::NETWORK_ID, false).expect("Failed to open storage");
+        let pruning: DataMap<(), (u32, u32)> = storage.open_map(MapId::Pruning).expect("Failed to open data map");
+        pruning.insert(&(), &(2, 0)).expect("Failed to insert");
+    }
+    let ledger = LedgerState::<Testnet2>::open_writer::<MemoryDB, _>(&path).expect("Failed to open pruned ledger");
+    assert_eq!(Some(2), ledger.pruning_depth());
+    assert_eq!(1, ledger.pruned_block_height());
+    assert!(ledger.get_block(0).is_err());
+    assert_eq!(block_1, ledger.get_block(1).expect("Failed to get block"));
+
+    // Mine the next block, which prunes block 1.
+    let (block_3, _) = ledger
+        .mine_next_block(address, true, &[], &terminator, rng)
+        .expect("Failed to mine");
+    ledger.add_next_block(&block_3).expect("Failed to add next block to ledger");
+    assert_eq!(2, ledger.pruned_block_height());
+    assert!(ledger.get_block(1).is_err());
+    assert!(ledger.get_blocks(1, 3).is_err());
+    assert_eq!(vec![block_2, block_3], ledger.get_blocks(2, 3).expect("Failed to get blocks"));
+
+    // Ensure the pruned block is still known, but its transactions are no longer served.
+    assert_eq!(block_1.header(), &ledger.get_block_header(1).expect("Failed to get block header"));
+    for transaction in block_1.transactions().iter() {
+        assert!(ledger.contains_transaction(&transaction.transaction_id()).unwrap());
+        assert!(ledger.get_transaction(&transaction.transaction_id()).is_err());
+        for transition_id in transaction.transition_ids() {
+            assert!(ledger.get_transition(&transition_id).is_err());
+        }
+    }
+
+    // Ensure the pruned ledger still serves ledger inclusion proofs for the commitments of the pruned block.
+    assert!(ledger.contains_commitment(&record_1.commitment()).unwrap());
+    assert!(ledger.get_ciphertext(&record_1.commitment()).is_err());
+    ledger
+        .get_ledger_inclusion_proof(record_1.commitment())
+        .expect("Failed to get ledger inclusion proof");
+
+    // Ensure the ledger remains pruned when it is reopened.
+    drop(ledger);
+    let ledger = LedgerState::<Testnet2>::open_writer::<MemoryDB, _>(&path).expect("Failed to open ledger");
+    assert_eq!(Some(2), ledger.pruning_depth());
+    assert_eq!(2, ledger.pruned_block_height());
+
+    // Ensure a pruning depth below the maximum fork depth is rejected.
+    assert!(LedgerState::<Testnet2>::open_pruned_writer::<MemoryDB, _>(temp_path(), 0).is_err());
+    assert!(LedgerState::<Testnet2>::open_pruned_writer::<MemoryDB, _>(temp_path(), 4095).is_err());
+}
+
+#[test]
+fn test_reader_notifications() {
+    let rng = &mut thread_rng();
+    let terminator = AtomicBool::new(false);
+
+    /// Waits for the given reader to catch up to the given ledger, for up to 2 seconds.
+    fn wait_for_reader(reader: &LedgerState<Testnet2>, ledger: &LedgerState<Testnet2>) -> bool {
+        let start = Instant::now();
+        while reader.latest_block_height() != ledger.latest_block_height() || reader.latest_ledger_root() != ledger.latest_ledger_root() {
+            if start.elapsed() > Duration::from_secs(2) {
+                return false;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        true
+    }
+
+    // Initialize a new ledger, and a reader of it in the same process.
+    let path = temp_path();
+    let ledger = LedgerState::<Testnet2>::open_writer::<MemoryDB, _>(&path).expect("Failed to initialize ledger");
+    let reader = LedgerState::<Testnet2>::open_reader::<MemoryDB, _>(&path).expect("Failed to open ledger reader");
+    assert_eq!(0, reader.latest_block_height());
+
+    // Initialize a new account.
+    let account = Account::<Testnet2>::new(&mut thread_rng());
+    let address = account.address();
+
+    // Ensure the reader is notified of a new block well before its polling interval.
+    let (block_1, _) = ledger
+        .mine_next_block(address, true, &[], &terminator, rng)
+        .expect("Failed to mine");
+    ledger.add_next_block(&block_1).expect("Failed to add next block to ledger");
+    assert!(wait_for_reader(&reader, &ledger));
+    assert_eq!(block_1, reader.latest_block());
+
+    // Ensure the reader is notified of a revert.
+    ledger.revert_to_block_height(0).expect("Failed to revert");
+    assert!(wait_for_reader(&reader, &ledger));
+    assert_eq!(0, reader.latest_block_height());
+}
+
+#[test]
+fn test_verify() {
+    let rng = &mut thread_rng();
+    let terminator = AtomicBool::new(false);
+
+    // Initialize a new ledger.
+    let path = temp_path();
+    let ledger = LedgerState::<Testnet2>::open_writer::<MemoryDB, _>(&path).expect("Failed to initialize ledger");
+
+    // Mine the next block.
+    let address = Account::<Testnet2>::new(&mut thread_rng()).address();
+    let (block_1, _) = ledger
+        .mine_next_block(address, true, &[], &terminator, rng)
+        .expect("Failed to mine");
+    ledger.add_next_block(&block_1).expect("Failed to add next block to ledger");
+    drop(ledger);
+
+    // Ensure the ledger is verified without discrepancies.
+    let report = LedgerState::<Testnet2>::verify::<MemoryDB, _>(&path).expect("Failed to verify ledger");
+    assert!(report.is_valid(), "{:?}", report.discrepancies);
+    assert_eq!(1, report.latest_block_height);
+    assert_eq!(2, report.number_of_blocks);
+    assert_eq!(2, report.number_of_ledger_roots);
+    assert_eq!(2, report.number_of_transactions);
+
+    // Remove the ledger root of block 1, and the first transition of block 1.
+    {
+        let storage = MemoryDB::open(&path, Testnet2::NETWORK_ID, false).expect("Failed to open storage");
+        let ledger_roots: DataMap<<Testnet2 as Network>::LedgerRoot, u32> =
+            storage.open_map(MapId::LedgerRoots).expect("Failed to open data map");
+        ledger_roots.remove(&block_1.previous_ledger_root()).expect("Failed to remove");
+
+        let transition_id = block_1.transactions().iter().next().unwrap().transition_ids().next().unwrap();
+        let transitions: DataMap<<Testnet2 as Network>::TransitionID, (<Testnet2 as Network>::TransactionID, u8, Transition<Testnet2>)> =
+            storage.open_map(MapId::Transitions).expect("Failed to open data map");
+        transitions.remove(&transition_id).expect("Failed to remove");
+    }
+
+    // Ensure the discrepancies are reported.
+    let report = LedgerState::<Testnet2>::verify::<MemoryDB, _>(&path).expect("Failed to verify ledger");
+    assert!(!report.is_valid());
+    let kinds = report.discrepancies.iter().map(|discrepancy| discrepancy.kind).collect::<Vec<_>>();
+    assert!(kinds.contains(&DiscrepancyKind::LedgerRoot));
+    assert!(kinds.contains(&DiscrepancyKind::Transition));
+    assert!(kinds.contains(&DiscrepancyKind::Commitment));
+    assert!(report.discrepancies.iter().all(|discrepancy| discrepancy.block_height != Some(0)));
+
+    // Replace the block header of block 1 with the genesis block header.
+    {
+        let storage = MemoryDB::open(&path, Testnet2::NETWORK_ID, false).expect("Failed to open storage");
+        let block_headers: DataMap<<Testnet2 as Network>::BlockHash, BlockHeader<Testnet2>> =
+            storage.open_map(MapId::BlockHeaders).expect("Failed to open data map");
+        block_headers
+            .insert(&block_1.hash(), Testnet2::genesis_block().header())
+            .expect("Failed to insert");
+    }
+
+    // Ensure the block header is reported as not corresponding to its block hash.
+    let report = LedgerState::<Testnet2>::verify::<MemoryDB, _>(&path).expect("Failed to verify ledger");
+    assert!(report
+        .discrepancies
+        .iter()
+        .any(|discrepancy| discrepancy.kind == DiscrepancyKind::PreviousBlockHash && discrepancy.block_height == Some(1)));
+}
+
+#[test]
+fn test_repair() {
+    let rng = &mut thread_rng();
+    let terminator = AtomicBool::new(false);
+
+    // Initialize a new ledger.
+    let path = temp_path();
+    let ledger = LedgerState::<Testnet2>::open_writer::<MemoryDB, _>(&path).expect("Failed to initialize ledger");
+
+    // Mine the next two blocks.
+    let address = Account::<Testnet2>::new(&mut thread_rng()).address();
+    let (block_1, record_1) = ledger
+        .mine_next_block(address, true, &[], &terminator, rng)
+        .expect("Failed to mine");
+    ledger.add_next_block(&block_1).expect("Failed to add next block to ledger");
+    let (block_2, _) = ledger
+        .mine_next_block(address, true, &[], &terminator, rng)
+        .expect("Failed to mine");
+    ledger.add_next_block(&block_2).expect("Failed to add next block to ledger");
+    let latest_ledger_root = ledger.latest_ledger_root();
+    drop(ledger);
+
+    // Remove the ledger root of block 2 and the commitment of block 1, and add an orphaned ledger root.
+    {
+        let storage = MemoryDB::open(&path, Testnet2::NETWORK_ID, false).expect("Failed to open storage");
+        let ledger_roots: DataMap<<Testnet2 as Network>::LedgerRoot, u32> =
+            storage.open_map(MapId::LedgerRoots).expect("Failed to open data map");
+        ledger_roots.remove(&block_2.previous_ledger_root()).expect("Failed to remove");
+        ledger_roots.insert(&latest_ledger_root, &3).expect("Failed to insert");
+
+        let commitments: DataMap<<Testnet2 as Network>::Commitment, <Testnet2 as Network>::TransitionID> =
+            storage.open_map(MapId::Commitments).expect("Failed to open data map");
+        commitments.remove(&record_1.commitment()).expect("Failed to remove");
+    }
+
+    // Ensure a dry run reports the changes, without writing them.
+    let target = Some(RepairTarget::BlockHash(block_1.hash()));
+    let report = LedgerState::<Testnet2>::repair::<MemoryDB, _>(&path, target.clone(), true).expect("Failed to repair ledger");
+    let changes = report
+        .changes
+        .iter()
+        .map(|change| (change.action, change.map.as_str(), change.block_height))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            (RepairAction::Insert, "ledger_roots", Some(2)),
+            (RepairAction::Remove, "ledger_roots", Some(3)),
+            (RepairAction::Insert, "commitments", Some(1)),
+            (RepairAction::Revert, "block_heights", Some(2)),
+        ],
+        changes
     );
+    assert_eq!(Some(1), report.target_block_height);
+    assert!(!LedgerState::<Testnet2>::verify::<MemoryDB, _>(&path).unwrap().is_valid());
+
+    // Ensure the repair writes the changes, and reverts the ledger to the target block.
+    let report = LedgerState::<Testnet2>::repair::<MemoryDB, _>(&path, target, false).expect("Failed to repair ledger");
+    assert_eq!(4, report.changes.len());
+    let verification = LedgerState::<Testnet2>::verify::<MemoryDB, _>(&path).expect("Failed to verify ledger");
+    assert!(verification.is_valid(), "{:?}", verification.discrepancies);
+    assert_eq!(1, verification.latest_block_height);
+
+    // Ensure a repaired ledger requires no further changes.
+    let report = LedgerState::<Testnet2>::repair::<MemoryDB, _>(&path, None, true).expect("Failed to repair ledger");
+    assert!(report.is_empty(), "{:?}", report.changes);
+}
+
+#[test]
+fn test_snapshot_export_and_import() {
+    let rng = &mut thread_rng();
+    let terminator = AtomicBool::new(false);
+
+    // Initialize a new ledger.
+    let ledger = create_new_ledger::<Testnet2, MemoryDB>();
+
+    // Initialize a new account.
+    let account = Account::<Testnet2>::new(&mut thread_rng());
+    let address = account.address();
+
+    // Mine the next block.
+    let (block_1, _) = ledger
+        .mine_next_block(address, true, &[], &terminator, rng)
+        .expect("Failed to mine");
+    ledger.add_next_block(&block_1).expect("Failed to add next block to ledger");
+    assert_eq!(1, ledger.latest_block_height());
+
+    // Export a snapshot of the ledger.
+    let mut snapshot = vec![];
+    let header = ledger.export_snapshot(&mut snapshot).expect("Failed to export snapshot");
+    assert_eq!(1, header.block_height);
+    assert_eq!(ledger.latest_block_hash(), header.block_hash);
+    assert_eq!(ledger.latest_ledger_root(), header.ledger_root);
+
+    // Import the snapshot into new storage.
+    let imported =
+        LedgerState::<Testnet2>::import_snapshot::<MemoryDB, _, _>(temp_path(), &snapshot[..]).expect("Failed to import snapshot");
+
+    // Ensure the imported ledger matches the original ledger.
+    assert_eq!(ledger.latest_block_height(), imported.latest_block_height());
+    assert_eq!(ledger.latest_block(), imported.latest_block());
+    assert_eq!(ledger.latest_ledger_root(), imported.latest_ledger_root());
+    assert_eq!(ledger.latest_block_locators(), imported.latest_block_locators());
+    for transaction in block_1.transactions().iter() {
+        assert!(imported.contains_transaction(&transaction.transaction_id()).unwrap());
+    }
+}
+
+#[test]
+fn test_snapshot_import_rejects_invalid_snapshots() {
+    // Initialize a new ledger.
+    let ledger = create_new_ledger::<Testnet2, MemoryDB>();
+
+    // Export a snapshot of the ledger.
+    let mut snapshot = vec![];
+    ledger.export_snapshot(&mut snapshot).expect("Failed to export snapshot");
+
+    // Ensure a corrupted snapshot is rejected, and leaves no storage behind.
+    let mut corrupted = snapshot.clone();
+    let index = corrupted.len() - 16;
+    corrupted[index] ^= 0xff;
+    let path = temp_path();
+    assert!(LedgerState::<Testnet2>::import_snapshot::<MemoryDB, _, _>(&path, &corrupted[..]).is_err());
+    assert!(!MemoryDB::exists(&path));
+
+    // Ensure a truncated snapshot is rejected.
+    let truncated = &snapshot[..snapshot.len() - 4];
+    assert!(LedgerState::<Testnet2>::import_snapshot::<MemoryDB, _, _>(temp_path(), truncated).is_err());
+
+    // Ensure a snapshot is not imported into existing storage.
+    let path = temp_path();
+    let _existing = LedgerState::<Testnet2>::open_writer::<MemoryDB, _>(&path).expect("Failed to initialize ledger");
+    assert!(LedgerState::<Testnet2>::import_snapshot::<MemoryDB, _, _>(&path, &snapshot[..]).is_err());
+}
+
+#[test]
+fn test_backup_and_restore() {
+    let rng = &mut thread_rng();
+    let terminator = AtomicBool::new(false);
+
+    // Initialize a new ledger and prover.
+    let ledger = create_new_ledger::<Testnet2, MemoryDB>();
+    let prover = ProverState::<Testnet2>::open_writer::<MemoryDB, _>(temp_path()).expect("Failed to open prover");
+
+    // Initialize a new account.
+    let account = Account::<Testnet2>::new(&mut thread_rng());
+    let address = account.address();
+
+    // Mine the next block, and store its coinbase record.
+    let (block_1, record) = ledger
+        .mine_next_block(address, true, &[], &terminator, rng)
+        .expect("Failed to mine");
+    ledger.add_next_block(&block_1).expect("Failed to add next block to ledger");
+    prover.add_coinbase_record(1, record.clone()).expect("Failed to add coinbase record");
+
+    // Write a backup of the ledger and the prover.
+    let backup = tempfile::tempdir().expect("Failed to open temporary directory");
+    let manifest = ledger.backup(&prover, backup.path()).expect("Failed to write backup");
+    assert_eq!(1, manifest.block_height);
+    assert_eq!(ledger.latest_block_hash(), manifest.block_hash);
+    assert_eq!(ledger.latest_ledger_root(), manifest.ledger_root);
+
+    // Ensure the backup is not overwritten.
+    assert!(ledger.backup(&prover, backup.path()).is_err());
+
+    // Ensure the ledger is unaffected by the backup, and advance it past the backup.
+    let (block_2, _) = ledger
+        .mine_next_block(address, true, &[], &terminator, rng)
+        .expect("Failed to mine");
+    ledger.add_next_block(&block_2).expect("Failed to add next block to ledger");
+
+    // Restore the backup into new storage.
+    let (ledger_path, prover_path) = (temp_path(), temp_path());
+    let restored_manifest = LedgerState::<Testnet2>::restore_backup::<MemoryDB, _, _, _>(backup.path(), &ledger_path, &prover_path)
+        .expect("Failed to restore backup");
+    assert_eq!(manifest, restored_manifest);
+
+    // Ensure the restored ledger and prover match the state at the time of the backup.
+    let restored = LedgerState::<Testnet2>::open_writer::<MemoryDB, _>(&ledger_path).expect("Failed to open ledger");
+    assert_eq!(1, restored.latest_block_height());
+    assert_eq!(block_1, restored.latest_block());
+    let restored_prover = ProverState::<Testnet2>::open_writer::<MemoryDB, _>(&prover_path).expect("Failed to open prover");
+    assert_eq!(vec![(1, record)], restored_prover.to_coinbase_records());
+
+    // Ensure a backup is not restored over existing storage, and that nothing is left behind.
+    let other_prover_path = temp_path();
+    assert!(LedgerState::<Testnet2>::restore_backup::<MemoryDB, _, _, _>(backup.path(), &ledger_path, &other_prover_path).is_err());
+    assert!(!MemoryDB::exists(&other_prover_path));
+
+    // Ensure a backup without a manifest is rejected.
+    std::fs::remove_file(backup.path().join("manifest.json")).expect("Failed to remove manifest");
+    assert!(LedgerState::<Testnet2>::restore_backup::<MemoryDB, _, _, _>(backup.path(), temp_path(), temp_path()).is_err());
+}
+
+#[test]
+fn test_prover_memory_pool() {
+    let path = temp_path();
+    let transaction = Testnet2::genesis_block()
+        .to_coinbase_transaction()
+        .expect("Failed to get coinbase transaction");
+
+    // Ensure an unconfirmed transaction is retained in storage across restarts.
+    let prover = ProverState::<Testnet2>::open_writer::<MemoryDB, _>(&path).expect("Failed to open prover");
+    assert!(prover.to_unconfirmed_transactions().is_empty());
+    prover
+        .add_unconfirmed_transaction(&transaction)
+        .expect("Failed to add unconfirmed transaction");
+    drop(prover);
+
+    let prover = ProverState::<Testnet2>::open_writer::<MemoryDB, _>(&path).expect("Failed to open prover");
+    assert_eq!(vec![transaction.clone()], prover.to_unconfirmed_transactions());
+
+    // Ensure a removed transaction is not restored.
+    prover
+        .remove_unconfirmed_transaction(&transaction.transaction_id())
+        .expect("Failed to remove unconfirmed transaction");
+    drop(prover);
+
+    let prover = ProverState::<Testnet2>::open_writer::<MemoryDB, _>(&path).expect("Failed to open prover");
+    assert!(prover.to_unconfirmed_transactions().is_empty());
+}
+
+#[test]
+fn test_schema_version() {
+    let path = temp_path();
+
+    // Initialize a new ledger, which records the current schema version.
+    let ledger = LedgerState::<Testnet2>::open_writer::<MemoryDB, _>(&path).expect("Failed to initialize ledger");
+    let storage = MemoryDB::open(&path, Testnet2::NETWORK_ID, false).expect("Failed to open storage");
+    let schema_version = storage.open_map::<(), u32>(MapId::SchemaVersion).expect("Failed to open data map");
+    assert_eq!(Some(SCHEMA_VERSION), schema_version.get(&()).expect("Failed to get"));
+
+    // Ensure storage with a newer schema version is not opened.
+    drop(ledger);
+    schema_version.insert(&(), &(SCHEMA_VERSION + 1)).expect("Failed to insert");
+    assert!(LedgerState::<Testnet2>::open_writer::<MemoryDB, _>(&path).is_err());
+    assert!(LedgerState::<Testnet2>::open_reader::<MemoryDB, _>(&path).is_err());
+    assert!(ProverState::<Testnet2>::open_writer::<MemoryDB, _>(&path).is_err());
+
+    // Ensure storage that predates schema versioning is migrated by a writer.
+    schema_version.remove(&()).expect("Failed to remove");
+    assert!(LedgerState::<Testnet2>::open_reader::<MemoryDB, _>(&path).is_err());
+    LedgerState::<Testnet2>::open_writer::<MemoryDB, _>(&path).expect("Failed to migrate ledger");
+    assert_eq!(Some(SCHEMA_VERSION), schema_version.get(&()).expect("Failed to get"));
+    LedgerState::<Testnet2>::open_reader::<MemoryDB, _>(&path).expect("Failed to open ledger reader");
+}
+
+#[test]
+fn test_schema_migration_of_block_heights() {
+    let storage = MemoryDB::open(temp_path(), Testnet2::NETWORK_ID, false).expect("Failed to open storage");
+    let schema_version = storage.open_map::<(), u32>(MapId::SchemaVersion).expect("Failed to open data map");
+    let block_heights: DataMap<u32, <Testnet2 as Network>::BlockHash> =
+        storage.open_map(MapId::BlockHeights).expect("Failed to open data map");
+
+    // Write block heights with little-endian keys, as in schema version 1.
+    let block_hash = Testnet2::genesis_block().hash();
+    let legacy_block_heights = block_heights.with_key_encoding(KeyEncoding::LittleEndian);
+    for block_height in 0..300u32 {
+        legacy_block_heights.insert(&block_height, &block_hash).expect("Failed to insert");
+    }
+    schema_version.insert(&(), &1).expect("Failed to insert");
+
+    // Ensure the migration rewrites the block heights in order.
+    check_schema_version::<Testnet2, MemoryDB>(&storage, false).expect("Failed to migrate storage");
+    assert_eq!(Some(SCHEMA_VERSION), schema_version.get(&()).expect("Failed to get"));
+    assert_eq!((0..300u32).collect::<Vec<_>>(), block_heights.keys().collect::<Vec<_>>());
+    assert_eq!(Some((299, block_hash)), block_heights.last().expect("Failed to get last"));
+    assert_eq!(45, block_heights.range(255..300).expect("Failed to get range").count());
 }
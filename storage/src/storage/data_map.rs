@@ -0,0 +1,321 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+#[cfg(feature = "rocks")]
+use crate::storage::rocksdb;
+#[cfg(feature = "sled")]
+use crate::storage::sled;
+use crate::storage::{memory, KeyEncoding, Map, StorageStats};
+
+use anyhow::{anyhow, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{borrow::Borrow, ops::RangeBounds, path::Path};
+
+///
+/// A map in storage, opened from one of the storage backends.
+///
+#[derive(Clone, Debug)]
+pub enum DataMap<K: Serialize + DeserializeOwned, V: Serialize + DeserializeOwned> {
+    #[cfg(feature = "rocks")]
+    RocksDB(rocksdb::DataMap<K, V>),
+    #[cfg(feature = "sled")]
+    Sled(sled::DataMap<K, V>),
+    Memory(memory::DataMap<K, V>),
+}
+
+/// Dispatches the given expression to the storage backend of the map.
+macro_rules! dispatch {
+    ($self:expr, $map:ident => $expression:expr) => {
+        match $self {
+            #[cfg(feature = "rocks")]
+            Self::RocksDB($map) => $expression,
+            #[cfg(feature = "sled")]
+            Self::Sled($map) => $expression,
+            Self::Memory($map) => $expression,
+        }
+    };
+}
+
+impl<K: Serialize + DeserializeOwned, V: Serialize + DeserializeOwned> DataMap<K, V> {
+    ///
+    /// Returns this map with the given key encoding, in order to read the entries of a map
+    /// that were written with a different key encoding while migrating the map.
+    ///
+    pub(crate) fn with_key_encoding(&self, key_encoding: KeyEncoding) -> Self {
+        match self {
+            #[cfg(feature = "rocks")]
+            Self::RocksDB(map) => Self::RocksDB(map.with_key_encoding(key_encoding)),
+            #[cfg(feature = "sled")]
+            Self::Sled(map) => Self::Sled(map.with_key_encoding(key_encoding)),
+            Self::Memory(map) => Self::Memory(map.with_key_encoding(key_encoding)),
+        }
+    }
+
+    ///
+    /// Creates a checkpoint of the storage this map was opened from, including all of its other maps,
+    /// at the given path. The checkpoint is a consistent view of storage, excluding any staged operations.
+    ///
+    pub(crate) fn create_checkpoint<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        dispatch!(self, map => map.create_checkpoint(path))
+    }
+
+    ///
+    /// Returns the size and health statistics of the storage this map was opened from, including all of its other maps.
+    ///
+    pub(crate) fn storage_stats(&self) -> Result<StorageStats> {
+        dispatch!(self, map => map.storage_stats())
+    }
+
+    ///
+    /// Rewrites every entry of this map into the given target map with the given function,
+    /// logging the progress as it goes, and returns the number of migrated entries.
+    ///
+    /// The target map may be this map opened with a different key or value type, in order to
+    /// change the layout of a map in place. All removals are staged before all inserts, so
+    /// migrated keys may coincide with keys that have yet to be migrated.
+    ///
+    /// *Attention*: The migration must be performed within an atomic batch, which holds
+    /// the migrated entries in memory until the batch is finished.
+    ///
+    pub fn migrate_into<K2, V2, F>(&self, target: &DataMap<K2, V2>, migrate: F) -> Result<usize>
+    where
+        K2: Serialize + DeserializeOwned,
+        V2: Serialize + DeserializeOwned,
+        F: Fn(K, V) -> Result<(K2, V2)>,
+    {
+        if !self.is_atomic_in_progress() {
+            return Err(anyhow!("Map migrations must be performed within an atomic batch"));
+        }
+
+        let total = self.keys().count();
+        let mut entries = Vec::with_capacity(total);
+
+        for (index, (key, value)) in self.iter().enumerate() {
+            self.remove(&key)?;
+            entries.push(migrate(key, value)?);
+
+            // Log the progress of the migration at every 10%.
+            let (progress, previous_progress) = ((index + 1) * 10 / total, index * 10 / total);
+            if progress != previous_progress {
+                info!("Migrated {} of {} entries ({}%)", index + 1, total, progress * 10);
+            }
+        }
+
+        for (key, value) in &entries {
+            target.insert(key, value)?;
+        }
+
+        Ok(entries.len())
+    }
+}
+
+impl<'a, K: 'a + Serialize + DeserializeOwned, V: 'a + Serialize + DeserializeOwned> Map<'a, K, V> for DataMap<K, V> {
+    type Iterator = Iter<'a, K, V>;
+    type Keys = Keys<'a, K, V>;
+    type Values = Values<'a, K, V>;
+
+    ///
+    /// Returns `true` if the given key exists in the map.
+    ///
+    fn contains_key<Q>(&self, key: &Q) -> Result<bool>
+    where
+        K: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        dispatch!(self, map => map.contains_key(key))
+    }
+
+    ///
+    /// Returns the value for the given key from the map, if it exists.
+    ///
+    fn get<Q>(&self, key: &Q) -> Result<Option<V>>
+    where
+        K: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        dispatch!(self, map => map.get(key))
+    }
+
+    ///
+    /// Inserts the given key-value pair into the map.
+    ///
+    fn insert<Q>(&self, key: &Q, value: &V) -> Result<()>
+    where
+        K: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        dispatch!(self, map => map.insert(key, value))
+    }
+
+    ///
+    /// Removes the key-value pair for the given key from the map.
+    ///
+    fn remove<Q>(&self, key: &Q) -> Result<()>
+    where
+        K: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        dispatch!(self, map => map.remove(key))
+    }
+
+    ///
+    /// Returns an iterator visiting each key-value pair in the map.
+    ///
+    fn iter(&'a self) -> Self::Iterator {
+        match self {
+            #[cfg(feature = "rocks")]
+            Self::RocksDB(map) => Iter::RocksDB(map.iter()),
+            #[cfg(feature = "sled")]
+            Self::Sled(map) => Iter::Sled(map.iter()),
+            Self::Memory(map) => Iter::Memory(map.iter()),
+        }
+    }
+
+    ///
+    /// Returns an iterator over each key in the map.
+    ///
+    fn keys(&'a self) -> Self::Keys {
+        match self {
+            #[cfg(feature = "rocks")]
+            Self::RocksDB(map) => Keys::RocksDB(map.keys()),
+            #[cfg(feature = "sled")]
+            Self::Sled(map) => Keys::Sled(map.keys()),
+            Self::Memory(map) => Keys::Memory(map.keys()),
+        }
+    }
+
+    ///
+    /// Returns an iterator over each value in the map.
+    ///
+    fn values(&'a self) -> Self::Values {
+        match self {
+            #[cfg(feature = "rocks")]
+            Self::RocksDB(map) => Values::RocksDB(map.values()),
+            #[cfg(feature = "sled")]
+            Self::Sled(map) => Values::Sled(map.values()),
+            Self::Memory(map) => Values::Memory(map.values()),
+        }
+    }
+
+    ///
+    /// Returns an iterator visiting each key-value pair in the given range of keys, in the order of
+    /// their serialized keys.
+    ///
+    fn range<R: RangeBounds<K>>(&'a self, range: R) -> Result<Self::Iterator> {
+        match self {
+            #[cfg(feature = "rocks")]
+            Self::RocksDB(map) => Ok(Iter::RocksDB(map.range(range)?)),
+            #[cfg(feature = "sled")]
+            Self::Sled(map) => Ok(Iter::Sled(map.range(range)?)),
+            Self::Memory(map) => Ok(Iter::Memory(map.range(range)?)),
+        }
+    }
+
+    ///
+    /// Returns the key-value pair with the greatest serialized key in the map, if the map is not empty.
+    ///
+    fn last(&self) -> Result<Option<(K, V)>> {
+        dispatch!(self, map => map.last())
+    }
+
+    ///
+    /// Begins an atomic batch of operations. Until the batch is finished or aborted, all inserts and
+    /// removes on every map opened from the same storage are staged instead of being written.
+    ///
+    fn start_atomic(&self) -> Result<()> {
+        dispatch!(self, map => map.start_atomic())
+    }
+
+    ///
+    /// Returns `true` if an atomic batch of operations is in progress.
+    ///
+    fn is_atomic_in_progress(&self) -> bool {
+        dispatch!(self, map => map.is_atomic_in_progress())
+    }
+
+    ///
+    /// Discards all operations staged since the atomic batch was started.
+    ///
+    fn abort_atomic(&self) {
+        dispatch!(self, map => map.abort_atomic())
+    }
+
+    ///
+    /// Writes all operations staged since the atomic batch was started to storage, as a single atomic write.
+    ///
+    fn finish_atomic(&self) -> Result<()> {
+        dispatch!(self, map => map.finish_atomic())
+    }
+
+    ///
+    /// Performs a refresh operation for implementations of `Map` that perform periodic operations.
+    /// Returns `true` if the database state has been updated.
+    ///
+    fn refresh(&self) -> bool {
+        dispatch!(self, map => map.refresh())
+    }
+}
+
+/// An iterator over all key-value pairs in a data map.
+pub enum Iter<'a, K: 'a + Serialize + DeserializeOwned, V: 'a + Serialize + DeserializeOwned> {
+    #[cfg(feature = "rocks")]
+    RocksDB(<rocksdb::DataMap<K, V> as Map<'a, K, V>>::Iterator),
+    #[cfg(feature = "sled")]
+    Sled(<sled::DataMap<K, V> as Map<'a, K, V>>::Iterator),
+    Memory(<memory::DataMap<K, V> as Map<'a, K, V>>::Iterator),
+}
+
+impl<'a, K: 'a + Serialize + DeserializeOwned, V: 'a + Serialize + DeserializeOwned> Iterator for Iter<'a, K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        dispatch!(self, iterator => iterator.next())
+    }
+}
+
+/// An iterator over the keys of a data map.
+pub enum Keys<'a, K: 'a + Serialize + DeserializeOwned, V: 'a + Serialize + DeserializeOwned> {
+    #[cfg(feature = "rocks")]
+    RocksDB(<rocksdb::DataMap<K, V> as Map<'a, K, V>>::Keys),
+    #[cfg(feature = "sled")]
+    Sled(<sled::DataMap<K, V> as Map<'a, K, V>>::Keys),
+    Memory(<memory::DataMap<K, V> as Map<'a, K, V>>::Keys),
+}
+
+impl<'a, K: 'a + Serialize + DeserializeOwned, V: 'a + Serialize + DeserializeOwned> Iterator for Keys<'a, K, V> {
+    type Item = K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        dispatch!(self, iterator => iterator.next())
+    }
+}
+
+/// An iterator over the values of a data map.
+pub enum Values<'a, K: 'a + Serialize + DeserializeOwned, V: 'a + Serialize + DeserializeOwned> {
+    #[cfg(feature = "rocks")]
+    RocksDB(<rocksdb::DataMap<K, V> as Map<'a, K, V>>::Values),
+    #[cfg(feature = "sled")]
+    Sled(<sled::DataMap<K, V> as Map<'a, K, V>>::Values),
+    Memory(<memory::DataMap<K, V> as Map<'a, K, V>>::Values),
+}
+
+impl<'a, K: 'a + Serialize + DeserializeOwned, V: 'a + Serialize + DeserializeOwned> Iterator for Values<'a, K, V> {
+    type Item = V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        dispatch!(self, iterator => iterator.next())
+    }
+}
@@ -0,0 +1,117 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::storage::KeyEncoding;
+
+use anyhow::Result;
+
+///
+/// The identifier of a map in storage, which is independent of the storage backend.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MapId {
+    BlockHeaders,
+    BlockHeights,
+    BlockTransactions,
+    Commitments,
+    LedgerRoots,
+    MemoryPool,
+    Pruning,
+    RecordProofs,
+    Records,
+    Reorgs,
+    SchemaVersion,
+    SerialNumbers,
+    SideChainBlocks,
+    SideChainChildren,
+    Transactions,
+    Transitions,
+    #[cfg(test)]
+    Test,
+}
+
+impl MapId {
+    /// The list of all maps in storage.
+    pub const ALL: &'static [MapId] = &[
+        Self::BlockHeaders,
+        Self::BlockHeights,
+        Self::BlockTransactions,
+        Self::Commitments,
+        Self::LedgerRoots,
+        Self::MemoryPool,
+        Self::Pruning,
+        Self::RecordProofs,
+        Self::Records,
+        Self::Reorgs,
+        Self::SchemaVersion,
+        Self::SerialNumbers,
+        Self::SideChainBlocks,
+        Self::SideChainChildren,
+        Self::Transactions,
+        Self::Transitions,
+        #[cfg(test)]
+        Self::Test,
+    ];
+
+    /// Returns the name of the map, which backends may use to store the map separately, such as in its own column family.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::BlockHeaders => "block_headers",
+            Self::BlockHeights => "block_heights",
+            Self::BlockTransactions => "block_transactions",
+            Self::Commitments => "commitments",
+            Self::LedgerRoots => "ledger_roots",
+            Self::MemoryPool => "memory_pool",
+            Self::Pruning => "pruning",
+            Self::RecordProofs => "record_proofs",
+            Self::Records => "records",
+            Self::Reorgs => "reorgs",
+            Self::SchemaVersion => "schema_version",
+            Self::SerialNumbers => "serial_numbers",
+            Self::SideChainBlocks => "side_chain_blocks",
+            Self::SideChainChildren => "side_chain_children",
+            Self::Transactions => "transactions",
+            Self::Transitions => "transitions",
+            #[cfg(test)]
+            Self::Test => "hello world",
+        }
+    }
+
+    pub fn as_bytes(&self) -> &'static [u8] {
+        self.name().as_bytes()
+    }
+
+    ///
+    /// Returns the prefix of the keys of the map, for backends that store every map in a single keyspace,
+    /// as := storage context || length of the name of the map || name of the map.
+    ///
+    pub(crate) fn prefix(&self, context: &[u8]) -> Result<Vec<u8>> {
+        let mut prefix = context.to_vec();
+        bincode::serialize_into(&mut prefix, &(self.as_bytes().len() as u32))?;
+        prefix.extend_from_slice(self.as_bytes());
+        Ok(prefix)
+    }
+
+    /// Returns the encoding of the keys of the map.
+    pub fn key_encoding(&self) -> KeyEncoding {
+        match self {
+            // Block heights are stored in order, so that the latest block is found with a single seek.
+            // Reorgs are journaled in the order they occur.
+            Self::BlockHeights | Self::Reorgs => KeyEncoding::BigEndian,
+            _ => KeyEncoding::LittleEndian,
+        }
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+
+/// An iterator over all key-value pairs in a data map.
+pub struct Iter<K, V> {
+    entries: std::vec::IntoIter<(Vec<u8>, Vec<u8>)>,
+    prefix_len: usize,
+    key_encoding: KeyEncoding,
+    _phantom: PhantomData<(K, V)>,
+}
+
+impl<K: DeserializeOwned, V: DeserializeOwned> Iter<K, V> {
+    pub(super) fn new(entries: Vec<(Vec<u8>, Vec<u8>)>, prefix_len: usize, key_encoding: KeyEncoding) -> Self {
+        Self {
+            entries: entries.into_iter(),
+            prefix_len,
+            key_encoding,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<K: DeserializeOwned, V: DeserializeOwned> Iterator for Iter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.entries.next()?;
+        let key = self.key_encoding.deserialize(&key[self.prefix_len..]).ok()?;
+        let value = bincode::deserialize(&value).ok()?;
+        Some((key, value))
+    }
+}
+
+/// An iterator over the keys of a prefix.
+pub struct Keys<K> {
+    entries: std::vec::IntoIter<(Vec<u8>, Vec<u8>)>,
+    prefix_len: usize,
+    key_encoding: KeyEncoding,
+    _phantom: PhantomData<K>,
+}
+
+impl<K: DeserializeOwned> Keys<K> {
+    pub(super) fn new(entries: Vec<(Vec<u8>, Vec<u8>)>, prefix_len: usize, key_encoding: KeyEncoding) -> Self {
+        Self {
+            entries: entries.into_iter(),
+            prefix_len,
+            key_encoding,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<K: DeserializeOwned> Iterator for Keys<K> {
+    type Item = K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, _) = self.entries.next()?;
+        self.key_encoding.deserialize(&key[self.prefix_len..]).ok()
+    }
+}
+
+/// An iterator over the values of a prefix.
+pub struct Values<V> {
+    entries: std::vec::IntoIter<(Vec<u8>, Vec<u8>)>,
+    _phantom: PhantomData<V>,
+}
+
+impl<V: DeserializeOwned> Values<V> {
+    pub(super) fn new(entries: Vec<(Vec<u8>, Vec<u8>)>) -> Self {
+        Self {
+            entries: entries.into_iter(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<V: DeserializeOwned> Iterator for Values<V> {
+    type Item = V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, value) = self.entries.next()?;
+        bincode::deserialize(&value).ok()
+    }
+}
@@ -0,0 +1,326 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+mod iterator;
+use iterator::*;
+
+mod map;
+pub use map::*;
+
+#[cfg(test)]
+mod tests;
+
+use crate::storage::{prefix_successor, AtomicBatch, KeyEncoding, Map, MapId, MapStats, Storage, StorageStats};
+
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use parking_lot::{Mutex, RwLock};
+use serde::{
+    de::{self, DeserializeOwned},
+    ser::SerializeSeq,
+    Deserializer,
+    Serialize,
+    Serializer,
+};
+use std::{
+    borrow::Borrow,
+    collections::{BTreeMap, HashMap},
+    fmt,
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+/// The in-memory databases of this process, keyed by the storage path they were opened with.
+static DATABASES: Lazy<Mutex<HashMap<PathBuf, Arc<Database>>>> = Lazy::new(Default::default);
+
+///
+/// The contents of an in-memory database, shared by all of its handles.
+///
+#[derive(Debug, Default)]
+pub(crate) struct Database {
+    /// The key-value pairs of the database, sorted by key.
+    entries: RwLock<BTreeMap<Vec<u8>, Vec<u8>>>,
+    /// The number of writes performed on the database.
+    sequence_number: AtomicU64,
+}
+
+impl Database {
+    /// Registers the given database at the given storage path, which must not be in use.
+    fn insert<P: AsRef<Path>>(path: P, database: Database) -> Result<()> {
+        let mut databases = DATABASES.lock();
+        if databases.contains_key(path.as_ref()) {
+            return Err(anyhow!("In-memory storage already exists at {}", path.as_ref().display()));
+        }
+        databases.insert(path.as_ref().to_path_buf(), Arc::new(database));
+        Ok(())
+    }
+
+    /// Returns the value for the given key, if it exists.
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.entries.read().get(key).cloned()
+    }
+
+    /// Writes the given operations := (key, Some(value)) for an insert, (key, None) for a remove, as a single write.
+    fn write(&self, operations: Vec<(Vec<u8>, Option<Vec<u8>>)>) {
+        let mut entries = self.entries.write();
+        for (key, value) in operations {
+            match value {
+                Some(value) => entries.insert(key, value),
+                None => entries.remove(&key),
+            };
+        }
+        self.sequence_number.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Returns a snapshot of all key-value pairs whose key starts with the given prefix.
+    fn snapshot(&self, prefix: &[u8]) -> Vec<(Vec<u8>, Vec<u8>)> {
+        self.snapshot_range(prefix, prefix.to_vec(), None)
+    }
+
+    ///
+    /// Returns a snapshot of all key-value pairs whose key starts with the given prefix,
+    /// from the given inclusive lower bound to the given exclusive upper bound, if any.
+    ///
+    fn snapshot_range(&self, prefix: &[u8], lower_bound: Vec<u8>, upper_bound: Option<Vec<u8>>) -> Vec<(Vec<u8>, Vec<u8>)> {
+        let upper_bound = match upper_bound {
+            Some(upper_bound) => Bound::Excluded(upper_bound),
+            None => Bound::Unbounded,
+        };
+        self.entries
+            .read()
+            .range((Bound::Included(lower_bound), upper_bound))
+            .take_while(|(key, _)| key.starts_with(prefix))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+
+    /// Returns the key-value pair with the greatest key that starts with the given prefix, if one exists.
+    fn last(&self, prefix: &[u8]) -> Option<(Vec<u8>, Vec<u8>)> {
+        let upper_bound = match prefix_successor(prefix) {
+            Some(successor) => Bound::Excluded(successor),
+            None => Bound::Unbounded,
+        };
+        self.entries
+            .read()
+            .range((Bound::Included(prefix.to_vec()), upper_bound))
+            .next_back()
+            .filter(|(key, _)| key.starts_with(prefix))
+            .map(|(key, value)| (key.clone(), value.clone()))
+    }
+
+    /// Returns the number of keys and the size of the keys and values of each map with the given storage context.
+    fn stats(&self, context: &[u8]) -> Result<StorageStats> {
+        let entries = self.entries.read();
+
+        let mut maps = Vec::with_capacity(MapId::ALL.len());
+        for map_id in MapId::ALL {
+            let prefix = map_id.prefix(context)?;
+            let (num_keys, approximate_size) = entries
+                .range(prefix.clone()..)
+                .take_while(|(key, _)| key.starts_with(&prefix))
+                .fold((0u64, 0u64), |(num_keys, size), (key, value)| {
+                    (num_keys + 1, size + (key.len() + value.len()) as u64)
+                });
+            maps.push(MapStats {
+                name: map_id.name().to_string(),
+                num_keys,
+                approximate_size,
+            });
+        }
+
+        Ok(StorageStats {
+            maps,
+            ..Default::default()
+        })
+    }
+}
+
+///
+/// An instance of an in-memory database.
+///
+/// Opening the same path more than once in a process returns a handle to the same database,
+/// which allows a read-only instance to observe the writes of a writable instance.
+/// The contents of the database are lost when the process exits.
+///
+#[derive(Clone)]
+pub struct MemoryDB {
+    database: Arc<Database>,
+    context: Vec<u8>,
+    is_read_only: bool,
+    /// The atomic batch shared by all maps opened from this storage.
+    atomic_batch: AtomicBatch,
+}
+
+impl Storage for MemoryDB {
+    ///
+    /// Opens storage at the given `path` and `context`.
+    ///
+    fn open<P: AsRef<Path>>(path: P, context: u16, is_read_only: bool) -> Result<Self> {
+        let context = context.to_le_bytes();
+        let mut context_bytes = bincode::serialize(&(context.len() as u32))?;
+        context_bytes.extend_from_slice(&context);
+
+        let mut databases = DATABASES.lock();
+        let database = match is_read_only {
+            // A reader may only be opened for an existing database.
+            true => match databases.get(path.as_ref()) {
+                Some(database) => database.clone(),
+                None => return Err(anyhow!("No in-memory storage exists at {}", path.as_ref().display())),
+            },
+            false => databases.entry(path.as_ref().to_path_buf()).or_default().clone(),
+        };
+
+        Ok(MemoryDB {
+            database,
+            context: context_bytes,
+            is_read_only,
+            atomic_batch: Default::default(),
+        })
+    }
+
+    ///
+    /// Opens a map with the given `context` from storage.
+    ///
+    fn open_map<K: Serialize + DeserializeOwned, V: Serialize + DeserializeOwned>(
+        &self,
+        map_id: MapId,
+    ) -> Result<crate::storage::DataMap<K, V>> {
+        Ok(crate::storage::DataMap::Memory(DataMap {
+            database: self.database.clone(),
+            context: map_id.prefix(&self.context)?,
+            storage_context: self.context.clone(),
+            key_encoding: map_id.key_encoding(),
+            is_read_only: self.is_read_only,
+            atomic_batch: self.atomic_batch.clone(),
+            last_seen_sequence_number: Arc::new(AtomicU64::new(self.database.sequence_number.load(Ordering::SeqCst))),
+            _phantom: PhantomData,
+        }))
+    }
+
+    ///
+    /// Imports the given serialized bytes to reconstruct storage.
+    ///
+    fn import<'de, D: Deserializer<'de>>(&self, deserializer: D) -> Result<(), D::Error> {
+        struct MemoryDBVisitor {
+            memory_db: MemoryDB,
+        }
+
+        impl<'de> de::Visitor<'de> for MemoryDBVisitor {
+            type Value = ();
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "a memory db seq")
+            }
+
+            fn visit_seq<A: de::SeqAccess<'de>>(self, mut map: A) -> std::result::Result<(), A::Error> {
+                if self.memory_db.is_read_only {
+                    return Err(de::Error::custom("Storage is in read-only mode"));
+                }
+
+                let mut operations = Vec::new();
+                while let Some((key, value)) = map.next_element::<(Vec<_>, Vec<_>)>()? {
+                    operations.push((key, Some(value)));
+                }
+                self.memory_db.database.write(operations);
+
+                Ok(())
+            }
+        }
+
+        deserializer.deserialize_seq(MemoryDBVisitor { memory_db: self.clone() })?;
+
+        Ok(())
+    }
+
+    ///
+    /// Exports the current state of storage into serialized bytes.
+    ///
+    fn export(&self) -> Result<serde_json::Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    ///
+    /// Restores the checkpoint at the given `checkpoint` path to storage at the given `path`, which must not exist.
+    ///
+    fn restore_checkpoint<P: AsRef<Path>, Q: AsRef<Path>>(checkpoint: P, path: Q) -> Result<()> {
+        let checkpoint = match DATABASES.lock().get(checkpoint.as_ref()) {
+            Some(checkpoint) => checkpoint.clone(),
+            None => return Err(anyhow!("No in-memory checkpoint exists at {}", checkpoint.as_ref().display())),
+        };
+
+        let database = Database {
+            entries: RwLock::new(checkpoint.entries.read().clone()),
+            sequence_number: Default::default(),
+        };
+        Database::insert(path, database)
+    }
+
+    ///
+    /// Returns `true` if storage exists at the given `path`.
+    ///
+    fn exists<P: AsRef<Path>>(path: P) -> bool {
+        DATABASES.lock().contains_key(path.as_ref())
+    }
+
+    ///
+    /// Moves the storage at the given `from` path to the given `to` path, which must not exist.
+    ///
+    fn rename<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> Result<()> {
+        let mut databases = DATABASES.lock();
+        if databases.contains_key(to.as_ref()) {
+            return Err(anyhow!("In-memory storage already exists at {}", to.as_ref().display()));
+        }
+        match databases.remove(from.as_ref()) {
+            Some(database) => {
+                databases.insert(to.as_ref().to_path_buf(), database);
+                Ok(())
+            }
+            None => Err(anyhow!("No in-memory storage exists at {}", from.as_ref().display())),
+        }
+    }
+
+    ///
+    /// Removes the storage at the given `path`, if it exists.
+    ///
+    fn destroy<P: AsRef<Path>>(path: P) -> Result<()> {
+        DATABASES.lock().remove(path.as_ref());
+        Ok(())
+    }
+
+    ///
+    /// Returns the size and health statistics of storage, for each map and for the storage backend.
+    ///
+    fn stats(&self) -> Result<StorageStats> {
+        self.database.stats(&self.context)
+    }
+}
+
+impl Serialize for MemoryDB {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let entries = self.database.entries.read();
+
+        let mut map = serializer.serialize_seq(Some(entries.len()))?;
+        for (key, value) in entries.iter() {
+            map.serialize_element(&(key, value))?;
+        }
+        map.end()
+    }
+}
@@ -0,0 +1,48 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::storage::{memory::MemoryDB, Map, MapId, Storage};
+
+/// Returns a unique storage path, which is never created on the filesystem.
+fn temp_path() -> std::path::PathBuf {
+    format!("snarkos-test-storage-{}", rand::random::<u64>()).into()
+}
+
+#[test]
+fn test_migrate_into() {
+    let storage = MemoryDB::open(temp_path(), 0, false).expect("Failed to open storage");
+    let map = storage.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
+    for i in 0..100u32 {
+        map.insert(&i, &i.to_string()).expect("Failed to insert");
+    }
+
+    // Ensure a migration outside of an atomic batch is rejected.
+    let migrated_map = storage.open_map::<u32, u64>(MapId::Test).expect("Failed to open data map");
+    assert!(map.migrate_into(&migrated_map, |key, value| Ok((key, value.parse()?))).is_err());
+
+    // Migrate the values in place, and shift the keys onto keys that are yet to be migrated.
+    map.start_atomic().expect("Failed to start atomic batch");
+    let num_migrated = map
+        .migrate_into(&migrated_map, |key, value| Ok((key + 1, value.parse::<u64>()? * 2)))
+        .expect("Failed to migrate");
+    map.finish_atomic().expect("Failed to finish atomic batch");
+    assert_eq!(100, num_migrated);
+
+    assert!(!migrated_map.contains_key(&0).expect("Failed to call contains key"));
+    for i in 1..=100u32 {
+        assert_eq!(Some((i as u64 - 1) * 2), migrated_map.get(&i).expect("Failed to get"));
+    }
+}
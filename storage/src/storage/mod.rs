@@ -14,12 +14,127 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
+pub mod memory;
+
 #[cfg(feature = "rocks")]
 pub mod rocksdb;
-#[cfg(feature = "rocks")]
-pub type DataMap<K, V> = crate::storage::rocksdb::DataMap<K, V>;
-#[cfg(feature = "rocks")]
-pub use crate::storage::rocksdb::MapId;
+
+#[cfg(feature = "sled")]
+pub mod sled;
+
+mod data_map;
+pub use data_map::*;
+
+mod map_id;
+pub use map_id::*;
+
+mod stats;
+pub use stats::*;
 
 pub mod traits;
 pub use traits::*;
+
+#[cfg(test)]
+mod tests;
+
+use anyhow::Result;
+use bincode::Options;
+use parking_lot::Mutex;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    ops::{Bound, RangeBounds},
+    sync::Arc,
+};
+
+/// The version of the storage layout, which determines how the keys and values of each map are encoded.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// A list of staged write operations := (key, Some(value)) for an insert, (key, None) for a remove.
+/// The key may carry additional information, such as the map the operation belongs to.
+pub(crate) type AtomicBatch<Key = Vec<u8>> = Arc<Mutex<Option<Vec<(Key, Option<Vec<u8>>)>>>>;
+
+///
+/// The encoding of the keys of a map in storage.
+///
+/// Big-endian keys are ordered in storage by their unsigned integer values, which allows
+/// the latest entries of a map keyed by height to be found with a seek instead of a full scan.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyEncoding {
+    LittleEndian,
+    BigEndian,
+}
+
+impl KeyEncoding {
+    /// Serializes the given key into the given buffer.
+    pub(crate) fn serialize_into<Q: Serialize + ?Sized>(&self, buffer: &mut Vec<u8>, key: &Q) -> Result<()> {
+        match self {
+            Self::LittleEndian => bincode::serialize_into(buffer, key)?,
+            Self::BigEndian => Self::big_endian().serialize_into(buffer, key)?,
+        }
+        Ok(())
+    }
+
+    /// Deserializes a key from the given bytes.
+    pub(crate) fn deserialize<K: DeserializeOwned>(&self, bytes: &[u8]) -> Result<K> {
+        match self {
+            Self::LittleEndian => Ok(bincode::deserialize(bytes)?),
+            Self::BigEndian => Ok(Self::big_endian().deserialize(bytes)?),
+        }
+    }
+
+    /// Returns the given key, serialized and prefixed with the given context.
+    pub(crate) fn key_bytes<Q: Serialize + ?Sized>(&self, context: &[u8], key: &Q) -> Result<Vec<u8>> {
+        let mut key_buf = context.to_vec();
+        key_buf.reserve(bincode::serialized_size(&key)? as usize);
+        self.serialize_into(&mut key_buf, key)?;
+        Ok(key_buf)
+    }
+
+    ///
+    /// Returns the serialized bounds of the given range of keys, prefixed with the given context,
+    /// as := (inclusive lower bound, exclusive upper bound). An upper bound of `None` is unbounded.
+    ///
+    pub(crate) fn range_bytes<K: Serialize, R: RangeBounds<K>>(&self, context: &[u8], range: &R) -> Result<(Vec<u8>, Option<Vec<u8>>)> {
+        // The smallest byte string greater than a given byte string is the byte string followed by a zero byte.
+        let lower_bound = match range.start_bound() {
+            Bound::Included(key) => self.key_bytes(context, key)?,
+            Bound::Excluded(key) => {
+                let mut key_buf = self.key_bytes(context, key)?;
+                key_buf.push(0u8);
+                key_buf
+            }
+            Bound::Unbounded => context.to_vec(),
+        };
+        let upper_bound = match range.end_bound() {
+            Bound::Included(key) => {
+                let mut key_buf = self.key_bytes(context, key)?;
+                key_buf.push(0u8);
+                Some(key_buf)
+            }
+            Bound::Excluded(key) => Some(self.key_bytes(context, key)?),
+            Bound::Unbounded => prefix_successor(context),
+        };
+        Ok((lower_bound, upper_bound))
+    }
+
+    /// Returns the bincode options for big-endian keys, with the same fixed-size integers as `bincode::serialize`.
+    fn big_endian() -> impl Options {
+        bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .with_big_endian()
+            .allow_trailing_bytes()
+    }
+}
+
+/// Returns the smallest byte string that is greater than every byte string starting with the given prefix, if one exists.
+pub(crate) fn prefix_successor(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut successor = prefix.to_vec();
+    while let Some(last_byte) = successor.pop() {
+        if last_byte < u8::MAX {
+            successor.push(last_byte + 1);
+            return Some(successor);
+        }
+    }
+    None
+}
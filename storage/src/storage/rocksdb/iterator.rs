@@ -20,14 +20,24 @@ use super::*;
 pub struct Iter<'a, K, V> {
     db_iter: rocksdb::DBRawIterator<'a>,
     prefix: Vec<u8>,
+    key_encoding: KeyEncoding,
+    /// The exclusive upper bound of the keys of the iterator, if any.
+    upper_bound: Option<Vec<u8>>,
     _phantom: PhantomData<(K, V)>,
 }
 
 impl<'a, K: DeserializeOwned, V: DeserializeOwned> Iter<'a, K, V> {
-    pub(super) fn new(db_iter: rocksdb::DBRawIterator<'a>, prefix: Vec<u8>) -> Self {
+    pub(super) fn new(
+        db_iter: rocksdb::DBRawIterator<'a>,
+        prefix: Vec<u8>,
+        key_encoding: KeyEncoding,
+        upper_bound: Option<Vec<u8>>,
+    ) -> Self {
         Self {
             db_iter,
             prefix,
+            key_encoding,
+            upper_bound,
             _phantom: PhantomData,
         }
     }
@@ -42,7 +52,8 @@ impl<'a, K: DeserializeOwned, V: DeserializeOwned> Iterator for Iter<'a, K, V> {
                 .db_iter
                 .key()
                 .and_then(|k| if k.starts_with(&self.prefix) { Some(k) } else { None })
-                .map(|k| bincode::deserialize(&k[self.prefix.len()..]).ok())
+                .filter(|k| self.upper_bound.as_ref().map_or(true, |upper_bound| *k < &upper_bound[..]))
+                .map(|k| self.key_encoding.deserialize(&k[self.prefix.len()..]).ok())
             {
                 Some(key) => key,
                 None => None,
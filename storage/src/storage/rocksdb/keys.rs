@@ -20,14 +20,16 @@ use super::*;
 pub struct Keys<'a, K> {
     db_iter: rocksdb::DBRawIterator<'a>,
     prefix: Vec<u8>,
+    key_encoding: KeyEncoding,
     _phantom: PhantomData<K>,
 }
 
 impl<'a, K: DeserializeOwned> Keys<'a, K> {
-    pub(crate) fn new(db_iter: rocksdb::DBRawIterator<'a>, prefix: Vec<u8>) -> Self {
+    pub(crate) fn new(db_iter: rocksdb::DBRawIterator<'a>, prefix: Vec<u8>, key_encoding: KeyEncoding) -> Self {
         Self {
             db_iter,
             prefix,
+            key_encoding,
             _phantom: PhantomData,
         }
     }
@@ -42,7 +44,7 @@ impl<'a, K: DeserializeOwned> Iterator for Keys<'a, K> {
                 .db_iter
                 .key()
                 .and_then(|k| if k.starts_with(&self.prefix) { Some(k) } else { None })
-                .map(|k| bincode::deserialize(&k[self.prefix.len()..]).ok())
+                .map(|k| self.key_encoding.deserialize(&k[self.prefix.len()..]).ok())
             {
                 Some(key) => key,
                 None => None,
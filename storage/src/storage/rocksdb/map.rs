@@ -16,47 +16,59 @@
 
 use super::*;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum MapId {
-    BlockHeaders,
-    BlockHeights,
-    BlockTransactions,
-    Commitments,
-    LedgerRoots,
-    Records,
-    SerialNumbers,
-    Transactions,
-    Transitions,
-    #[cfg(test)]
-    Test,
-}
-
-impl MapId {
-    pub fn as_bytes(&self) -> &'static [u8] {
-        match self {
-            Self::BlockHeaders => b"block_headers",
-            Self::BlockHeights => b"block_heights",
-            Self::BlockTransactions => b"block_transactions",
-            Self::Commitments => b"commitments",
-            Self::LedgerRoots => b"ledger_roots",
-            Self::Records => b"records",
-            Self::SerialNumbers => b"serial_numbers",
-            Self::Transactions => b"transactions",
-            Self::Transitions => b"transitions",
-            #[cfg(test)]
-            Self::Test => b"hello world",
-        }
-    }
-}
-
 #[derive(Clone, Debug)]
 pub struct DataMap<K: Serialize + DeserializeOwned, V: Serialize + DeserializeOwned> {
     pub(super) rocksdb: Arc<rocksdb::DB>,
+    pub(super) map_id: MapId,
     pub(super) context: Vec<u8>,
+    pub(super) key_encoding: KeyEncoding,
     pub(super) is_read_only: bool,
+    pub(super) atomic_batch: AtomicBatch<(MapId, Vec<u8>)>,
     pub(super) _phantom: PhantomData<(K, V)>,
 }
 
+impl<K: Serialize + DeserializeOwned, V: Serialize + DeserializeOwned> DataMap<K, V> {
+    /// Returns the column family of the map.
+    fn column_family(&self) -> &rocksdb::ColumnFamily {
+        // The column family is ensured to exist when the map is opened, and is never dropped.
+        self.rocksdb
+            .cf_handle(self.map_id.name())
+            .expect("The column family of a map must exist once the map is opened")
+    }
+
+    /// Returns this map with the given key encoding, in order to migrate the keys of the map.
+    pub(crate) fn with_key_encoding(&self, key_encoding: KeyEncoding) -> Self {
+        Self {
+            rocksdb: self.rocksdb.clone(),
+            map_id: self.map_id,
+            context: self.context.clone(),
+            key_encoding,
+            is_read_only: self.is_read_only,
+            atomic_batch: self.atomic_batch.clone(),
+            _phantom: PhantomData,
+        }
+    }
+
+    ///
+    /// Creates a checkpoint of the storage of this map at the given path, which must not exist.
+    /// The files of the checkpoint are hard-linked to the files of the storage where possible.
+    ///
+    pub(crate) fn create_checkpoint<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        // If the storage is in read-only mode, this method cannot be called.
+        if self.is_read_only {
+            return Err(anyhow!("Storage must be writable to create a checkpoint"));
+        }
+
+        rocksdb::checkpoint::Checkpoint::new(&self.rocksdb)?.create_checkpoint(path)?;
+        Ok(())
+    }
+
+    /// Returns the size and health statistics of the storage of this map.
+    pub(crate) fn storage_stats(&self) -> Result<StorageStats> {
+        RocksDB::stats_of(&self.rocksdb)
+    }
+}
+
 impl<'a, K: Serialize + DeserializeOwned, V: Serialize + DeserializeOwned> Map<'a, K, V> for DataMap<K, V> {
     type Iterator = Iter<'a, K, V>;
     type Keys = Keys<'a, K>;
@@ -81,10 +93,8 @@ impl<'a, K: Serialize + DeserializeOwned, V: Serialize + DeserializeOwned> Map<'
         K: Borrow<Q>,
         Q: Serialize + ?Sized,
     {
-        let mut key_buf = self.context.clone();
-        key_buf.reserve(bincode::serialized_size(&key)? as usize);
-        bincode::serialize_into(&mut key_buf, &key)?;
-        match self.rocksdb.get(&key_buf)? {
+        let key_buf = self.key_encoding.key_bytes(&self.context, key)?;
+        match self.rocksdb.get_cf(self.column_family(), &key_buf)? {
             Some(data) => Ok(Some(bincode::deserialize(&data)?)),
             None => Ok(None),
         }
@@ -98,12 +108,14 @@ impl<'a, K: Serialize + DeserializeOwned, V: Serialize + DeserializeOwned> Map<'
         K: Borrow<Q>,
         Q: Serialize + ?Sized,
     {
-        let mut key_buf = self.context.clone();
-        key_buf.reserve(bincode::serialized_size(&key)? as usize);
-        bincode::serialize_into(&mut key_buf, &key)?;
+        let key_buf = self.key_encoding.key_bytes(&self.context, key)?;
         let value_buf = bincode::serialize(value)?;
 
-        self.rocksdb.put(&key_buf, &value_buf)?;
+        // If an atomic batch is in progress, stage the operation instead of writing it.
+        match &mut *self.atomic_batch.lock() {
+            Some(batch) => batch.push(((self.map_id, key_buf), Some(value_buf))),
+            None => self.rocksdb.put_cf(self.column_family(), &key_buf, &value_buf)?,
+        }
         Ok(())
     }
 
@@ -115,11 +127,13 @@ impl<'a, K: Serialize + DeserializeOwned, V: Serialize + DeserializeOwned> Map<'
         K: Borrow<Q>,
         Q: Serialize + ?Sized,
     {
-        let mut key_buf = self.context.clone();
-        key_buf.reserve(bincode::serialized_size(&key)? as usize);
-        bincode::serialize_into(&mut key_buf, &key)?;
+        let key_buf = self.key_encoding.key_bytes(&self.context, key)?;
 
-        self.rocksdb.delete(&key_buf)?;
+        // If an atomic batch is in progress, stage the operation instead of writing it.
+        match &mut *self.atomic_batch.lock() {
+            Some(batch) => batch.push(((self.map_id, key_buf), None)),
+            None => self.rocksdb.delete_cf(self.column_family(), &key_buf)?,
+        }
         Ok(())
     }
 
@@ -127,32 +141,128 @@ impl<'a, K: Serialize + DeserializeOwned, V: Serialize + DeserializeOwned> Map<'
     /// Returns an iterator visiting each key-value pair in the map.
     ///
     fn iter(&'a self) -> Self::Iterator {
-        let mut db_iter = self.rocksdb.raw_iterator();
+        let mut db_iter = self.rocksdb.raw_iterator_cf(self.column_family());
         db_iter.seek(&self.context);
 
-        Iter::new(db_iter, self.context.clone())
+        Iter::new(db_iter, self.context.clone(), self.key_encoding, None)
     }
 
     ///
     /// Returns an iterator over each key in the map.
     ///
     fn keys(&'a self) -> Self::Keys {
-        let mut db_iter = self.rocksdb.raw_iterator();
+        let mut db_iter = self.rocksdb.raw_iterator_cf(self.column_family());
         db_iter.seek(&self.context);
 
-        Keys::new(db_iter, self.context.clone())
+        Keys::new(db_iter, self.context.clone(), self.key_encoding)
     }
 
     ///
     /// Returns an iterator over each value in the map.
     ///
     fn values(&'a self) -> Self::Values {
-        let mut db_iter = self.rocksdb.raw_iterator();
+        let mut db_iter = self.rocksdb.raw_iterator_cf(self.column_family());
         db_iter.seek(&self.context);
 
         Values::new(db_iter, self.context.clone())
     }
 
+    ///
+    /// Returns an iterator visiting each key-value pair in the given range of keys, in the order of
+    /// their serialized keys. This seeks to the start of the range, instead of scanning the map.
+    ///
+    fn range<R: RangeBounds<K>>(&'a self, range: R) -> Result<Self::Iterator> {
+        let (lower_bound, upper_bound) = self.key_encoding.range_bytes(&self.context, &range)?;
+
+        let mut db_iter = self.rocksdb.raw_iterator_cf(self.column_family());
+        db_iter.seek(&lower_bound);
+
+        Ok(Iter::new(db_iter, self.context.clone(), self.key_encoding, upper_bound))
+    }
+
+    ///
+    /// Returns the key-value pair with the greatest serialized key in the map, if the map is not empty.
+    ///
+    fn last(&self) -> Result<Option<(K, V)>> {
+        let mut db_iter = self.rocksdb.raw_iterator_cf(self.column_family());
+
+        // Seek to the last key before the keys of the next context, if there is one.
+        match prefix_successor(&self.context) {
+            Some(successor) => {
+                db_iter.seek_for_prev(&successor);
+                // The seek lands on the successor itself, if it is a key in the column family.
+                if db_iter.key() == Some(&successor[..]) {
+                    db_iter.prev();
+                }
+            }
+            None => db_iter.seek_to_last(),
+        }
+
+        match (db_iter.key(), db_iter.value()) {
+            (Some(key), Some(value)) if key.starts_with(&self.context) => Ok(Some((
+                self.key_encoding.deserialize(&key[self.context.len()..])?,
+                bincode::deserialize(value)?,
+            ))),
+            _ => Ok(None),
+        }
+    }
+
+    ///
+    /// Begins an atomic batch of operations. Until the batch is finished or aborted, all inserts and
+    /// removes on every map opened from the same storage are staged instead of being written.
+    ///
+    fn start_atomic(&self) -> Result<()> {
+        let mut atomic_batch = self.atomic_batch.lock();
+        // Nested batches are rejected, as their operations would silently be merged into the outer batch.
+        if atomic_batch.is_some() {
+            return Err(anyhow!("Attempted to start an atomic batch while another one is in progress"));
+        }
+        *atomic_batch = Some(Vec::new());
+        Ok(())
+    }
+
+    ///
+    /// Returns `true` if an atomic batch of operations is in progress.
+    ///
+    fn is_atomic_in_progress(&self) -> bool {
+        self.atomic_batch.lock().is_some()
+    }
+
+    ///
+    /// Discards all operations staged since the atomic batch was started.
+    ///
+    fn abort_atomic(&self) {
+        *self.atomic_batch.lock() = None;
+    }
+
+    ///
+    /// Writes all operations staged since the atomic batch was started to storage, as a single atomic write.
+    ///
+    fn finish_atomic(&self) -> Result<()> {
+        // Take the staged operations, which ends the atomic batch.
+        let operations = match self.atomic_batch.lock().take() {
+            Some(operations) => operations,
+            None => return Err(anyhow!("Attempted to finish an atomic batch that was never started")),
+        };
+
+        // Prepare the write batch, preserving the order in which the operations were staged.
+        let mut batch = rocksdb::WriteBatch::default();
+        for ((map_id, key), value) in operations {
+            let column_family = match self.rocksdb.cf_handle(map_id.name()) {
+                Some(column_family) => column_family,
+                None => return Err(anyhow!("Missing the column family for {}", map_id.name())),
+            };
+            match value {
+                Some(value) => batch.put_cf(column_family, key, value),
+                None => batch.delete_cf(column_family, key),
+            }
+        }
+
+        // Write the batch to storage.
+        self.rocksdb.write(batch)?;
+        Ok(())
+    }
+
     ///
     /// Performs a refresh operation for implementations of `Map` that perform periodic operations.
     /// This method is implemented here for RocksDB to catch up a reader (secondary) database.
@@ -23,15 +23,18 @@ use keys::*;
 mod map;
 pub use map::*;
 
+mod options;
+pub use options::*;
+
 mod values;
 use values::*;
 
 #[cfg(test)]
 mod tests;
 
-use crate::storage::{Map, Storage};
+use crate::storage::{prefix_successor, AtomicBatch, CompactionStats, KeyEncoding, Map, MapId, MapStats, Storage, StorageStats};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use serde::{
     de::{self, DeserializeOwned},
     ser::SerializeSeq,
@@ -39,7 +42,7 @@ use serde::{
     Serialize,
     Serializer,
 };
-use std::{borrow::Borrow, fmt, marker::PhantomData, path::Path, sync::Arc};
+use std::{borrow::Borrow, fmt, marker::PhantomData, ops::RangeBounds, path::Path, sync::Arc};
 
 ///
 /// An instance of a RocksDB database.
@@ -49,6 +52,8 @@ pub struct RocksDB {
     rocksdb: Arc<rocksdb::DB>,
     context: Vec<u8>,
     is_read_only: bool,
+    /// The atomic batch shared by all maps opened from this storage.
+    atomic_batch: AtomicBatch<(MapId, Vec<u8>)>,
 }
 
 impl Storage for RocksDB {
@@ -68,14 +73,36 @@ impl Storage for RocksDB {
             true => {
                 // Construct the directory paths.
                 let reader = path.as_ref().join("reader");
+                // A reader opens the column families that were created by the writer.
+                let column_families = rocksdb::DB::list_cf(&options, &primary)?;
                 // Open a secondary reader for the primary rocksdb.
-                let rocksdb = rocksdb::DB::open_as_secondary(&options, &primary, &reader)?;
+                let rocksdb = rocksdb::DB::open_cf_as_secondary(&options, &primary, &reader, column_families)?;
                 Arc::new(rocksdb)
             }
             false => {
                 options.increase_parallelism(2);
                 options.create_if_missing(true);
-                Arc::new(rocksdb::DB::open(&options, &primary)?)
+                options.create_missing_column_families(true);
+
+                // Open a column family for each map, with the configured options of the map.
+                let map_options = Self::options();
+                let mut column_families = Vec::with_capacity(MapId::ALL.len());
+                for map_id in MapId::ALL {
+                    let options = map_options.to_column_family_options(*map_id)?;
+                    column_families.push(rocksdb::ColumnFamilyDescriptor::new(map_id.name(), options));
+                }
+                // All existing column families must be opened, including those of maps that are no longer in use.
+                if let Ok(existing_column_families) = rocksdb::DB::list_cf(&options, &primary) {
+                    for name in existing_column_families {
+                        if !MapId::ALL.iter().any(|map_id| map_id.name() == name) {
+                            column_families.push(rocksdb::ColumnFamilyDescriptor::new(name, rocksdb::Options::default()));
+                        }
+                    }
+                }
+
+                let rocksdb = rocksdb::DB::open_cf_descriptors(&options, &primary, column_families)?;
+                Self::migrate_prefix_layout(&rocksdb, &context_bytes)?;
+                Arc::new(rocksdb)
             }
         };
 
@@ -83,27 +110,31 @@ impl Storage for RocksDB {
             rocksdb,
             context: context_bytes,
             is_read_only,
+            atomic_batch: Default::default(),
         })
     }
 
     ///
     /// Opens a map with the given `context` from storage.
     ///
-    fn open_map<K: Serialize + DeserializeOwned, V: Serialize + DeserializeOwned>(&self, map_id: MapId) -> Result<DataMap<K, V>> {
-        // Convert the new context into bytes.
-        let new_context = map_id.as_bytes();
-
-        // Combine contexts to create a new scope.
-        let mut context_bytes = self.context.clone();
-        bincode::serialize_into(&mut context_bytes, &(new_context.len() as u32))?;
-        context_bytes.extend_from_slice(new_context);
+    fn open_map<K: Serialize + DeserializeOwned, V: Serialize + DeserializeOwned>(
+        &self,
+        map_id: MapId,
+    ) -> Result<crate::storage::DataMap<K, V>> {
+        // Ensure the column family of the map exists, as a reader may only open existing column families.
+        if self.rocksdb.cf_handle(map_id.name()).is_none() {
+            return Err(anyhow!("Missing the column family for {} in storage", map_id.name()));
+        }
 
-        Ok(DataMap {
+        Ok(crate::storage::DataMap::RocksDB(DataMap {
             rocksdb: self.rocksdb.clone(),
-            context: context_bytes,
+            map_id,
+            context: self.context.clone(),
+            key_encoding: map_id.key_encoding(),
             is_read_only: self.is_read_only,
+            atomic_batch: self.atomic_batch.clone(),
             _phantom: PhantomData,
-        })
+        }))
     }
 
     ///
@@ -122,8 +153,15 @@ impl Storage for RocksDB {
             }
 
             fn visit_seq<A: de::SeqAccess<'de>>(self, mut map: A) -> std::result::Result<(), A::Error> {
-                while let Some((key, value)) = map.next_element::<(Vec<_>, Vec<_>)>()? {
-                    self.rocksdb.rocksdb.put(&key, &value).map_err(serde::de::Error::custom)?;
+                while let Some((name, key, value)) = map.next_element::<(String, Vec<u8>, Vec<u8>)>()? {
+                    let column_family = match self.rocksdb.rocksdb.cf_handle(&name) {
+                        Some(column_family) => column_family,
+                        None => return Err(de::Error::custom(format!("Missing the column family for {}", name))),
+                    };
+                    self.rocksdb
+                        .rocksdb
+                        .put_cf(column_family, &key, &value)
+                        .map_err(serde::de::Error::custom)?;
                 }
 
                 Ok(())
@@ -141,19 +179,187 @@ impl Storage for RocksDB {
     fn export(&self) -> Result<serde_json::Value> {
         Ok(serde_json::to_value(&self)?)
     }
+
+    ///
+    /// Restores the checkpoint at the given `checkpoint` path to storage at the given `path`, which must not exist.
+    ///
+    fn restore_checkpoint<P: AsRef<Path>, Q: AsRef<Path>>(checkpoint: P, path: Q) -> Result<()> {
+        let (checkpoint, path) = (checkpoint.as_ref(), path.as_ref());
+        if !checkpoint.is_dir() {
+            return Err(anyhow!("No checkpoint exists at {}", checkpoint.display()));
+        }
+        if path.exists() {
+            return Err(anyhow!("Storage already exists at {}", path.display()));
+        }
+
+        // A checkpoint is a complete database with a flat layout, so it is restored by copying its files.
+        // The files are copied instead of hard-linked, so the checkpoint remains intact once storage is written to.
+        std::fs::create_dir_all(path)?;
+        for entry in std::fs::read_dir(checkpoint)? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                std::fs::copy(entry.path(), path.join(entry.file_name()))?;
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Returns `true` if storage exists at the given `path`.
+    ///
+    fn exists<P: AsRef<Path>>(path: P) -> bool {
+        path.as_ref().exists()
+    }
+
+    ///
+    /// Moves the storage at the given `from` path to the given `to` path, which must not exist.
+    /// The storage must not be open while it is moved.
+    ///
+    fn rename<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> Result<()> {
+        let (from, to) = (from.as_ref(), to.as_ref());
+        if !from.is_dir() {
+            return Err(anyhow!("No storage exists at {}", from.display()));
+        }
+        if to.exists() {
+            return Err(anyhow!("Storage already exists at {}", to.display()));
+        }
+        std::fs::rename(from, to)?;
+        Ok(())
+    }
+
+    ///
+    /// Removes the storage at the given `path`, if it exists. The storage must not be open while it is removed.
+    ///
+    fn destroy<P: AsRef<Path>>(path: P) -> Result<()> {
+        if path.as_ref().exists() {
+            std::fs::remove_dir_all(path)?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Returns the size and health statistics of storage, for each map and for the storage backend.
+    ///
+    fn stats(&self) -> Result<StorageStats> {
+        Self::stats_of(&self.rocksdb)
+    }
+}
+
+impl RocksDB {
+    ///
+    /// Returns the size and health statistics of the given database. The statistics are estimated
+    /// from the properties of each column family, instead of scanning each map.
+    ///
+    fn stats_of(rocksdb: &rocksdb::DB) -> Result<StorageStats> {
+        let property = |column_family: &rocksdb::ColumnFamily, name: &str| -> Result<u64> {
+            Ok(rocksdb.property_int_value_cf(column_family, name)?.unwrap_or(0))
+        };
+
+        let mut maps = Vec::with_capacity(MapId::ALL.len());
+        let mut compaction = CompactionStats::default();
+        for map_id in MapId::ALL {
+            // A reader may be missing the column families of maps that were added since its writer last ran.
+            let column_family = match rocksdb.cf_handle(map_id.name()) {
+                Some(column_family) => column_family,
+                None => continue,
+            };
+
+            // The writes held in the memtables of the map are included in its size.
+            let unflushed_bytes = property(column_family, "rocksdb.cur-size-all-mem-tables")?;
+            maps.push(MapStats {
+                name: map_id.name().to_string(),
+                num_keys: property(column_family, "rocksdb.estimate-num-keys")?,
+                approximate_size: property(column_family, "rocksdb.total-sst-files-size")? + unflushed_bytes,
+            });
+
+            compaction.pending_compaction_bytes += property(column_family, "rocksdb.estimate-pending-compaction-bytes")?;
+            compaction.unflushed_bytes += unflushed_bytes;
+            compaction.pending_flushes += property(column_family, "rocksdb.mem-table-flush-pending")?;
+            compaction.pending_compactions += property(column_family, "rocksdb.compaction-pending")?;
+        }
+
+        // The running compactions and flushes, and the write stop, are reported for the whole database.
+        compaction.running_compactions = rocksdb.property_int_value("rocksdb.num-running-compactions")?.unwrap_or(0);
+        compaction.running_flushes = rocksdb.property_int_value("rocksdb.num-running-flushes")?.unwrap_or(0);
+        compaction.is_write_stopped = rocksdb.property_int_value("rocksdb.is-write-stopped")?.unwrap_or(0) != 0;
+
+        Ok(StorageStats {
+            maps,
+            compaction: Some(compaction),
+            reader_lag: None,
+        })
+    }
+
+    ///
+    /// Moves the entries of each map from the default column family, where each map was distinguished
+    /// by a prefix of its name, into the column family of the map. The entries are moved in atomic batches,
+    /// so an interrupted migration resumes the next time storage is opened.
+    ///
+    fn migrate_prefix_layout(rocksdb: &rocksdb::DB, context: &[u8]) -> Result<()> {
+        const BATCH_SIZE: usize = 10_000;
+
+        for map_id in MapId::ALL {
+            let column_family = match rocksdb.cf_handle(map_id.name()) {
+                Some(column_family) => column_family,
+                None => return Err(anyhow!("Missing the column family for {}", map_id.name())),
+            };
+
+            // Construct the prefix of the map in the default column family.
+            let prefix = map_id.prefix(context)?;
+
+            let mut iterator = rocksdb.raw_iterator();
+            iterator.seek(&prefix);
+
+            let mut batch = rocksdb::WriteBatch::default();
+            let mut num_migrated = 0usize;
+            while let (Some(key), Some(value)) = (iterator.key(), iterator.value()) {
+                if !key.starts_with(&prefix) {
+                    break;
+                }
+
+                // The key in the column family retains the context, without the name of the map.
+                let mut new_key = context.to_vec();
+                new_key.extend_from_slice(&key[prefix.len()..]);
+                batch.put_cf(column_family, new_key, value);
+                batch.delete(key);
+                num_migrated += 1;
+
+                if num_migrated % BATCH_SIZE == 0 {
+                    rocksdb.write(std::mem::take(&mut batch))?;
+                    debug!("Migrated {} entries of {} into its own column family", num_migrated, map_id.name());
+                }
+                iterator.next();
+            }
+
+            if !batch.is_empty() {
+                rocksdb.write(batch)?;
+            }
+            if num_migrated > 0 {
+                info!("Migrated {} entries of {} into its own column family", num_migrated, map_id.name());
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Serialize for RocksDB {
     fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
-        let mut iterator = self.rocksdb.raw_iterator();
-        iterator.seek_to_first();
-
         let mut map = serializer.serialize_seq(None)?;
-        while iterator.valid() {
-            if let (Some(key), Some(value)) = (iterator.key(), iterator.value()) {
-                map.serialize_element(&(key, value))?;
+        for map_id in MapId::ALL {
+            // A reader may be missing the column families of maps that were added since its writer last ran.
+            let column_family = match self.rocksdb.cf_handle(map_id.name()) {
+                Some(column_family) => column_family,
+                None => continue,
+            };
+
+            let mut iterator = self.rocksdb.raw_iterator_cf(column_family);
+            iterator.seek_to_first();
+            while iterator.valid() {
+                if let (Some(key), Some(value)) = (iterator.key(), iterator.value()) {
+                    map.serialize_element(&(map_id.name(), key, value))?;
+                }
+                iterator.next();
             }
-            iterator.next();
         }
         map.end()
     }
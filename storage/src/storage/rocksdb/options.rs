@@ -0,0 +1,188 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// The options used to open the column families of RocksDB storage.
+static OPTIONS: Lazy<RwLock<RocksDBOptions>> = Lazy::new(Default::default);
+
+///
+/// The compression algorithm of a map in RocksDB.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    None,
+    Lz4,
+    Zstd,
+}
+
+impl From<Compression> for rocksdb::DBCompressionType {
+    fn from(compression: Compression) -> Self {
+        match compression {
+            Compression::None => Self::None,
+            Compression::Lz4 => Self::Lz4,
+            Compression::Zstd => Self::Zstd,
+        }
+    }
+}
+
+///
+/// The options of the column family of a map in RocksDB.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MapOptions {
+    /// The size of the block cache in bytes.
+    pub block_cache_size: usize,
+    /// The compression algorithm of the map.
+    pub compression: Compression,
+    /// The number of bloom filter bits per key, if bloom filters are enabled.
+    pub bloom_filter_bits_per_key: Option<i32>,
+    /// The size of each write buffer in bytes.
+    pub write_buffer_size: usize,
+}
+
+impl Default for MapOptions {
+    fn default() -> Self {
+        Self {
+            block_cache_size: 8 * 1024 * 1024,
+            compression: Compression::Lz4,
+            bloom_filter_bits_per_key: None,
+            write_buffer_size: 64 * 1024 * 1024,
+        }
+    }
+}
+
+impl MapOptions {
+    /// Returns the RocksDB options for the column family of the map.
+    fn to_column_family_options(&self) -> Result<rocksdb::Options> {
+        let mut table_options = rocksdb::BlockBasedOptions::default();
+        table_options.set_block_cache(&rocksdb::Cache::new_lru_cache(self.block_cache_size)?);
+        if let Some(bits_per_key) = self.bloom_filter_bits_per_key {
+            table_options.set_bloom_filter(bits_per_key, false);
+        }
+
+        let mut options = rocksdb::Options::default();
+        options.set_block_based_table_factory(&table_options);
+        options.set_compression_type(self.compression.into());
+        options.set_write_buffer_size(self.write_buffer_size);
+        Ok(options)
+    }
+}
+
+///
+/// The options of each map in RocksDB, keyed by the name of the map.
+///
+/// These options may be loaded from a JSON file of the form `{ "transitions": { "compression": "zstd" } }`,
+/// where each listed map replaces the default options of that map, and omitted fields take the values
+/// of `MapOptions::default`.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RocksDBOptions {
+    maps: BTreeMap<String, MapOptions>,
+}
+
+impl Default for RocksDBOptions {
+    fn default() -> Self {
+        let mut maps = BTreeMap::new();
+
+        // Large maps that are rarely read are compressed more heavily.
+        for map_id in [
+            MapId::BlockTransactions,
+            MapId::RecordProofs,
+            MapId::Transactions,
+            MapId::Transitions,
+        ]
+        .iter()
+        {
+            let options = MapOptions {
+                compression: Compression::Zstd,
+                ..Default::default()
+            };
+            maps.insert(map_id.name().to_string(), options);
+        }
+
+        // Maps that are frequently queried by key use bloom filters to speed up lookups.
+        for map_id in [
+            MapId::BlockHeights,
+            MapId::BlockHeaders,
+            MapId::LedgerRoots,
+            MapId::SerialNumbers,
+            MapId::Commitments,
+        ]
+        .iter()
+        {
+            let options = MapOptions {
+                block_cache_size: 32 * 1024 * 1024,
+                bloom_filter_bits_per_key: Some(10),
+                ..Default::default()
+            };
+            maps.insert(map_id.name().to_string(), options);
+        }
+
+        Self { maps }
+    }
+}
+
+impl RocksDBOptions {
+    ///
+    /// Loads the options from the JSON file at the given path, on top of the default options.
+    ///
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = std::fs::File::open(path)?;
+        let overrides: BTreeMap<String, MapOptions> = serde_json::from_reader(std::io::BufReader::new(file))?;
+
+        let mut options = Self::default();
+        for (name, map_options) in overrides {
+            if !MapId::ALL.iter().any(|map_id| map_id.name() == name) {
+                return Err(anyhow!("Unknown map '{}' in the RocksDB options", name));
+            }
+            options.maps.insert(name, map_options);
+        }
+        Ok(options)
+    }
+
+    /// Returns the options of the given map.
+    pub fn get(&self, map_id: MapId) -> MapOptions {
+        self.maps.get(map_id.name()).cloned().unwrap_or_default()
+    }
+
+    /// Returns the RocksDB options for the column family of the given map.
+    pub(super) fn to_column_family_options(&self, map_id: MapId) -> Result<rocksdb::Options> {
+        self.get(map_id).to_column_family_options()
+    }
+}
+
+impl RocksDB {
+    ///
+    /// Sets the options used to open the column families of RocksDB storage from here on.
+    ///
+    pub fn configure(options: RocksDBOptions) {
+        *OPTIONS.write() = options;
+    }
+
+    /// Returns the options used to open the column families of RocksDB storage.
+    pub(super) fn options() -> RocksDBOptions {
+        OPTIONS.read().clone()
+    }
+}
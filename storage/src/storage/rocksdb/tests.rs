@@ -14,100 +14,62 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::storage::{rocksdb::RocksDB, Map, MapId, Storage};
+use crate::storage::{
+    rocksdb::{Compression, MapOptions, RocksDB, RocksDBOptions},
+    Map,
+    MapId,
+    Storage,
+};
 
 fn temp_dir() -> std::path::PathBuf {
     tempfile::tempdir().expect("Failed to open temporary directory").into_path()
 }
 
 #[test]
-fn test_open() {
-    let _storage = RocksDB::open(temp_dir(), 0, false).expect("Failed to open storage");
-}
-
-#[test]
-fn test_open_map() {
-    let storage = RocksDB::open(temp_dir(), 0, false).expect("Failed to open storage");
-    storage.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
-}
-
-#[test]
-fn test_insert_and_contains_key() {
-    let storage = RocksDB::open(temp_dir(), 0, false).expect("Failed to open storage");
-    let map = storage.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
-
-    map.insert(&123456789, &"123456789".to_string()).expect("Failed to insert");
-    assert!(map.contains_key(&123456789).expect("Failed to call contains key"));
-    assert!(!map.contains_key(&000000000).expect("Failed to call contains key"));
-}
-
-#[test]
-fn test_insert_and_get() {
-    let storage = RocksDB::open(temp_dir(), 0, false).expect("Failed to open storage");
-    let map = storage.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
-
-    map.insert(&123456789, &"123456789".to_string()).expect("Failed to insert");
-    assert_eq!(Some("123456789".to_string()), map.get(&123456789).expect("Failed to get"));
-    assert_eq!(None, map.get(&000000000).expect("Failed to get"));
-}
-
-#[test]
-fn test_insert_and_remove() {
-    let storage = RocksDB::open(temp_dir(), 0, false).expect("Failed to open storage");
-    let map = storage.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
-
-    map.insert(&123456789, &"123456789".to_string()).expect("Failed to insert");
-    assert!(map.get(&123456789).expect("Failed to get").is_some());
-
-    map.remove(&123456789).expect("Failed to remove");
-    assert!(map.get(&123456789).expect("Failed to get").is_none());
-}
-
-#[test]
-fn test_insert_and_iter() {
-    let storage = RocksDB::open(temp_dir(), 0, false).expect("Failed to open storage");
-    let map = storage.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
-    map.insert(&123456789, &"123456789".to_string()).expect("Failed to insert");
-
-    let mut iter = map.iter();
-    assert_eq!(Some((123456789, "123456789".to_string())), iter.next());
-    assert_eq!(None, iter.next());
-}
-
-#[test]
-fn test_insert_and_keys() {
-    let storage = RocksDB::open(temp_dir(), 0, false).expect("Failed to open storage");
-    let map = storage.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
-    map.insert(&123456789, &"123456789".to_string()).expect("Failed to insert");
-
-    let mut keys = map.keys();
-    assert_eq!(Some(123456789), keys.next());
-    assert_eq!(None, keys.next());
-}
-
-#[test]
-fn test_insert_and_values() {
-    let storage = RocksDB::open(temp_dir(), 0, false).expect("Failed to open storage");
-    let map = storage.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
-    map.insert(&123456789, &"123456789".to_string()).expect("Failed to insert");
-
-    let mut values = map.values();
-    assert_eq!(Some("123456789".to_string()), values.next());
-    assert_eq!(None, values.next());
-}
-
-#[test]
-fn test_reopen() {
+fn test_migrate_prefix_layout() {
     let directory = temp_dir();
     {
         let storage = RocksDB::open(directory.clone(), 0, false).expect("Failed to open storage");
-        let map = storage.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
-        map.insert(&123456789, &"123456789".to_string()).expect("Failed to insert");
-        drop(storage);
+
+        // Write an entry in the prefix layout, where each map is distinguished by a prefix of its name.
+        let mut key = storage.context.clone();
+        bincode::serialize_into(&mut key, &(MapId::Test.as_bytes().len() as u32)).unwrap();
+        key.extend_from_slice(MapId::Test.as_bytes());
+        bincode::serialize_into(&mut key, &123456789u32).unwrap();
+        let value = bincode::serialize(&"123456789".to_string()).unwrap();
+        storage.rocksdb.put(&key, &value).expect("Failed to put");
     }
     {
         let storage = RocksDB::open(directory, 0, false).expect("Failed to open storage");
         let map = storage.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
         assert_eq!(Some("123456789".to_string()), map.get(&123456789).expect("Failed to get"));
+
+        // Ensure the entry was removed from the default column family.
+        let mut iterator = storage.rocksdb.raw_iterator();
+        iterator.seek_to_first();
+        assert!(!iterator.valid());
     }
 }
+
+#[test]
+fn test_load_options() {
+    let path = temp_dir().join("rocksdb.json");
+    std::fs::write(
+        &path,
+        r#"{ "block_heights": { "compression": "zstd", "bloom_filter_bits_per_key": 16 } }"#,
+    )
+    .expect("Failed to write options");
+
+    let options = RocksDBOptions::load(&path).expect("Failed to load options");
+    let block_heights = options.get(MapId::BlockHeights);
+    assert_eq!(Compression::Zstd, block_heights.compression);
+    assert_eq!(Some(16), block_heights.bloom_filter_bits_per_key);
+    assert_eq!(MapOptions::default().write_buffer_size, block_heights.write_buffer_size);
+    assert_eq!(RocksDBOptions::default().get(MapId::Transitions), options.get(MapId::Transitions));
+
+    // Ensure unknown maps and fields are rejected.
+    std::fs::write(&path, r#"{ "unknown": {} }"#).expect("Failed to write options");
+    assert!(RocksDBOptions::load(&path).is_err());
+    std::fs::write(&path, r#"{ "transitions": { "unknown": 1 } }"#).expect("Failed to write options");
+    assert!(RocksDBOptions::load(&path).is_err());
+}
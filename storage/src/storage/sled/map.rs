@@ -0,0 +1,249 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+use super::*;
+
+#[derive(Clone, Debug)]
+pub struct DataMap<K: Serialize + DeserializeOwned, V: Serialize + DeserializeOwned> {
+    pub(super) database: Arc<Database>,
+    pub(super) context: Vec<u8>,
+    /// The context of the storage the map was opened from, which prefixes the keys of every map.
+    pub(super) storage_context: Vec<u8>,
+    pub(super) key_encoding: KeyEncoding,
+    pub(super) is_read_only: bool,
+    pub(super) atomic_batch: AtomicBatch,
+    pub(super) last_seen_sequence_number: Arc<AtomicU64>,
+    pub(super) _phantom: PhantomData<(K, V)>,
+}
+
+impl<K: Serialize + DeserializeOwned, V: Serialize + DeserializeOwned> DataMap<K, V> {
+    /// Returns the given key, serialized and prefixed with the context of the map.
+    fn key_bytes<Q: Serialize + ?Sized>(&self, key: &Q) -> Result<Vec<u8>> {
+        self.key_encoding.key_bytes(&self.context, key)
+    }
+
+    /// Returns this map with the given key encoding, in order to migrate the keys of the map.
+    pub(crate) fn with_key_encoding(&self, key_encoding: KeyEncoding) -> Self {
+        Self {
+            database: self.database.clone(),
+            context: self.context.clone(),
+            storage_context: self.storage_context.clone(),
+            key_encoding,
+            is_read_only: self.is_read_only,
+            atomic_batch: self.atomic_batch.clone(),
+            last_seen_sequence_number: self.last_seen_sequence_number.clone(),
+            _phantom: PhantomData,
+        }
+    }
+
+    ///
+    /// Creates a checkpoint of the storage of this map at the given path, which must not exist.
+    /// As sled does not support checkpoints, every entry of the storage is copied into a new database.
+    ///
+    pub(crate) fn create_checkpoint<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        // If the storage is in read-only mode, this method cannot be called.
+        if self.is_read_only {
+            return Err(anyhow!("Storage must be writable to create a checkpoint"));
+        }
+
+        self.database.copy_to(path.as_ref())
+    }
+
+    /// Returns the size and health statistics of the storage of this map.
+    pub(crate) fn storage_stats(&self) -> Result<StorageStats> {
+        self.database.stats(&self.storage_context)
+    }
+
+    /// Writes the given operation, or stages it if an atomic batch is in progress.
+    fn write(&self, key: Vec<u8>, value: Option<Vec<u8>>) -> Result<()> {
+        // If the storage is in read-only mode, this method cannot be called.
+        if self.is_read_only {
+            return Err(anyhow!("Storage is in read-only mode"));
+        }
+
+        match &mut *self.atomic_batch.lock() {
+            Some(batch) => batch.push((key, value)),
+            None => self.database.write(vec![(key, value)])?,
+        }
+        Ok(())
+    }
+}
+
+impl<'a, K: Serialize + DeserializeOwned, V: Serialize + DeserializeOwned> Map<'a, K, V> for DataMap<K, V> {
+    type Iterator = Iter<K, V>;
+    type Keys = Keys<K>;
+    type Values = Values<V>;
+
+    ///
+    /// Returns `true` if the given key exists in the map.
+    ///
+    fn contains_key<Q>(&self, key: &Q) -> Result<bool>
+    where
+        K: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        Ok(self.database.sled.contains_key(&self.key_bytes(key)?)?)
+    }
+
+    ///
+    /// Returns the value for the given key from the map, if it exists.
+    ///
+    fn get<Q>(&self, key: &Q) -> Result<Option<V>>
+    where
+        K: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        match self.database.sled.get(&self.key_bytes(key)?)? {
+            Some(data) => Ok(Some(bincode::deserialize(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    ///
+    /// Inserts the given key-value pair into the map.
+    ///
+    fn insert<Q>(&self, key: &Q, value: &V) -> Result<()>
+    where
+        K: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        self.write(self.key_bytes(key)?, Some(bincode::serialize(value)?))
+    }
+
+    ///
+    /// Removes the key-value pair for the given key from the map.
+    ///
+    fn remove<Q>(&self, key: &Q) -> Result<()>
+    where
+        K: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        self.write(self.key_bytes(key)?, None)
+    }
+
+    ///
+    /// Returns an iterator visiting each key-value pair in the map.
+    ///
+    fn iter(&'a self) -> Self::Iterator {
+        Iter::new(
+            self.database.sled.scan_prefix(&self.context),
+            self.context.clone(),
+            self.key_encoding,
+        )
+    }
+
+    ///
+    /// Returns an iterator over each key in the map.
+    ///
+    fn keys(&'a self) -> Self::Keys {
+        Keys::new(
+            self.database.sled.scan_prefix(&self.context),
+            self.context.clone(),
+            self.key_encoding,
+        )
+    }
+
+    ///
+    /// Returns an iterator over each value in the map.
+    ///
+    fn values(&'a self) -> Self::Values {
+        Values::new(self.database.sled.scan_prefix(&self.context), self.context.clone())
+    }
+
+    ///
+    /// Returns an iterator visiting each key-value pair in the given range of keys, in the order of
+    /// their serialized keys.
+    ///
+    fn range<R: RangeBounds<K>>(&'a self, range: R) -> Result<Self::Iterator> {
+        let (lower_bound, upper_bound) = self.key_encoding.range_bytes(&self.context, &range)?;
+        let entries = match upper_bound {
+            Some(upper_bound) => self.database.sled.range(lower_bound..upper_bound),
+            None => self.database.sled.range(lower_bound..),
+        };
+
+        Ok(Iter::new(entries, self.context.clone(), self.key_encoding))
+    }
+
+    ///
+    /// Returns the key-value pair with the greatest serialized key in the map, if the map is not empty.
+    ///
+    fn last(&self) -> Result<Option<(K, V)>> {
+        let mut entries = match prefix_successor(&self.context) {
+            Some(successor) => self.database.sled.range(self.context.clone()..successor),
+            None => self.database.sled.range(self.context.clone()..),
+        };
+
+        match entries.next_back().transpose()? {
+            Some((key, value)) if key.starts_with(&self.context) => Ok(Some((
+                self.key_encoding.deserialize(&key[self.context.len()..])?,
+                bincode::deserialize(&value)?,
+            ))),
+            _ => Ok(None),
+        }
+    }
+
+    ///
+    /// Begins an atomic batch of operations. Until the batch is finished or aborted, all inserts and
+    /// removes on every map opened from the same storage are staged instead of being written.
+    ///
+    fn start_atomic(&self) -> Result<()> {
+        let mut atomic_batch = self.atomic_batch.lock();
+        // Nested batches are rejected, as their operations would silently be merged into the outer batch.
+        if atomic_batch.is_some() {
+            return Err(anyhow!("Attempted to start an atomic batch while another one is in progress"));
+        }
+        *atomic_batch = Some(Vec::new());
+        Ok(())
+    }
+
+    ///
+    /// Returns `true` if an atomic batch of operations is in progress.
+    ///
+    fn is_atomic_in_progress(&self) -> bool {
+        self.atomic_batch.lock().is_some()
+    }
+
+    ///
+    /// Discards all operations staged since the atomic batch was started.
+    ///
+    fn abort_atomic(&self) {
+        *self.atomic_batch.lock() = None;
+    }
+
+    ///
+    /// Writes all operations staged since the atomic batch was started to storage, as a single atomic write.
+    ///
+    fn finish_atomic(&self) -> Result<()> {
+        // Take the staged operations, which ends the atomic batch.
+        match self.atomic_batch.lock().take() {
+            Some(operations) => self.database.write(operations),
+            None => Err(anyhow!("Attempted to finish an atomic batch that was never started")),
+        }
+    }
+
+    ///
+    /// Performs a refresh operation for implementations of `Map` that perform periodic operations.
+    /// As a reader shares the database of its writer in this process, it observes all writes immediately.
+    /// Returns `true` if the database has been written to since the last refresh.
+    ///
+    fn refresh(&self) -> bool {
+        if self.is_read_only {
+            let sequence_number = self.database.sequence_number.load(Ordering::SeqCst);
+            let last_seen_sequence_number = self.last_seen_sequence_number.swap(sequence_number, Ordering::SeqCst);
+            return sequence_number > last_seen_sequence_number;
+        }
+        false
+    }
+}
@@ -0,0 +1,323 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+mod iterator;
+use iterator::*;
+
+mod map;
+pub use map::*;
+
+#[cfg(test)]
+mod tests;
+
+use crate::storage::{prefix_successor, AtomicBatch, KeyEncoding, Map, MapId, MapStats, Storage, StorageStats};
+
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{
+    de::{self, DeserializeOwned},
+    ser::SerializeSeq,
+    Deserializer,
+    Serialize,
+    Serializer,
+};
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    fmt,
+    marker::PhantomData,
+    ops::RangeBounds,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+        Weak,
+    },
+};
+
+/// The sled databases that are open in this process, keyed by the storage path they were opened with.
+static DATABASES: Lazy<Mutex<HashMap<PathBuf, Weak<Database>>>> = Lazy::new(Default::default);
+
+///
+/// A sled database, shared by all of its handles in this process.
+///
+/// A sled database may only be opened by one process at a time, so a reader in another process
+/// may only be opened while no writer is running.
+///
+#[derive(Debug)]
+pub(crate) struct Database {
+    sled: sled::Db,
+    /// The number of writes performed on the database.
+    sequence_number: AtomicU64,
+}
+
+impl Database {
+    /// The number of operations written in each batch when copying a database.
+    const COPY_BATCH_SIZE: usize = 10_000;
+
+    ///
+    /// Returns the database at the given storage path, opening it if it is not already open in this process.
+    /// If `create_if_missing` is `false`, the database must already exist.
+    ///
+    fn open(path: &Path, create_if_missing: bool) -> Result<Arc<Self>> {
+        let mut databases = DATABASES.lock();
+        if let Some(database) = databases.get(path).and_then(|database| database.upgrade()) {
+            return Ok(database);
+        }
+        if !create_if_missing && !path.exists() {
+            return Err(anyhow!("No sled storage exists at {}", path.display()));
+        }
+
+        let database = Arc::new(Database {
+            sled: sled::Config::new().path(path).open()?,
+            sequence_number: Default::default(),
+        });
+        databases.insert(path.to_path_buf(), Arc::downgrade(&database));
+        Ok(database)
+    }
+
+    /// Ensures the database at the given storage path is not open in this process.
+    fn ensure_closed(path: &Path) -> Result<()> {
+        let mut databases = DATABASES.lock();
+        match databases.get(path).map(|database| database.strong_count() > 0) {
+            Some(true) => Err(anyhow!("Sled storage at {} is still open", path.display())),
+            Some(false) => {
+                databases.remove(path);
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Writes the given operations := (key, Some(value)) for an insert, (key, None) for a remove, as a single write.
+    fn write(&self, operations: Vec<(Vec<u8>, Option<Vec<u8>>)>) -> Result<()> {
+        let mut batch = sled::Batch::default();
+        for (key, value) in operations {
+            match value {
+                Some(value) => batch.insert(key, value),
+                None => batch.remove(key),
+            }
+        }
+        self.sled.apply_batch(batch)?;
+        self.sequence_number.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    ///
+    /// Copies every entry of this database into a new database at the given path, which must not exist.
+    /// The caller must ensure no writes are performed while the database is copied.
+    ///
+    fn copy_to(&self, path: &Path) -> Result<()> {
+        if path.exists() {
+            return Err(anyhow!("Storage already exists at {}", path.display()));
+        }
+
+        let target = sled::Config::new().path(path).open()?;
+        let mut batch = sled::Batch::default();
+        for (index, entry) in self.sled.iter().enumerate() {
+            let (key, value) = entry?;
+            batch.insert(key, value);
+
+            if (index + 1) % Self::COPY_BATCH_SIZE == 0 {
+                target.apply_batch(std::mem::take(&mut batch))?;
+            }
+        }
+        target.apply_batch(batch)?;
+        target.flush()?;
+        Ok(())
+    }
+
+    /// Returns the number of keys and the size of the keys and values of each map with the given storage context.
+    fn stats(&self, context: &[u8]) -> Result<StorageStats> {
+        let mut maps = Vec::with_capacity(MapId::ALL.len());
+        for map_id in MapId::ALL {
+            let (mut num_keys, mut approximate_size) = (0u64, 0u64);
+            for entry in self.sled.scan_prefix(map_id.prefix(context)?) {
+                let (key, value) = entry?;
+                num_keys += 1;
+                approximate_size += (key.len() + value.len()) as u64;
+            }
+            maps.push(MapStats {
+                name: map_id.name().to_string(),
+                num_keys,
+                approximate_size,
+            });
+        }
+
+        Ok(StorageStats {
+            maps,
+            ..Default::default()
+        })
+    }
+}
+
+///
+/// An instance of a sled database, a pure-Rust embedded database.
+///
+/// Each map is distinguished by a prefix of its name, and all maps are written to the same tree,
+/// so that an atomic batch across maps is written with a single atomic write.
+///
+#[derive(Clone)]
+pub struct SledDB {
+    database: Arc<Database>,
+    context: Vec<u8>,
+    is_read_only: bool,
+    /// The atomic batch shared by all maps opened from this storage.
+    atomic_batch: AtomicBatch,
+}
+
+impl Storage for SledDB {
+    ///
+    /// Opens storage at the given `path` and `context`.
+    ///
+    fn open<P: AsRef<Path>>(path: P, context: u16, is_read_only: bool) -> Result<Self> {
+        let context = context.to_le_bytes();
+        let mut context_bytes = bincode::serialize(&(context.len() as u32))?;
+        context_bytes.extend_from_slice(&context);
+
+        // A reader may only be opened for an existing database.
+        let database = Database::open(path.as_ref(), !is_read_only)?;
+
+        Ok(SledDB {
+            database,
+            context: context_bytes,
+            is_read_only,
+            atomic_batch: Default::default(),
+        })
+    }
+
+    ///
+    /// Opens a map with the given `context` from storage.
+    ///
+    fn open_map<K: Serialize + DeserializeOwned, V: Serialize + DeserializeOwned>(
+        &self,
+        map_id: MapId,
+    ) -> Result<crate::storage::DataMap<K, V>> {
+        Ok(crate::storage::DataMap::Sled(DataMap {
+            database: self.database.clone(),
+            context: map_id.prefix(&self.context)?,
+            storage_context: self.context.clone(),
+            key_encoding: map_id.key_encoding(),
+            is_read_only: self.is_read_only,
+            atomic_batch: self.atomic_batch.clone(),
+            last_seen_sequence_number: Arc::new(AtomicU64::new(self.database.sequence_number.load(Ordering::SeqCst))),
+            _phantom: PhantomData,
+        }))
+    }
+
+    ///
+    /// Imports the given serialized bytes to reconstruct storage.
+    ///
+    fn import<'de, D: Deserializer<'de>>(&self, deserializer: D) -> Result<(), D::Error> {
+        struct SledDBVisitor {
+            sled_db: SledDB,
+        }
+
+        impl<'de> de::Visitor<'de> for SledDBVisitor {
+            type Value = ();
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "a sled db seq")
+            }
+
+            fn visit_seq<A: de::SeqAccess<'de>>(self, mut map: A) -> std::result::Result<(), A::Error> {
+                if self.sled_db.is_read_only {
+                    return Err(de::Error::custom("Storage is in read-only mode"));
+                }
+
+                let mut operations = Vec::new();
+                while let Some((key, value)) = map.next_element::<(Vec<_>, Vec<_>)>()? {
+                    operations.push((key, Some(value)));
+                }
+                self.sled_db.database.write(operations).map_err(de::Error::custom)?;
+
+                Ok(())
+            }
+        }
+
+        deserializer.deserialize_seq(SledDBVisitor { sled_db: self.clone() })?;
+
+        Ok(())
+    }
+
+    ///
+    /// Exports the current state of storage into serialized bytes.
+    ///
+    fn export(&self) -> Result<serde_json::Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    ///
+    /// Restores the checkpoint at the given `checkpoint` path to storage at the given `path`, which must not exist.
+    ///
+    fn restore_checkpoint<P: AsRef<Path>, Q: AsRef<Path>>(checkpoint: P, path: Q) -> Result<()> {
+        let checkpoint = Database::open(checkpoint.as_ref(), false)?;
+        checkpoint.copy_to(path.as_ref())
+    }
+
+    ///
+    /// Returns `true` if storage exists at the given `path`.
+    ///
+    fn exists<P: AsRef<Path>>(path: P) -> bool {
+        path.as_ref().exists()
+    }
+
+    ///
+    /// Moves the storage at the given `from` path to the given `to` path, which must not exist.
+    /// The storage must not be open while it is moved.
+    ///
+    fn rename<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> Result<()> {
+        let (from, to) = (from.as_ref(), to.as_ref());
+        Database::ensure_closed(from)?;
+        if !from.exists() {
+            return Err(anyhow!("No sled storage exists at {}", from.display()));
+        }
+        if to.exists() {
+            return Err(anyhow!("Storage already exists at {}", to.display()));
+        }
+        std::fs::rename(from, to)?;
+        Ok(())
+    }
+
+    ///
+    /// Removes the storage at the given `path`, if it exists. The storage must not be open while it is removed.
+    ///
+    fn destroy<P: AsRef<Path>>(path: P) -> Result<()> {
+        Database::ensure_closed(path.as_ref())?;
+        if path.as_ref().exists() {
+            std::fs::remove_dir_all(path)?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Returns the size and health statistics of storage, for each map and for the storage backend.
+    ///
+    fn stats(&self) -> Result<StorageStats> {
+        self.database.stats(&self.context)
+    }
+}
+
+impl Serialize for SledDB {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_seq(None)?;
+        for entry in self.database.sled.iter() {
+            let (key, value) = entry.map_err(serde::ser::Error::custom)?;
+            map.serialize_element(&(&*key, &*value))?;
+        }
+        map.end()
+    }
+}
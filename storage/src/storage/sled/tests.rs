@@ -0,0 +1,45 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+use crate::storage::{sled::SledDB, Map, MapId, Storage};
+
+fn temp_dir() -> std::path::PathBuf {
+    tempfile::tempdir().expect("Failed to open temporary directory").into_path()
+}
+
+#[test]
+fn test_checkpoint() {
+    let directory = temp_dir();
+    let (checkpoint, restored) = (directory.join("checkpoint"), directory.join("restored"));
+
+    let storage = SledDB::open(directory.join("storage"), 0, false).expect("Failed to open storage");
+    let map = storage.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
+    map.insert(&123456789, &"123456789".to_string()).expect("Failed to insert");
+    map.create_checkpoint(&checkpoint).expect("Failed to create checkpoint");
+
+    // Ensure the checkpoint excludes later writes, and is not overwritten.
+    map.insert(&987654321, &"987654321".to_string()).expect("Failed to insert");
+    assert!(map.create_checkpoint(&checkpoint).is_err());
+
+    // Ensure the restored storage holds the entries of the checkpoint.
+    SledDB::restore_checkpoint(&checkpoint, &restored).expect("Failed to restore checkpoint");
+    assert!(SledDB::restore_checkpoint(&checkpoint, &restored).is_err());
+    let restored_storage = SledDB::open(&restored, 0, true).expect("Failed to open storage");
+    let restored_map = restored_storage
+        .open_map::<u32, String>(MapId::Test)
+        .expect("Failed to open data map");
+    assert_eq!(Some("123456789".to_string()), restored_map.get(&123456789).expect("Failed to get"));
+    assert!(restored_map.get(&987654321).expect("Failed to get").is_none());
+}
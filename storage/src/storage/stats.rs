@@ -0,0 +1,77 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+use serde::{Deserialize, Serialize};
+
+///
+/// The size and health statistics of storage.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageStats {
+    /// The statistics of each map in storage.
+    pub maps: Vec<MapStats>,
+    /// The compaction and pending write statistics, for storage backends that compact their files.
+    pub compaction: Option<CompactionStats>,
+    /// The number of blocks the read-only ledger is behind the writable ledger, if a reader is open.
+    pub reader_lag: Option<u32>,
+}
+
+impl StorageStats {
+    /// Returns the total number of keys in storage.
+    pub fn num_keys(&self) -> u64 {
+        self.maps.iter().map(|map| map.num_keys).sum()
+    }
+
+    /// Returns the total approximate size of storage, in bytes.
+    pub fn approximate_size(&self) -> u64 {
+        self.maps.iter().map(|map| map.approximate_size).sum()
+    }
+}
+
+///
+/// The size statistics of a map in storage.
+///
+/// The statistics of RocksDB are estimates from the metadata of its files, which avoids scanning the map.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MapStats {
+    /// The name of the map.
+    pub name: String,
+    /// The number of keys in the map.
+    pub num_keys: u64,
+    /// The approximate size of the keys and values of the map, in bytes.
+    pub approximate_size: u64,
+}
+
+///
+/// The compaction and pending write statistics of RocksDB, summed across all maps.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactionStats {
+    /// The estimated number of bytes that compactions must rewrite to settle every level.
+    pub pending_compaction_bytes: u64,
+    /// The number of compactions that are currently running.
+    pub running_compactions: u64,
+    /// The number of memtable flushes that are currently running.
+    pub running_flushes: u64,
+    /// The size of the writes held in memtables, which are yet to be flushed to disk, in bytes.
+    pub unflushed_bytes: u64,
+    /// The number of maps with a pending memtable flush.
+    pub pending_flushes: u64,
+    /// The number of maps with a pending compaction.
+    pub pending_compactions: u64,
+    /// If `true`, writes are stopped until compactions catch up.
+    pub is_write_stopped: bool,
+}
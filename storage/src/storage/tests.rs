@@ -0,0 +1,359 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+#[cfg(feature = "rocks")]
+use crate::storage::rocksdb::RocksDB;
+#[cfg(feature = "sled")]
+use crate::storage::sled::SledDB;
+use crate::storage::{memory::MemoryDB, Map, MapId, Storage};
+
+///
+/// Defines the storage tests for the given storage backend, which are run against every backend.
+///
+macro_rules! storage_tests {
+    ($backend:ident, $storage:ty) => {
+        mod $backend {
+            use super::*;
+
+            #[test]
+            fn test_open() {
+                super::test_open::<$storage>()
+            }
+
+            #[test]
+            fn test_open_map() {
+                super::test_open_map::<$storage>()
+            }
+
+            #[test]
+            fn test_insert_and_contains_key() {
+                super::test_insert_and_contains_key::<$storage>()
+            }
+
+            #[test]
+            fn test_insert_and_get() {
+                super::test_insert_and_get::<$storage>()
+            }
+
+            #[test]
+            fn test_insert_and_remove() {
+                super::test_insert_and_remove::<$storage>()
+            }
+
+            #[test]
+            fn test_insert_and_iter() {
+                super::test_insert_and_iter::<$storage>()
+            }
+
+            #[test]
+            fn test_insert_and_keys() {
+                super::test_insert_and_keys::<$storage>()
+            }
+
+            #[test]
+            fn test_insert_and_values() {
+                super::test_insert_and_values::<$storage>()
+            }
+
+            #[test]
+            fn test_range_and_last() {
+                super::test_range_and_last::<$storage>()
+            }
+
+            #[test]
+            fn test_reopen() {
+                super::test_reopen::<$storage>()
+            }
+
+            #[test]
+            fn test_atomic_batch() {
+                super::test_atomic_batch::<$storage>()
+            }
+
+            #[test]
+            fn test_abort_atomic_batch() {
+                super::test_abort_atomic_batch::<$storage>()
+            }
+
+            #[test]
+            fn test_nested_atomic_batch() {
+                super::test_nested_atomic_batch::<$storage>()
+            }
+
+            #[test]
+            fn test_stats() {
+                super::test_stats::<$storage>()
+            }
+
+            #[test]
+            fn test_reader() {
+                super::test_reader::<$storage>()
+            }
+
+            #[test]
+            fn test_export_and_import() {
+                super::test_export_and_import::<$storage>()
+            }
+        }
+    };
+}
+
+storage_tests!(memory, MemoryDB);
+#[cfg(feature = "rocks")]
+storage_tests!(rocksdb, RocksDB);
+#[cfg(feature = "sled")]
+storage_tests!(sled, SledDB);
+
+fn temp_dir() -> std::path::PathBuf {
+    tempfile::tempdir().expect("Failed to open temporary directory").into_path()
+}
+
+fn test_open<S: Storage>() {
+    let _storage = S::open(temp_dir(), 0, false).expect("Failed to open storage");
+}
+
+fn test_open_map<S: Storage>() {
+    let storage = S::open(temp_dir(), 0, false).expect("Failed to open storage");
+    storage.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
+}
+
+fn test_insert_and_contains_key<S: Storage>() {
+    let storage = S::open(temp_dir(), 0, false).expect("Failed to open storage");
+    let map = storage.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
+
+    map.insert(&123456789, &"123456789".to_string()).expect("Failed to insert");
+    assert!(map.contains_key(&123456789).expect("Failed to call contains key"));
+    assert!(!map.contains_key(&000000000).expect("Failed to call contains key"));
+}
+
+fn test_insert_and_get<S: Storage>() {
+    let storage = S::open(temp_dir(), 0, false).expect("Failed to open storage");
+    let map = storage.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
+
+    map.insert(&123456789, &"123456789".to_string()).expect("Failed to insert");
+    assert_eq!(Some("123456789".to_string()), map.get(&123456789).expect("Failed to get"));
+    assert_eq!(None, map.get(&000000000).expect("Failed to get"));
+}
+
+fn test_insert_and_remove<S: Storage>() {
+    let storage = S::open(temp_dir(), 0, false).expect("Failed to open storage");
+    let map = storage.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
+
+    map.insert(&123456789, &"123456789".to_string()).expect("Failed to insert");
+    assert!(map.get(&123456789).expect("Failed to get").is_some());
+
+    map.remove(&123456789).expect("Failed to remove");
+    assert!(map.get(&123456789).expect("Failed to get").is_none());
+}
+
+fn test_insert_and_iter<S: Storage>() {
+    let storage = S::open(temp_dir(), 0, false).expect("Failed to open storage");
+    let map = storage.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
+    map.insert(&123456789, &"123456789".to_string()).expect("Failed to insert");
+
+    let mut iter = map.iter();
+    assert_eq!(Some((123456789, "123456789".to_string())), iter.next());
+    assert_eq!(None, iter.next());
+}
+
+fn test_insert_and_keys<S: Storage>() {
+    let storage = S::open(temp_dir(), 0, false).expect("Failed to open storage");
+    let map = storage.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
+    map.insert(&123456789, &"123456789".to_string()).expect("Failed to insert");
+
+    let mut keys = map.keys();
+    assert_eq!(Some(123456789), keys.next());
+    assert_eq!(None, keys.next());
+}
+
+fn test_insert_and_values<S: Storage>() {
+    let storage = S::open(temp_dir(), 0, false).expect("Failed to open storage");
+    let map = storage.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
+    map.insert(&123456789, &"123456789".to_string()).expect("Failed to insert");
+
+    let mut values = map.values();
+    assert_eq!(Some("123456789".to_string()), values.next());
+    assert_eq!(None, values.next());
+}
+
+fn test_range_and_last<S: Storage>() {
+    let storage = S::open(temp_dir(), 0, false).expect("Failed to open storage");
+    let map = storage
+        .open_map::<u32, String>(MapId::BlockHeights)
+        .expect("Failed to open data map");
+    let other_map = storage
+        .open_map::<u32, String>(MapId::BlockHeaders)
+        .expect("Failed to open data map");
+    assert_eq!(None, map.last().expect("Failed to get last"));
+
+    // Insert keys whose little-endian encodings are out of order.
+    for i in [1u32, 255, 256, 65536, 16777216].iter() {
+        map.insert(i, &i.to_string()).expect("Failed to insert");
+    }
+    other_map.insert(&u32::MAX, &u32::MAX.to_string()).expect("Failed to insert");
+
+    assert_eq!(vec![1, 255, 256, 65536, 16777216], map.keys().collect::<Vec<_>>());
+    assert_eq!(Some((16777216, "16777216".to_string())), map.last().expect("Failed to get last"));
+
+    let range = |range: std::ops::Range<u32>| map.range(range).expect("Failed to get range").map(|(k, _)| k).collect::<Vec<_>>();
+    assert_eq!(vec![255, 256], range(2..65536));
+    assert_eq!(vec![1, 255, 256, 65536, 16777216], range(0..u32::MAX));
+    assert!(range(257..65536).is_empty());
+
+    assert_eq!(
+        vec![256, 65536],
+        map.range(256..=65536)
+            .expect("Failed to get range")
+            .map(|(k, _)| k)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![65536, 16777216],
+        map.range(257..).expect("Failed to get range").map(|(k, _)| k).collect::<Vec<_>>()
+    );
+}
+
+fn test_reopen<S: Storage>() {
+    let directory = temp_dir();
+    {
+        let storage = S::open(directory.clone(), 0, false).expect("Failed to open storage");
+        let map = storage.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
+        map.insert(&123456789, &"123456789".to_string()).expect("Failed to insert");
+        drop(storage);
+    }
+    {
+        let storage = S::open(directory, 0, false).expect("Failed to open storage");
+        let map = storage.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
+        assert_eq!(Some("123456789".to_string()), map.get(&123456789).expect("Failed to get"));
+    }
+}
+
+fn test_atomic_batch<S: Storage>() {
+    let storage = S::open(temp_dir(), 0, false).expect("Failed to open storage");
+    let map = storage.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
+    let other_map = storage.open_map::<u32, u32>(MapId::Records).expect("Failed to open data map");
+    map.insert(&987654321, &"987654321".to_string()).expect("Failed to insert");
+
+    map.start_atomic().expect("Failed to start atomic batch");
+    assert!(map.is_atomic_in_progress());
+    assert!(other_map.is_atomic_in_progress());
+
+    // Ensure the staged operations are not written before the batch is finished.
+    map.insert(&123456789, &"123456789".to_string()).expect("Failed to insert");
+    other_map.insert(&123456789, &123456789).expect("Failed to insert");
+    map.remove(&987654321).expect("Failed to remove");
+    assert!(map.get(&123456789).expect("Failed to get").is_none());
+    assert!(other_map.get(&123456789).expect("Failed to get").is_none());
+    assert!(map.get(&987654321).expect("Failed to get").is_some());
+
+    // Ensure the staged operations across both maps are written once the batch is finished.
+    other_map.finish_atomic().expect("Failed to finish atomic batch");
+    assert!(!map.is_atomic_in_progress());
+    assert_eq!(Some("123456789".to_string()), map.get(&123456789).expect("Failed to get"));
+    assert_eq!(Some(123456789), other_map.get(&123456789).expect("Failed to get"));
+    assert!(map.get(&987654321).expect("Failed to get").is_none());
+}
+
+fn test_abort_atomic_batch<S: Storage>() {
+    let storage = S::open(temp_dir(), 0, false).expect("Failed to open storage");
+    let map = storage.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
+
+    map.start_atomic().expect("Failed to start atomic batch");
+    map.insert(&123456789, &"123456789".to_string()).expect("Failed to insert");
+    map.abort_atomic();
+    assert!(!map.is_atomic_in_progress());
+
+    // Ensure the aborted operations were discarded.
+    assert!(map.get(&123456789).expect("Failed to get").is_none());
+    assert!(map.finish_atomic().is_err());
+
+    // Ensure operations are written directly once the batch has ended.
+    map.insert(&123456789, &"123456789".to_string()).expect("Failed to insert");
+    assert!(map.get(&123456789).expect("Failed to get").is_some());
+}
+
+fn test_nested_atomic_batch<S: Storage>() {
+    let storage = S::open(temp_dir(), 0, false).expect("Failed to open storage");
+    let map = storage.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
+    let other_map = storage.open_map::<u32, u32>(MapId::Records).expect("Failed to open data map");
+
+    map.start_atomic().expect("Failed to start atomic batch");
+    map.insert(&123456789, &"123456789".to_string()).expect("Failed to insert");
+
+    // Ensure a nested batch is rejected, from any map in storage.
+    assert!(map.start_atomic().is_err());
+    assert!(other_map.start_atomic().is_err());
+
+    // Ensure the outer batch is left intact.
+    assert!(map.is_atomic_in_progress());
+    map.finish_atomic().expect("Failed to finish atomic batch");
+    assert!(map.get(&123456789).expect("Failed to get").is_some());
+}
+
+fn test_stats<S: Storage>() {
+    let storage = S::open(temp_dir(), 0, false).expect("Failed to open storage");
+    let map = storage.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
+    map.insert(&123456789, &"123456789".to_string()).expect("Failed to insert");
+
+    let stats = storage.stats().expect("Failed to get stats");
+    assert_eq!(MapId::ALL.len(), stats.maps.len());
+    assert_eq!(1, stats.num_keys());
+    assert!(stats.reader_lag.is_none());
+
+    // Ensure the entry is counted towards its map.
+    let map_stats = stats.maps.iter().find(|map| map.name == MapId::Test.name()).unwrap();
+    assert_eq!(1, map_stats.num_keys);
+    assert!(map_stats.approximate_size > 0);
+
+    // Ensure the stats may be retrieved from any map in storage.
+    assert_eq!(stats.num_keys(), map.storage_stats().expect("Failed to get stats").num_keys());
+}
+
+fn test_reader<S: Storage>() {
+    let path = temp_dir().join("storage");
+
+    // Ensure a reader cannot be opened before the storage exists.
+    assert!(S::open(&path, 0, true).is_err());
+
+    let writer = S::open(&path, 0, false).expect("Failed to open storage");
+    let writer_map = writer.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
+    let reader = S::open(&path, 0, true).expect("Failed to open storage");
+    let reader_map = reader.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
+    assert!(!reader_map.refresh());
+
+    // Ensure the reader observes the writes of the writer.
+    writer_map.insert(&123456789, &"123456789".to_string()).expect("Failed to insert");
+    assert!(reader_map.refresh());
+    assert!(!reader_map.refresh());
+    assert_eq!(Some("123456789".to_string()), reader_map.get(&123456789).expect("Failed to get"));
+
+    // Ensure the reader cannot write.
+    assert!(reader_map.insert(&987654321, &"987654321".to_string()).is_err());
+    assert!(reader_map.remove(&123456789).is_err());
+}
+
+fn test_export_and_import<S: Storage>() {
+    let storage = S::open(temp_dir(), 0, false).expect("Failed to open storage");
+    let map = storage.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
+    map.insert(&123456789, &"123456789".to_string()).expect("Failed to insert");
+
+    let value = storage.export().expect("Failed to export");
+
+    let other_storage = S::open(temp_dir(), 0, false).expect("Failed to open storage");
+    other_storage.import(value).expect("Failed to import");
+    let other_map = other_storage.open_map::<u32, String>(MapId::Test).expect("Failed to open data map");
+    assert_eq!(Some("123456789".to_string()), other_map.get(&123456789).expect("Failed to get"));
+}
@@ -14,11 +14,11 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
-use super::{DataMap, MapId};
+use super::{DataMap, MapId, StorageStats};
 
 use anyhow::Result;
 use serde::{de::DeserializeOwned, Deserializer, Serialize};
-use std::{borrow::Borrow, path::Path};
+use std::{borrow::Borrow, ops::RangeBounds, path::Path};
 
 pub trait Storage: Serialize {
     ///
@@ -42,6 +42,40 @@ pub trait Storage: Serialize {
     /// Exports the current state of storage into serialized bytes.
     ///
     fn export(&self) -> Result<serde_json::Value>;
+
+    ///
+    /// Restores the checkpoint at the given `checkpoint` path to storage at the given `path`, which must not exist.
+    ///
+    fn restore_checkpoint<P: AsRef<Path>, Q: AsRef<Path>>(checkpoint: P, path: Q) -> Result<()>
+    where
+        Self: Sized;
+
+    ///
+    /// Returns `true` if storage exists at the given `path`.
+    ///
+    fn exists<P: AsRef<Path>>(path: P) -> bool
+    where
+        Self: Sized;
+
+    ///
+    /// Moves the storage at the given `from` path to the given `to` path, which must not exist.
+    /// The storage must not be open while it is moved.
+    ///
+    fn rename<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> Result<()>
+    where
+        Self: Sized;
+
+    ///
+    /// Removes the storage at the given `path`, if it exists. The storage must not be open while it is removed.
+    ///
+    fn destroy<P: AsRef<Path>>(path: P) -> Result<()>
+    where
+        Self: Sized;
+
+    ///
+    /// Returns the size and health statistics of storage, for each map and for the storage backend.
+    ///
+    fn stats(&self) -> Result<StorageStats>;
 }
 
 pub trait Map<'a, K: Serialize + DeserializeOwned, V: Serialize + DeserializeOwned> {
@@ -96,6 +130,48 @@ pub trait Map<'a, K: Serialize + DeserializeOwned, V: Serialize + DeserializeOwn
     ///
     fn values(&'a self) -> Self::Values;
 
+    ///
+    /// Returns an iterator visiting each key-value pair in the given range of keys, in the order of
+    /// their serialized keys. This seeks to the start of the range, instead of scanning the map.
+    ///
+    /// *Attention*: The order of the serialized keys matches the order of the keys
+    /// only for maps with `KeyEncoding::BigEndian` and unsigned integer keys.
+    ///
+    fn range<R: RangeBounds<K>>(&'a self, range: R) -> Result<Self::Iterator>;
+
+    ///
+    /// Returns the key-value pair with the greatest serialized key in the map, if the map is not empty.
+    ///
+    /// *Attention*: The order of the serialized keys matches the order of the keys
+    /// only for maps with `KeyEncoding::BigEndian` and unsigned integer keys.
+    ///
+    fn last(&self) -> Result<Option<(K, V)>>;
+
+    ///
+    /// Begins an atomic batch of operations. Until the batch is finished or aborted, all inserts and
+    /// removes on every map opened from the same storage are staged instead of being written.
+    ///
+    /// Returns an error if an atomic batch is already in progress.
+    ///
+    /// *Attention*: Staged operations are not visible to reads until the batch is finished.
+    ///
+    fn start_atomic(&self) -> Result<()>;
+
+    ///
+    /// Returns `true` if an atomic batch of operations is in progress.
+    ///
+    fn is_atomic_in_progress(&self) -> bool;
+
+    ///
+    /// Discards all operations staged since the atomic batch was started.
+    ///
+    fn abort_atomic(&self);
+
+    ///
+    /// Writes all operations staged since the atomic batch was started to storage, as a single atomic write.
+    ///
+    fn finish_atomic(&self) -> Result<()>;
+
     ///
     /// Performs a refresh operation for implementations of `Map` that perform periodic operations.
     /// Returns `true` if the database state has been updated.
@@ -81,5 +81,26 @@ impl Drop for ClientNode {
             "Storage cleanup failed! The expected path \"{}\" doesn't exist",
             db_path
         );
+
+        let identity_path = format!("/tmp/snarkos-test-identity-{}", self.local_addr().port());
+        assert!(
+            fs::remove_file(&identity_path).is_ok(),
+            "Identity cleanup failed! The expected path \"{}\" doesn't exist",
+            identity_path
+        );
+
+        let address_book_path = format!("/tmp/snarkos-test-peers-{}", self.local_addr().port());
+        assert!(
+            fs::remove_file(&address_book_path).is_ok(),
+            "Address book cleanup failed! The expected path \"{}\" doesn't exist",
+            address_book_path
+        );
+
+        let ban_list_path = format!("/tmp/snarkos-test-bans-{}", self.local_addr().port());
+        assert!(
+            fs::remove_file(&ban_list_path).is_ok(),
+            "Ban list cleanup failed! The expected path \"{}\" doesn't exist",
+            ban_list_path
+        );
     }
 }
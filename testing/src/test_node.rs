@@ -20,35 +20,43 @@ use snarkos::{
     Data,
     Environment,
     Message,
+    NodeIdentity,
     NodeType,
+    NoiseCodec,
 };
 use snarkos_storage::BlockLocators;
 use snarkvm::{dpc::testnet2::Testnet2, traits::Network};
 
+use bytes::BytesMut;
 use pea2pea::{
     protocols::{Disconnect, Handshake, Reading, Writing},
     Config,
     Connection,
+    ConnectionSide,
     Node as Pea2PeaNode,
     Pea2Pea,
 };
 use rand::{thread_rng, Rng};
 use std::{
+    collections::HashMap,
     convert::TryInto,
     io,
     net::{IpAddr, Ipv4Addr, SocketAddr},
+    pin::Pin,
     sync::Arc,
+    task::{Context, Poll},
     time::Duration,
 };
 use tokio::{
-    io::{AsyncReadExt, AsyncWriteExt},
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf},
     sync::Mutex,
     task,
 };
+use tokio_util::codec::{Decoder, Encoder};
 use tracing::*;
 
 // Consts & aliases.
-const MESSAGE_LENGTH_PREFIX_SIZE: usize = 4;
+const NOISE_LENGTH_PREFIX_SIZE: usize = 2;
 const CHALLENGE_HEIGHT: u32 = 0;
 const PING_INTERVAL_SECS: u64 = 5;
 const PEER_INTERVAL_SECS: u64 = 3;
@@ -59,6 +67,7 @@ const MAXIMUM_FORK_DEPTH: u32 = <Client<Testnet2>>::MAXIMUM_FORK_DEPTH;
 pub const MAXIMUM_NUMBER_OF_PEERS: usize = <Client<Testnet2>>::MAXIMUM_NUMBER_OF_PEERS;
 
 type ClientMessage = Message<Testnet2, Client<Testnet2>>;
+type ClientCodec = NoiseCodec<Testnet2, Client<Testnet2>>;
 pub type ClientNonce = u64;
 
 /// The test node; it consists of a `Node` that handles networking and `State`
@@ -80,6 +89,9 @@ pub struct ClientPeer {
 #[derive(Clone)]
 pub struct ClientState {
     pub local_nonce: ClientNonce,
+    pub local_identity: NodeIdentity<Testnet2>,
+    /// The Noise codecs used to encrypt and decrypt the messages of each connection.
+    pub codecs: Arc<std::sync::Mutex<HashMap<SocketAddr, ClientCodec>>>,
     /// The list of known peers; `Pea2Pea` includes its own internal peer handling,
     /// but snarkOS nodes must discover the listening address and unique nonce of each peer;
     /// this collection facilitates the snarkOS peering experience to align with snarkOS logic.
@@ -91,6 +103,8 @@ impl Default for ClientState {
     fn default() -> Self {
         Self {
             local_nonce: thread_rng().gen(),
+            local_identity: NodeIdentity::new().unwrap(),
+            codecs: Default::default(),
             peers: Default::default(),
             status: Status::new(),
         }
@@ -193,6 +207,13 @@ impl Handshake for TestNode {
 
         let genesis_block_header = Testnet2::genesis_block().header();
 
+        // Perform the Noise handshake with the peer.
+        let is_initiator = connection.side == ConnectionSide::Initiator;
+        let noise_keypair = self.state.local_identity.noise_keypair();
+        let (mut codec, peer_public_key) = ClientCodec::handshake(&mut ConnectionStream(&mut connection), noise_keypair, is_initiator)
+            .await
+            .map_err(|_| io::Error::from(io::ErrorKind::InvalidData))?;
+
         // Send a challenge request to the peer.
         let own_request = ClientMessage::ChallengeRequest(
             MESSAGE_VERSION,
@@ -202,23 +223,16 @@ impl Handshake for TestNode {
             own_ip.port(),
             self.state.local_nonce,
             0,
+            self.state.local_identity.peer_id(),
         );
         trace!(parent: self.node().span(), "sending a challenge request to {}", peer_ip);
-        let msg = own_request.serialize().unwrap();
-        let len = u32::to_le_bytes(msg.len() as u32);
-        connection.writer().write_all(&len).await?;
-        connection.writer().write_all(&msg).await?;
-
-        let mut buf = [0u8; 1024];
+        write_encrypted(&mut connection, &mut codec, own_request).await?;
 
         // Read the challenge request from the peer.
-        connection.reader().read_exact(&mut buf[..MESSAGE_LENGTH_PREFIX_SIZE]).await?;
-        let len = u32::from_le_bytes(buf[..MESSAGE_LENGTH_PREFIX_SIZE].try_into().unwrap()) as usize;
-        connection.reader().read_exact(&mut buf[..len]).await?;
-        let peer_request = ClientMessage::deserialize(&buf[..len]);
+        let peer_request = read_encrypted(&mut connection, &mut codec).await;
 
         // Register peer's nonce.
-        let (peer_listening_addr, peer_nonce) = if let Ok(Message::ChallengeRequest(
+        let (peer_listening_addr, peer_nonce, peer_id) = if let Ok(Message::ChallengeRequest(
             peer_version,
             _peer_fork_depth,
             _peer_node_type,
@@ -226,6 +240,7 @@ impl Handshake for TestNode {
             peer_listening_port,
             peer_nonce,
             _cumulative_weight,
+            peer_id,
         )) = peer_request
         {
             if peer_version < MESSAGE_VERSION {
@@ -244,29 +259,37 @@ impl Handshake for TestNode {
 
             trace!(parent: self.node().span(), "received a challenge request from {}", peer_ip);
 
-            (peer_listening_ip, peer_nonce)
+            (peer_listening_ip, peer_nonce, peer_id)
         } else {
             error!(parent: self.node().span(), "invalid challenge request from {}", peer_ip);
             return Err(io::ErrorKind::InvalidData.into());
         };
 
         // Respond with own challenge request.
-        let own_response = ClientMessage::ChallengeResponse(Data::Object(genesis_block_header.clone()));
+        let signature = self
+            .state
+            .local_identity
+            .sign_challenge(peer_nonce)
+            .map_err(|_| io::Error::from(io::ErrorKind::InvalidData))?;
+        let own_response = ClientMessage::ChallengeResponse(signature, Data::Object(genesis_block_header.clone()));
         trace!(parent: self.node().span(), "sending a challenge response to {}", peer_ip);
-        let msg = own_response.serialize().unwrap();
-        let len = u32::to_le_bytes(msg.len() as u32);
-        connection.writer().write_all(&len).await?;
-        connection.writer().write_all(&msg).await?;
+        write_encrypted(&mut connection, &mut codec, own_response).await?;
 
         // Wait for the challenge response to come in.
-        connection.reader().read_exact(&mut buf[..MESSAGE_LENGTH_PREFIX_SIZE]).await?;
-        let len = u32::from_le_bytes(buf[..MESSAGE_LENGTH_PREFIX_SIZE].try_into().unwrap()) as usize;
-        connection.reader().read_exact(&mut buf[..len]).await?;
-        let peer_response = ClientMessage::deserialize(&buf[..len]);
+        let peer_response = read_encrypted(&mut connection, &mut codec).await;
 
-        if let Ok(Message::ChallengeResponse(block_header)) = peer_response {
+        if let Ok(Message::ChallengeResponse(signature, block_header)) = peer_response {
             let block_header = block_header.deserialize().await.unwrap();
 
+            // Ensure the peer signed the challenge nonce with the key of its peer ID.
+            match NodeIdentity::verify_challenge(&peer_id, self.state.local_nonce, &peer_public_key, &signature) {
+                Ok(true) => (),
+                _ => {
+                    error!(parent: self.node().span(), "invalid challenge signature from {}", peer_ip);
+                    return Err(io::ErrorKind::InvalidData.into());
+                }
+            }
+
             trace!(parent: self.node().span(), "received a challenge response from {}", peer_ip);
             if block_header.height() == CHALLENGE_HEIGHT && &block_header == genesis_block_header && block_header.is_valid() {
                 // Register the newly connected snarkOS peer.
@@ -277,6 +300,9 @@ impl Handshake for TestNode {
                 });
                 debug!(parent: self.node().span(), "connected to {} (listening addr: {})", peer_ip, peer_listening_addr);
 
+                // Register the codec used to encrypt the messages of this connection.
+                self.state.codecs.lock().unwrap().insert(peer_ip, codec);
+
                 Ok(connection)
             } else {
                 error!(parent: self.node().span(), "invalid challenge response from {}", peer_ip);
@@ -298,18 +324,21 @@ impl Reading for TestNode {
         // FIXME: use the maximum message size allowed by the protocol or (better) use streaming deserialization.
         let mut buf = [0u8; 8 * 1024];
 
-        reader.read_exact(&mut buf[..MESSAGE_LENGTH_PREFIX_SIZE])?;
-        let len = u32::from_le_bytes(buf[..MESSAGE_LENGTH_PREFIX_SIZE].try_into().unwrap()) as usize;
+        reader.read_exact(&mut buf[..NOISE_LENGTH_PREFIX_SIZE])?;
+        let len = u16::from_be_bytes(buf[..NOISE_LENGTH_PREFIX_SIZE].try_into().unwrap()) as usize;
 
-        if reader.read_exact(&mut buf[..len]).is_err() {
+        if reader.read_exact(&mut buf[NOISE_LENGTH_PREFIX_SIZE..][..len]).is_err() {
             return Ok(None);
         }
 
-        match ClientMessage::deserialize(&buf[..len]) {
-            Ok(msg) => {
+        let mut codecs = self.state.codecs.lock().unwrap();
+        let codec = codecs.get_mut(&source).ok_or(io::ErrorKind::NotConnected)?;
+        match codec.decode(&mut BytesMut::from(&buf[..NOISE_LENGTH_PREFIX_SIZE + len])) {
+            Ok(Some(msg)) => {
                 info!(parent: self.node().span(), "received a {} from {}", msg.name(), source);
                 Ok(Some(msg))
             }
+            Ok(None) => Ok(None),
             Err(e) => {
                 error!("a message from {} failed to deserialize: {}", source, e);
                 Err(io::ErrorKind::InvalidData.into())
@@ -343,12 +372,15 @@ impl Reading for TestNode {
 impl Writing for TestNode {
     type Message = ClientMessage;
 
-    fn write_message<W: io::Write>(&self, _target: SocketAddr, payload: &Self::Message, writer: &mut W) -> io::Result<()> {
-        let serialized = payload.serialize().unwrap();
-        let len = u32::to_le_bytes(serialized.len() as u32);
+    fn write_message<W: io::Write>(&self, target: SocketAddr, payload: &Self::Message, writer: &mut W) -> io::Result<()> {
+        let mut codecs = self.state.codecs.lock().unwrap();
+        let codec = codecs.get_mut(&target).ok_or(io::ErrorKind::NotConnected)?;
 
-        writer.write_all(&len)?;
-        writer.write_all(&serialized)
+        let mut encrypted = BytesMut::new();
+        codec
+            .encode(payload.clone(), &mut encrypted)
+            .map_err(|_| io::Error::from(io::ErrorKind::InvalidData))?;
+        writer.write_all(&encrypted)
     }
 }
 
@@ -359,7 +391,58 @@ impl Disconnect for TestNode {
         let mut locked_peers = self.state.peers.lock().await;
         let initial_len = locked_peers.len();
         locked_peers.retain(|peer| peer.connected_addr != disconnecting_addr);
-        assert_eq!(locked_peers.len(), initial_len - 1)
+        assert_eq!(locked_peers.len(), initial_len - 1);
+
+        self.state.codecs.lock().unwrap().remove(&disconnecting_addr);
+    }
+}
+
+/// Exposes both halves of a `Connection` as a single stream, for the Noise handshake.
+struct ConnectionStream<'a>(&'a mut Connection);
+
+impl AsyncRead for ConnectionStream<'_> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        Pin::new(self.get_mut().0.reader()).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for ConnectionStream<'_> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(self.get_mut().0.writer()).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(self.get_mut().0.writer()).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(self.get_mut().0.writer()).poll_shutdown(cx)
+    }
+}
+
+/// Encrypts the given message and writes it to the connection.
+async fn write_encrypted(connection: &mut Connection, codec: &mut ClientCodec, message: ClientMessage) -> io::Result<()> {
+    let mut encrypted = BytesMut::new();
+    codec
+        .encode(message, &mut encrypted)
+        .map_err(|_| io::Error::from(io::ErrorKind::InvalidData))?;
+    connection.writer().write_all(&encrypted).await
+}
+
+/// Reads the next encrypted message from the connection.
+async fn read_encrypted(connection: &mut Connection, codec: &mut ClientCodec) -> io::Result<ClientMessage> {
+    let mut encrypted = BytesMut::new();
+    loop {
+        let mut len = [0u8; NOISE_LENGTH_PREFIX_SIZE];
+        connection.reader().read_exact(&mut len).await?;
+        let mut buf = vec![0u8; u16::from_be_bytes(len) as usize];
+        connection.reader().read_exact(&mut buf).await?;
+
+        encrypted.extend_from_slice(&len);
+        encrypted.extend_from_slice(&buf);
+        if let Some(message) = codec.decode(&mut encrypted)? {
+            return Ok(message);
+        }
     }
 }
 